@@ -0,0 +1,24 @@
+#![no_std]
+
+extern crate galil_seiferas;
+
+use core::panic::PanicInfo;
+use core::slice;
+
+use galil_seiferas::gs_find;
+
+/// Search for a pattern, so that the search is linked in.
+#[no_mangle]
+pub unsafe extern "C" fn no_std_check_find(text: *const u8, text_len: usize,
+                                           pattern: *const u8, pattern_len: usize)
+    -> isize
+{
+    let text = slice::from_raw_parts(text, text_len);
+    let pattern = slice::from_raw_parts(pattern, pattern_len);
+    gs_find(text, pattern).map_or(-1, |i| i as isize)
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop { }
+}
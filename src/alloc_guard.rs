@@ -0,0 +1,44 @@
+//! Verifying that searches allocate nothing, with `assert_no_alloc`.
+//!
+//! Install `AllocDisabler` as the global allocator of a test binary, and
+//! `allocations` counts the heap allocations of a closure. A test that
+//! passes the searches of an application to it demonstrates the **O(1)**
+//! space claim for those searches, instead of relying on the documentation.
+//!
+//! The allocator only counts; it does not abort, so the test can report
+//! the count.
+//!
+//! ```
+//! extern crate galil_seiferas;
+//!
+//! use galil_seiferas::alloc_guard::{allocations, AllocDisabler};
+//! use galil_seiferas::{gs_find, Finder};
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: AllocDisabler = AllocDisabler;
+//!
+//! # fn main() {
+//! let text = b"abaababaabaababaababaabaababaabaab".repeat(100);
+//! let count = allocations(|| {
+//!     assert_eq!(gs_find(&text, b"babaab"), Some(4));
+//!     assert_eq!(Finder::new(b"aabaab").find_iter(&text).count(), 300);
+//! });
+//! assert_eq!(count, 0);
+//! # }
+//! ```
+
+use assert_no_alloc::{assert_no_alloc, reset_violation_count, violation_count};
+
+pub use assert_no_alloc::AllocDisabler;
+
+/// Return the number of heap allocations that `f` performs.
+///
+/// Each allocation, deallocation and reallocation counts as one, so a
+/// temporary allocation counts at least twice. The count is only correct if
+/// `AllocDisabler` is the global allocator; it is shared by all threads, so
+/// do not run other allocating tests concurrently with `f`.
+pub fn allocations<F: FnOnce()>(f: F) -> u32 {
+    reset_violation_count();
+    assert_no_alloc(f);
+    violation_count()
+}
@@ -0,0 +1,473 @@
+
+use std::borrow::Borrow;
+use std::cmp::min;
+#[cfg(feature = "alloc")]
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use haystack::{skip, sub, Haystack};
+use kmp::borders;
+use {decompose_by, decomposition_by, longest_common_prefix_from, text_has_prefix};
+use {Hrp, GS_K};
+
+/// Structural information about a pattern, see [`analyze`](fn.analyze.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PatternInfo {
+    len: usize,
+    period: usize,
+    u_len: usize,
+    hrp: Option<Hrp>,
+}
+
+impl PatternInfo {
+    /// The length of the pattern
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the pattern is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The smallest period of the pattern.
+    ///
+    /// This is the smallest *p* > 0 such that `pattern[i] == pattern[i + p]`
+    /// for all valid *i*; it is equal to the pattern length if the pattern
+    /// has no shorter period, and it is zero for the empty pattern.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Return `true` if the pattern is periodic, which means that its
+    /// smallest period fits at least twice in the pattern.
+    pub fn is_periodic(&self) -> bool {
+        self.len > 0 && self.period * 2 <= self.len
+    }
+
+    /// The length of *u* in the decomposition of the pattern into u v,
+    /// where u is “short” and v is k-simple.
+    pub fn u_len(&self) -> usize {
+        self.u_len
+    }
+
+    /// The length of *v* in the decomposition of the pattern into u v,
+    /// where u is “short” and v is k-simple.
+    pub fn v_len(&self) -> usize {
+        self.len - self.u_len
+    }
+
+    /// The (only) k-HRP of *v*, if it exists.
+    pub fn hrp(&self) -> Option<Hrp> {
+        self.hrp
+    }
+}
+
+/// Analyze the structure of `pattern`: its period and its decomposition.
+///
+/// This computes in **O(m)** time and **O(1)** space.
+pub fn analyze<T: Eq>(pattern: &[T]) -> PatternInfo {
+    analyze_by(pattern, T::eq)
+}
+
+/// Analyze the structure of `pattern`: its period and its decomposition.
+///
+/// Use `equal` as the equality comparison function.
+pub fn analyze_by<T, F>(pattern: &[T], equal: F) -> PatternInfo
+    where F: Fn(&T, &T) -> bool,
+{
+    let (u, _, hrp) = decompose_by(pattern, &equal);
+    PatternInfo {
+        len: pattern.len(),
+        period: smallest_period_by(pattern, &equal),
+        u_len: u.len(),
+        hrp,
+    }
+}
+
+/// Analyze `pattern[range]`, where `info` is the analysis of `pattern`.
+///
+/// The decomposition u v of the pattern is reused where it stays valid: for
+/// a suffix that contains v, with the rest of u, and for a prefix that
+/// contains u, where the prefix of v has the same HRP (cut to the prefix)
+/// and u is still short; otherwise the slice is analyzed from scratch. The
+/// period of the slice is always computed.
+pub(crate) fn analyze_slice<T: Eq>(pattern: &[T], info: &PatternInfo, range: Range<usize>)
+    -> PatternInfo
+{
+    let slice = &pattern[range.clone()];
+    let u_len = info.u_len;
+    let period = smallest_period_by(slice, &T::eq);
+    if range.end == pattern.len() && range.start <= u_len {
+        // v is unchanged, and u is shorter
+        let u_len = u_len - range.start;
+        return PatternInfo { len: slice.len(), period, u_len, hrp: info.hrp };
+    }
+    if range.start == 0 && range.end >= u_len {
+        // v has at most one k-HRP, so its prefix has at most the same one
+        let v_len = range.end - u_len;
+        let hrp = info.hrp.and_then(|hrp| {
+            let len = min(hrp.len, v_len);
+            if len >= GS_K * hrp.period { Some(Hrp { period: hrp.period, len }) } else { None }
+        });
+        if u_len == 0 || u_len <= 2 * smallest_period_by(&slice[u_len..], &T::eq) {
+            return PatternInfo { len: slice.len(), period, u_len, hrp };
+        }
+    }
+    let (u, _, hrp) = decompose_by(slice, &T::eq);
+    PatternInfo { len: slice.len(), period, u_len: u.len(), hrp }
+}
+
+/// Fill `out[i]` with the smallest period of the prefix `x[..i + 1]`, for
+/// each prefix of `x`.
+///
+/// The periods are computed in **O(m)** time from the borders of the
+/// prefixes, like the Knuth-Morris-Pratt table, in the first m elements of
+/// `out`; the rest of `out` is not modified.
+///
+/// **Panics** if `out` is shorter than `x`.
+///
+/// ```
+/// use galil_seiferas::prefix_periods_array;
+///
+/// let mut periods = [0; 6];
+/// prefix_periods_array(b"abaaba", &mut periods);
+/// assert_eq!(periods, [1, 2, 2, 3, 3, 3]);
+/// ```
+pub fn prefix_periods_array<T: Eq>(x: &[T], out: &mut [usize]) {
+    let m = x.len();
+    assert!(out.len() >= m, "prefix_periods_array: out must be at least as long as x");
+    let out = &mut out[..m];
+    borders(x, out);
+    for (i, period) in out.iter_mut().enumerate() {
+        *period = i + 1 - *period;
+    }
+}
+
+/// Fill `out[p]` with the reach of *p*: the length of the longest prefix of
+/// `x` that has period *p*, for each *p* in 0..m.
+///
+/// This is how far each candidate period extends in [GS] Preprocessing a
+/// Pattern, which computes it for some of the candidates, and it is *p* plus
+/// the length of the longest common prefix of `x` and `x[p..]`; the reach of
+/// 0 is m. The reaches are computed in **O(m)** time, with the Z algorithm,
+/// in the first m elements of `out`; the rest of `out` is not modified.
+///
+/// **Panics** if `out` is shorter than `x`.
+///
+/// ```
+/// use galil_seiferas::reach_array;
+///
+/// let mut reach = [0; 7];
+/// reach_array(b"abababb", &mut reach);
+/// assert_eq!(reach, [7, 1, 6, 3, 6, 5, 6]);
+/// ```
+pub fn reach_array<T: Eq>(x: &[T], out: &mut [usize]) {
+    let m = x.len();
+    assert!(out.len() >= m, "reach_array: out must be at least as long as x");
+    if m == 0 {
+        return;
+    }
+    out[0] = m;
+    // x[l..r] is a prefix of x, with the greatest r so far
+    let (mut l, mut r) = (0, 0);
+    for p in 1..m {
+        let mut len = if p < r { min(r - p, out[p - l] - (p - l)) } else { 0 };
+        while p + len < m && x[len] == x[p + len] {
+            len += 1;
+        }
+        if p + len > r {
+            l = p;
+            r = p + len;
+        }
+        out[p] = p + len;
+    }
+}
+
+/// Return an iterator of all the periods of `x`, in increasing order.
+///
+/// The periods are the *p* in 1..=m such that `x[i] == x[i + p]` for all
+/// valid *i*, so the length of `x` is the last period. They are computed
+/// from the borders of `x`, in **O(m)** time and space in total.
+///
+/// ```
+/// use galil_seiferas::periods;
+///
+/// assert_eq!(periods(b"abaababaab").collect::<Vec<_>>(), [5, 8, 10]);
+/// assert_eq!(periods(b"aaa").collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn periods<T: Eq>(x: &[T]) -> Periods {
+    let m = x.len();
+    let mut table = ::alloc::vec![0; m];
+    borders(x, &mut table);
+    Periods { border: table.last().cloned(), borders: table }
+}
+
+/// An iterator of the periods of a word, created by `periods`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct Periods {
+    /// The borders of each prefix
+    borders: Vec<usize>,
+    /// The border of the next period, if any
+    border: Option<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for Periods {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let border = self.border?;
+        // The next longest border is the longest border of this border
+        self.border = if border == 0 { None } else { Some(self.borders[border - 1]) };
+        Some(self.borders.len() - border)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for Periods { }
+
+/// Return the smallest approximate period of `x`: the smallest *p* > 0 such
+/// that `x[i] != x[i + p]` for at most `max_mismatches` positions *i*.
+///
+/// With zero mismatches, this is the smallest period of `x`. It is equal to
+/// the length of `x` if there is no shorter approximate period, and it is
+/// zero if `x` is empty.
+///
+/// Each candidate period is checked until the mismatch budget runs out, so
+/// this computes in **O(m p)** time and **O(1)** space.
+///
+/// ```
+/// use galil_seiferas::approximate_period;
+///
+/// let samples = [1, 5, 9, 1, 5, 9, 1, 4, 9, 1, 5, 9, 1];
+/// assert_eq!(approximate_period(&samples, 0), 9);
+/// assert_eq!(approximate_period(&samples, 2), 3);
+/// ```
+pub fn approximate_period<T: Eq>(x: &[T], max_mismatches: usize) -> usize {
+    approximate_period_by(x, max_mismatches, T::eq)
+}
+
+/// Like `approximate_period`, using `equal` as the equality comparison
+/// function.
+pub fn approximate_period_by<T, F>(x: &[T], max_mismatches: usize, equal: F) -> usize
+    where F: Fn(&T, &T) -> bool,
+{
+    (1..x.len()).find(|&p| {
+        let mut budget = max_mismatches;
+        x.iter().zip(&x[p..]).all(|(a, b)| {
+            if equal(a, b) {
+                true
+            } else if budget > 0 {
+                budget -= 1;
+                true
+            } else {
+                false
+            }
+        })
+    }).unwrap_or(x.len())
+}
+
+/// Compute the smallest period of `x`, or zero if `x` is empty.
+pub(crate) fn smallest_period_by<T, F>(x: &[T], equal: &F) -> usize
+    where F: Fn(&T, &T) -> bool,
+{
+    // The period is p where x[p..] is the longest proper suffix of x
+    // that is also a prefix of x (the longest border).
+    if x.is_empty() {
+        return 0;
+    }
+    x.len() - longest_suffix_prefix_by(&x[1..], x, equal)
+}
+
+/// Return the length of the longest suffix of `text` that is a prefix of
+/// `pattern`.
+///
+/// This is a Galil-Seiferas search where matches are allowed to run off the
+/// end of the text; the shift rules in `search_simple` hold just the same for
+/// such partial matches, since they only depend on the matched prefix.
+///
+/// Partial matches too short to cover u are found by repeating the search
+/// with the pattern u; |u| <= 2/3 |pattern| so this terminates quickly.
+pub(crate) fn longest_suffix_prefix_by<T, H, P, F>(text: H, pattern: P, equal: &F) -> usize
+    where H: Haystack + Copy,
+          H::Item: Borrow<T>,
+          P: Haystack + Copy,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    let mut text = skip(text, text.len() - min(text.len(), pattern.len()));
+    let mut pattern = skip(pattern, 0);
+    // invariant: text.len() <= pattern.len()
+    while !pattern.is_empty() {
+        let (u_len, hrp1) = decomposition_by(pattern, equal);
+        let u = pattern.narrow(0..u_len);
+        let v = skip(pattern, u_len);
+
+        // Candidates where the text suffix covers all of u and part of v
+        if text.len() > u_len {
+            let t = skip(text, u_len);
+            let (scope_l, scope_r) = match hrp1 {
+                Some(hrp1) => (hrp1.period * 2, hrp1.len),
+                None => (0, 0),
+            };
+            let has_scope = scope_l < scope_r;
+            let (mut pos, mut j) = (0, 0);
+            while pos < t.len() {
+                let rest = t.len() - pos;
+                j = longest_common_prefix_from(j, skip(t, pos), sub(v, 0..rest), equal);
+                if j == rest && text_has_prefix(skip(text, pos), u, equal) {
+                    return text.len() - pos;
+                }
+                if has_scope && j >= scope_l && j <= scope_r {
+                    pos += scope_l / 2;
+                    j -= scope_l / 2;
+                } else {
+                    pos += j / GS_K + 1;
+                    j = 0;
+                }
+            }
+        }
+
+        // Remaining candidates are the suffixes no longer than u
+        text = text.narrow(text.len() - min(text.len(), u_len)..text.len());
+        pattern = u;
+    }
+    0
+}
+
+#[cfg(test)]
+fn brute_force_period(x: &[u8]) -> usize {
+    (1..x.len() + 1).find(|&p| x[p..] == x[..x.len() - p]).unwrap_or(0)
+}
+
+#[test]
+fn test_smallest_period() {
+    assert_eq!(smallest_period_by(b"", &u8::eq), 0);
+    assert_eq!(smallest_period_by(b"a", &u8::eq), 1);
+    assert_eq!(smallest_period_by(b"aaaa", &u8::eq), 1);
+    assert_eq!(smallest_period_by(b"abaab", &u8::eq), 3);
+    assert_eq!(smallest_period_by(b"abcabcab", &u8::eq), 3);
+    assert_eq!(smallest_period_by(b"abcd", &u8::eq), 4);
+
+    // every binary word up to length 14
+    for len in 0..15 {
+        for bits in 0..1u32 << len {
+            let x = (0..len).map(|i| b'a' + (bits >> i & 1) as u8).collect::<Vec<_>>();
+            assert_eq!(smallest_period_by(&x, &u8::eq), brute_force_period(&x),
+                       "period of {}", ::Bytestring(&x));
+        }
+    }
+}
+
+#[test]
+fn test_prefix_periods_array() {
+    let mut out = [usize::MAX; 16];
+    prefix_periods_array(b"", &mut out);
+    assert_eq!(out[0], usize::MAX);
+    let x = b"aabaabaaabaabaa";
+    prefix_periods_array(x, &mut out);
+    for i in 0..x.len() {
+        assert_eq!(out[i], brute_force_period(&x[..i + 1]), "{}", i);
+    }
+    assert_eq!(out[15], usize::MAX);
+}
+
+#[test]
+#[should_panic]
+fn test_prefix_periods_array_short() {
+    prefix_periods_array(b"abc", &mut [0; 2]);
+}
+
+#[test]
+fn test_reach_array() {
+    // Every ternary string of length < 9
+    for len in 0..9 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| n / 3u32.pow(i) % 3).collect::<Vec<_>>();
+            let mut reach = vec![!0; x.len() + 1];
+            reach_array(&x, &mut reach);
+            for p in 0..x.len() {
+                let lcp = x.iter().zip(&x[p..]).take_while(|&(a, b)| a == b).count();
+                assert_eq!(reach[p], p + lcp, "{:?}", x);
+            }
+            assert_eq!(reach[x.len()], !0);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_periods() {
+    assert_eq!(periods::<u8>(b"").count(), 0);
+    assert_eq!(periods(b"a").collect::<Vec<_>>(), [1]);
+    // Every binary string of length < 13
+    for len in 0..13 {
+        for bits in 0..1u32 << len {
+            let x = (0..len).map(|i| (bits >> i) & 1).collect::<Vec<_>>();
+            let expected = (1..len + 1)
+                .filter(|&p| (p..len).all(|i| x[i] == x[i - p]))
+                .collect::<Vec<_>>();
+            assert_eq!(periods(&x).collect::<Vec<_>>(), expected, "{:?}", x);
+        }
+    }
+}
+
+#[test]
+fn test_approximate_period() {
+    assert_eq!(approximate_period::<u8>(b"", 3), 0);
+    assert_eq!(approximate_period(b"a", 0), 1);
+    assert_eq!(approximate_period(b"abcd", 3), 1);
+    assert_eq!(approximate_period(b"abcabcabd", 0), 9);
+    assert_eq!(approximate_period(b"abcabcabd", 1), 3);
+    assert_eq!(approximate_period(b"abcabdabc", 1), 6);
+    assert_eq!(approximate_period(b"abcabdabc", 2), 3);
+    assert_eq!(approximate_period_by(b"abcABCabc", 0, |a, b| a.eq_ignore_ascii_case(b)), 3);
+
+    // exact periods
+    for len in 0..11 {
+        for bits in 0..1u32 << len {
+            let x = (0..len).map(|i| b'a' + (bits >> i & 1) as u8).collect::<Vec<_>>();
+            assert_eq!(approximate_period(&x, 0), brute_force_period(&x));
+        }
+    }
+}
+
+#[test]
+fn test_smallest_period_periodic() {
+    let s = "aaab".repeat(4) + "aaa";
+    assert_eq!(smallest_period_by(s.as_bytes(), &u8::eq), 4);
+    let s = "abaababa".repeat(7) + "abaab";
+    assert_eq!(smallest_period_by(s.as_bytes(), &u8::eq), 8);
+}
+
+#[test]
+fn test_analyze() {
+    let info = analyze(b"banana");
+    assert_eq!(info.len(), 6);
+    assert_eq!(info.period(), 6);
+    assert!(!info.is_periodic());
+    assert_eq!(info.hrp(), None);
+
+    let s = "aaab".repeat(4) + "bbbb";
+    let info = analyze(s.as_bytes());
+    assert_eq!(info.period(), 20);
+    assert_eq!(info.u_len() + info.v_len(), 20);
+    assert_eq!(info.hrp(), Some(Hrp { period: 4, len: 15 }));
+
+    let s = "ab".repeat(10);
+    let info = analyze(s.as_bytes());
+    assert_eq!(info.period(), 2);
+    assert!(info.is_periodic());
+    assert_eq!(info.u_len(), 0);
+
+    let info = analyze::<u8>(&[]);
+    assert_eq!(info.period(), 0);
+    assert!(!info.is_periodic());
+}
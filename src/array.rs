@@ -0,0 +1,100 @@
+
+use std::convert::TryFrom;
+
+use analysis::{analyze, PatternInfo};
+use engine::SearchEngine;
+use Pattern;
+
+/// The longest pattern that is searched by comparing whole windows
+const SMALL: usize = 4;
+
+/// A finder for a pattern with a length that is known at compile time, like
+/// a magic number of a file format or a protocol.
+///
+/// A pattern of at most four elements is compared with each window of the
+/// text as an array, which the compiler unrolls, and without the
+/// preprocessing of the Galil-Seiferas search; this is still at most **4 n**
+/// comparisons. A longer pattern is preprocessed when the finder is created
+/// and searched like with `Finder`.
+///
+/// ```
+/// use galil_seiferas::{ArrayFinder, SearchEngine};
+///
+/// let elf = ArrayFinder::from_array(*b"\x7fELF");
+/// assert_eq!(elf.find(b"\0\0\x7fELF\x02\x01"), Some(2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ArrayFinder<T, const N: usize> {
+    pattern: [T; N],
+    /// The preprocessing, for a pattern that is not small
+    info: Option<PatternInfo>,
+}
+
+impl<T: Eq, const N: usize> ArrayFinder<T, N> {
+    /// Create a finder for `pattern`.
+    pub fn from_array(pattern: [T; N]) -> Self {
+        let info = if N <= SMALL { None } else { Some(analyze(&pattern)) };
+        ArrayFinder { pattern, info }
+    }
+
+    /// The pattern
+    pub fn pattern(&self) -> &[T; N] {
+        &self.pattern
+    }
+}
+
+impl<T: Eq, const N: usize> SearchEngine<T> for ArrayFinder<T, N> {
+    fn pattern(&self) -> &[T] {
+        &self.pattern
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        let rest = text.get(start..)?;
+        match self.info {
+            None => {
+                let last = rest.len().checked_sub(N)?;
+                (0..last + 1).find(|&i| {
+                    // Compare arrays, so that the comparison is unrolled
+                    let window = <&[T; N]>::try_from(&rest[i..i + N]).unwrap();
+                    *window == self.pattern
+                }).map(|i| start + i)
+            }
+            Some(info) => {
+                let (u, v) = self.pattern.split_at(info.u_len());
+                let pattern = Pattern { u, v, hrp1: info.hrp(), equal: T::eq };
+                pattern.find_in(rest).map(|i| start + i)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_array_finder() {
+    use gs_find;
+
+    fn check<const N: usize>(pattern: [u8; N], text: &[u8]) {
+        let finder = ArrayFinder::from_array(pattern);
+        assert_eq!(finder.info.is_none(), N <= SMALL);
+        for start in 0..text.len() + 2 {
+            let expected = text.get(start..).and_then(|rest| gs_find(rest, pattern))
+                                            .map(|i| start + i);
+            assert_eq!(finder.find_at(text, start), expected);
+        }
+    }
+
+    // Every binary text of length < 11
+    for len in 0..11 {
+        for bits in 0..1u32 << len {
+            let text = (0..len).map(|i| b'a' + ((bits >> i) & 1) as u8).collect::<Vec<_>>();
+            check([], &text);
+            check(*b"a", &text);
+            check(*b"ab", &text);
+            check(*b"aba", &text);
+            check(*b"abba", &text);
+            check(*b"aabaa", &text);
+            check(*b"aaaaaaa", &text);
+        }
+    }
+    let finder = ArrayFinder::from_array(*b"%PDF-");
+    assert_eq!(finder.find_iter(b"%PDF-1.7 %PDF-").collect::<Vec<_>>(), [0, 9]);
+}
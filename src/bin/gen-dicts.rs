@@ -0,0 +1,128 @@
+//! Generate fuzzing dictionaries of repetitive words.
+//!
+//! ```text
+//! cargo run --features tools --bin gen-dicts -- [--fibwords N] [--lsys N] [--repeats N] [--out DIR]
+//! ```
+//!
+//! This writes two dictionaries in the libFuzzer format, one word per line:
+//!
+//! - `dict-fibwords`: the Fibonacci words *S*₀ to *S*ₙ₋₁, with *S*₀ = a,
+//!   *S*₁ = ab and *S*ₙ = *S*ₙ₋₁ *S*ₙ₋₂ (default *N* = 10).
+//! - `dict-lsys`: the generations 0 to *N* − 1 of an L-system that rewrites
+//!   0 → 100 and 1 → 11 (default *N* = 6), and the concatenations of one or
+//!   two of them, each repeated 1 to `--repeats` times (default 5).
+//!
+//! The output is the same for the same arguments. Use a dictionary with
+//! `-dict=DIR/dict-fibwords`, for example in the scripts in `fuzz/`.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// The options, with their defaults
+struct Options {
+    fibwords: usize,
+    lsys: usize,
+    repeats: usize,
+    out: PathBuf,
+}
+
+const USAGE: &str = "usage: gen-dicts [--fibwords N] [--lsys N] [--repeats N] [--out DIR]";
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options { fibwords: 10, lsys: 6, repeats: 5, out: PathBuf::from(".") };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            process::exit(0);
+        }
+        let value = args.next().ok_or_else(|| format!("{}: missing value", arg))?;
+        let number = || value.parse::<usize>().map_err(|e| format!("{}: {}", arg, e));
+        match &arg[..] {
+            "--fibwords" => options.fibwords = number()?,
+            "--lsys" => options.lsys = number()?,
+            "--repeats" => options.repeats = number()?,
+            "--out" => options.out = PathBuf::from(&value),
+            _ => return Err(format!("unknown option {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// The Fibonacci words S0 to Sn-1
+fn fibonacci_words(n: usize) -> Vec<String> {
+    let mut words = vec![String::from("a"), String::from("ab")];
+    while words.len() < n {
+        let next = words[words.len() - 1].clone() + &words[words.len() - 2];
+        words.push(next);
+    }
+    words.truncate(n);
+    words
+}
+
+/// Starting state of the L-system
+const LSTART: &str = "0";
+
+/// Rewrite rules of the L-system
+const LRULES: &[(char, &str)] = &[
+    ('0', "100"),
+    ('1', "11"),
+];
+
+/// The generations 0 to n-1 of the L-system
+fn lsys_generations(n: usize) -> Vec<String> {
+    let mut generations = Vec::new();
+    let mut word = String::from(LSTART);
+    for _ in 0..n {
+        let next = word.chars().map(|c| {
+            LRULES.iter().find(|rule| rule.0 == c).map_or(c.to_string(), |rule| rule.1.to_string())
+        }).collect();
+        generations.push(word);
+        word = next;
+    }
+    generations
+}
+
+/// Write each word of `words` on its own line of the file `name` in `dir`
+fn write_dict<I>(dir: &Path, name: &str, words: I) -> io::Result<()>
+    where I: IntoIterator<Item = String>,
+{
+    let mut file = BufWriter::new(File::create(dir.join(name))?);
+    for word in words {
+        writeln!(file, "\"{}\"", word)?;
+    }
+    file.flush()
+}
+
+fn run(options: &Options) -> io::Result<()> {
+    write_dict(&options.out, "dict-fibwords", fibonacci_words(options.fibwords))?;
+
+    let generations = lsys_generations(options.lsys);
+    let mut lsys = Vec::new();
+    for first in &generations {
+        for r1 in 1..options.repeats + 1 {
+            let word = first.repeat(r1);
+            lsys.push(word.clone());
+            for second in &generations {
+                for r2 in 1..options.repeats + 1 {
+                    lsys.push(word.clone() + &second.repeat(r2));
+                }
+            }
+        }
+    }
+    write_dict(&options.out, "dict-lsys", lsys)
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|error| {
+        eprintln!("gen-dicts: {}\n{}", error, USAGE);
+        process::exit(2);
+    });
+    if let Err(error) = run(&options) {
+        eprintln!("gen-dicts: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,135 @@
+
+use std::iter::FusedIterator;
+
+use bytes::Bytes;
+
+use analysis::PatternInfo;
+use finder::Finder;
+use SearchState;
+
+/// Find the next non-overlapping match in `text`, resuming the search from
+/// `state`; return the range of the match.
+fn next_match(finder: &Finder<u8>, info: &PatternInfo, text: &[u8], state: &mut SearchState)
+    -> Option<(usize, usize)>
+{
+    let start = finder.as_pattern(info).find_from(text, state)?;
+    let end = start + info.len();
+    if state.pos < end {
+        state.restart(end);
+    }
+    Some((start, end))
+}
+
+/// An iterator of the non-overlapping matches in a `Bytes` text, as slices
+/// of the text, created by `Finder::find_iter_bytes`.
+///
+/// Like `Matches`, the search is resumed from where the last match was
+/// found.
+#[derive(Clone, Debug)]
+pub struct BytesMatches<'f, 'a: 'f> {
+    finder: &'f Finder<'a, u8>,
+    info: PatternInfo,
+    text: Bytes,
+    state: SearchState,
+}
+
+impl<'f, 'a> BytesMatches<'f, 'a> {
+    pub(crate) fn new(finder: &'f Finder<'a, u8>, text: Bytes) -> Self {
+        BytesMatches { finder, info: finder.info(), text, state: SearchState::default() }
+    }
+}
+
+impl<'f, 'a> Iterator for BytesMatches<'f, 'a> {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        let (start, end) = next_match(self.finder, &self.info, &self.text, &mut self.state)?;
+        Some(self.text.slice(start..end))
+    }
+}
+
+impl<'f, 'a> FusedIterator for BytesMatches<'f, 'a> { }
+
+/// An iterator of the parts of a `Bytes` text between the non-overlapping
+/// matches, as slices of the text, created by `Finder::split_bytes`.
+#[derive(Clone, Debug)]
+pub struct BytesSplit<'f, 'a: 'f> {
+    finder: &'f Finder<'a, u8>,
+    info: PatternInfo,
+    text: Bytes,
+    state: SearchState,
+    /// The start of the next part
+    start: usize,
+    done: bool,
+}
+
+impl<'f, 'a> BytesSplit<'f, 'a> {
+    pub(crate) fn new(finder: &'f Finder<'a, u8>, text: Bytes) -> Self {
+        BytesSplit {
+            finder,
+            info: finder.info(),
+            text,
+            state: SearchState::default(),
+            start: 0,
+            done: false,
+        }
+    }
+}
+
+impl<'f, 'a> Iterator for BytesSplit<'f, 'a> {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.done {
+            return None;
+        }
+        match next_match(self.finder, &self.info, &self.text, &mut self.state) {
+            Some((start, end)) => {
+                let part = self.text.slice(self.start..start);
+                self.start = end;
+                Some(part)
+            }
+            None => {
+                self.done = true;
+                Some(self.text.slice(self.start..))
+            }
+        }
+    }
+}
+
+impl<'f, 'a> FusedIterator for BytesSplit<'f, 'a> { }
+
+#[test]
+fn test_bytes() {
+    use bytes::BytesMut;
+
+    let text = Bytes::from_static(b"a, b, , c");
+    let finder = Finder::new(b", ");
+    let parts = finder.split_bytes(text.clone()).collect::<Vec<_>>();
+    assert_eq!(parts, [&b"a"[..], b"b", b"", b"c"]);
+    // The parts share the buffer of the text
+    assert_eq!(parts[3].as_ptr(), text[8..].as_ptr());
+    assert_eq!(finder.find_iter_bytes(text.clone()).count(), 3);
+    assert_eq!(finder.find_bytes(text.clone()).unwrap().as_ptr(), text[1..].as_ptr());
+    assert_eq!(finder.find_bytes(&b"abc"[..]), None);
+
+    let mut buffer = BytesMut::new();
+    buffer.extend_from_slice(b"xaaax");
+    let finder = Finder::new(b"aa");
+    assert_eq!(finder.find_iter_bytes(buffer.clone()).collect::<Vec<_>>(), [&b"aa"[..]]);
+    assert_eq!(finder.split_bytes(buffer).collect::<Vec<_>>(), [&b"x"[..], b"ax"]);
+
+    let finder = Finder::new(b"");
+    assert_eq!(finder.find_iter_bytes(&b"ab"[..]).count(), 3);
+    assert_eq!(finder.split_bytes(&b"ab"[..]).collect::<Vec<_>>(), [&b""[..], b"a", b"b", b""]);
+    assert_eq!(finder.split_bytes(Bytes::new()).collect::<Vec<_>>(), [&b""[..], b""]);
+
+    // The same matches as find_iter
+    let finder = Finder::new(b"aba");
+    let text = Bytes::from_static(b"abababa.aba..ab");
+    let starts = finder.find_iter(&text).map(|m| m.start()).collect::<Vec<_>>();
+    let found = finder.find_iter_bytes(text.clone())
+                      .map(|m| m.as_ptr() as usize - text.as_ptr() as usize)
+                      .collect::<Vec<_>>();
+    assert_eq!(found, starts);
+}
@@ -0,0 +1,177 @@
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use alloc::vec::Vec;
+
+use finder::Finder;
+
+/// A thread-safe cache of preprocessed byte string patterns, which evicts
+/// the least recently used finder when it is full.
+///
+/// This is for call sites that search for the same patterns again and again,
+/// for example in request handlers, where keeping the `Finder` around is not
+/// practical. The lookup compares the pattern with each cached pattern, so
+/// the cache should be small.
+///
+/// The process has a global cache, see `global_finder_cache`, which is
+/// empty with capacity zero until it is configured.
+///
+/// ```
+/// use galil_seiferas::FinderCache;
+///
+/// let cache = FinderCache::new(16);
+/// for line in &["GET /index.html", "GET /favicon.ico", "POST /form"] {
+///     if cache.find(line.as_bytes(), b"GET ").is_some() {
+///         // ...
+///     }
+/// }
+/// assert_eq!(cache.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct FinderCache {
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug)]
+struct Entries {
+    capacity: usize,
+    /// The finders in the order of use, most recently used last
+    finders: Vec<Arc<Finder<'static, u8>>>,
+}
+
+impl FinderCache {
+    /// Create an empty cache with room for `capacity` finders.
+    pub const fn new(capacity: usize) -> Self {
+        FinderCache {
+            entries: Mutex::new(Entries { capacity, finders: Vec::new() }),
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Entries> {
+        // The entries are always consistent, even if a thread panicked
+        self.entries.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Return the finder for `pattern`, from the cache if it is there;
+    /// otherwise preprocess the pattern and add its finder to the cache.
+    pub fn finder(&self, pattern: &[u8]) -> Arc<Finder<'static, u8>> {
+        if let Some(finder) = self.entries().lookup(pattern) {
+            return finder;
+        }
+        // Preprocess without holding the lock
+        let finder = Arc::new(Finder::from_vec(pattern.to_vec()));
+        let mut entries = self.entries();
+        // Another thread may have added it meanwhile
+        if let Some(finder) = entries.lookup(pattern) {
+            return finder;
+        }
+        if entries.capacity > 0 {
+            if entries.finders.len() >= entries.capacity {
+                entries.finders.remove(0);
+            }
+            entries.finders.push(finder.clone());
+        }
+        finder
+    }
+
+    /// Return the start of the first match of `pattern` in `text`, if any,
+    /// using the cached finder for `pattern`.
+    pub fn find(&self, text: &[u8], pattern: &[u8]) -> Option<usize> {
+        self.finder(pattern).find(text).map(|m| m.start())
+    }
+
+    /// The number of cached finders
+    pub fn len(&self) -> usize {
+        self.entries().finders.len()
+    }
+
+    /// Return `true` if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of cached finders
+    pub fn capacity(&self) -> usize {
+        self.entries().capacity
+    }
+
+    /// Set the maximum number of cached finders; the least recently used
+    /// finders are evicted if there are more. Zero disables the cache.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut entries = self.entries();
+        let len = entries.finders.len();
+        if len > capacity {
+            entries.finders.drain(..len - capacity);
+        }
+        entries.capacity = capacity;
+    }
+
+    /// Remove all the cached finders.
+    pub fn clear(&self) {
+        self.entries().finders.clear();
+    }
+}
+
+impl Entries {
+    /// Return the finder for `pattern`, if it is cached, and mark it as the
+    /// most recently used.
+    fn lookup(&mut self, pattern: &[u8]) -> Option<Arc<Finder<'static, u8>>> {
+        let index = self.finders.iter().rposition(|f| f.pattern() == pattern)?;
+        let finder = self.finders.remove(index);
+        self.finders.push(finder.clone());
+        Some(finder)
+    }
+}
+
+static GLOBAL: FinderCache = FinderCache::new(0);
+
+/// The global finder cache, which is used by `gs_find_cached`.
+///
+/// It is disabled (capacity zero) until it is configured with
+/// `FinderCache::set_capacity`.
+pub fn global_finder_cache() -> &'static FinderCache {
+    &GLOBAL
+}
+
+/// This is the Galil-Seiferas string matching algorithm, for byte strings,
+/// reusing the preprocessing of `pattern` from the global finder cache.
+///
+/// If a match exists where `pattern` is a substring of `text`, return the
+/// offset to the start of the match inside `Some(_)`. If not, return `None`.
+///
+/// ```
+/// use galil_seiferas::{global_finder_cache, gs_find_cached};
+///
+/// global_finder_cache().set_capacity(64);
+/// assert_eq!(gs_find_cached(b"bananas", b"nana"), Some(2));
+/// assert_eq!(gs_find_cached(b"nananas", b"nana"), Some(0));
+/// ```
+pub fn gs_find_cached(text: &[u8], pattern: &[u8]) -> Option<usize> {
+    GLOBAL.find(text, pattern)
+}
+
+#[test]
+fn test_finder_cache() {
+    let cache = FinderCache::new(2);
+    assert_eq!(cache.find(b"abcabc", b"ca"), Some(2));
+    assert_eq!(cache.find(b"abcabc", b"bc"), Some(1));
+    let ca = cache.finder(b"ca");
+    assert_eq!(cache.len(), 2);
+    assert!(Arc::ptr_eq(&ca, &cache.finder(b"ca")));
+
+    // bc is the least recently used
+    assert_eq!(cache.find(b"abcabc", b"x"), None);
+    assert_eq!(cache.len(), 2);
+    assert!(Arc::ptr_eq(&ca, &cache.finder(b"ca")));
+    let bc = cache.finder(b"bc");
+    assert!(Arc::ptr_eq(&bc, &cache.finder(b"bc")));
+
+    cache.set_capacity(1);
+    assert_eq!(cache.len(), 1);
+    assert!(Arc::ptr_eq(&bc, &cache.finder(b"bc")));
+
+    cache.set_capacity(0);
+    assert!(cache.is_empty());
+    assert_eq!(cache.find(b"abcabc", b"ca"), Some(2));
+    assert!(cache.is_empty());
+}
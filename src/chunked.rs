@@ -0,0 +1,126 @@
+use std::cmp::{max, min};
+use std::ops::Range;
+
+use finder::Match;
+
+/// The offset bookkeeping of a search of a stream in chunks, which
+/// translates the positions in each searched window to offsets in the
+/// stream.
+///
+/// Each window is the carry, the end of the previous window, followed by the
+/// next chunk of the stream. The carry is the last m − 1 elements, so that
+/// the matches that span two chunks are found, and none is found twice.
+/// Search each window from `search_start`, `record` each match, and then
+/// `advance` to get the length of the carry.
+///
+/// The offsets are `u64`, so a stream of any length can be searched, also on
+/// 32-bit targets.
+///
+/// ```
+/// use galil_seiferas::{Finder, OffsetTracker};
+///
+/// let finder = Finder::new(b"abcab");
+/// let mut tracker = OffsetTracker::new(finder.pattern().len());
+/// let mut window = Vec::new();
+/// let mut found = Vec::new();
+/// for chunk in &[&b"xxabca"[..], b"bcabx", b"abcabcab"] {
+///     window.extend_from_slice(chunk);
+///     let mut matches = finder.find_iter(&window);
+///     matches.advance_to(tracker.search_start());
+///     for m in matches {
+///         found.push(tracker.record(m));
+///     }
+///     let carry = tracker.advance(window.len());
+///     window.drain(..window.len() - carry);
+/// }
+/// assert_eq!(found, [2..7, 11..16]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OffsetTracker {
+    pattern_len: usize,
+    /// The stream offset of the window
+    base: u64,
+    /// The stream offset where the search of the next window starts: the
+    /// end of the last match, so that the matches do not overlap
+    next: u64,
+}
+
+impl OffsetTracker {
+    /// Create a tracker for a pattern of length `pattern_len`, at the start of
+    /// the stream.
+    pub fn new(pattern_len: usize) -> Self {
+        OffsetTracker { pattern_len, base: 0, next: 0 }
+    }
+
+    /// The stream offset of the start of the current window
+    pub fn window_offset(&self) -> u64 {
+        self.base
+    }
+
+    /// The position in the current window where its search starts: it is
+    /// after the matches that are already recorded, for a search of the
+    /// non-overlapping matches like `Finder::find_iter`.
+    ///
+    /// For all the matches, including the overlapping ones, search each
+    /// whole window instead; for a nonempty pattern, no match is in two
+    /// windows.
+    pub fn search_start(&self) -> usize {
+        self.next.saturating_sub(self.base) as usize
+    }
+
+    /// Return the stream offset of the position `pos` in the current window.
+    pub fn absolute(&self, pos: usize) -> u64 {
+        self.base + pos as u64
+    }
+
+    /// Record the match `m` in the current window, and return its range in
+    /// the stream.
+    pub fn record(&mut self, m: Match) -> Range<u64> {
+        let range = self.absolute(m.start())..self.absolute(m.end());
+        // The next empty match is at the next position
+        let end = if m.is_empty() { range.end + 1 } else { range.end };
+        self.next = max(self.next, end);
+        range
+    }
+
+    /// The current window of length `window_len` is searched to its end;
+    /// return the length of the carry, the end of the window that starts
+    /// the next window, and move to the next window.
+    pub fn advance(&mut self, window_len: usize) -> usize {
+        let carry = min(self.pattern_len.saturating_sub(1), window_len);
+        self.base += (window_len - carry) as u64;
+        carry
+    }
+}
+
+#[test]
+fn test_offset_tracker() {
+    use finder::Finder;
+
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbbaaabaaabaaabaabbbb";
+    // the last pattern has a nonempty u
+    for pattern in &[&b"abaab"[..], b"aab", b"aaa", b"baabaabaab", b"", b"aaabaaabaaabaabbbb"] {
+        let finder = Finder::new(pattern);
+        let expected = finder.find_iter(text).map(|m| m.start() as u64..m.end() as u64)
+                                             .collect::<Vec<_>>();
+        for &chunk_len in &[1, 2, 3, 7, text.len()] {
+            let mut tracker = OffsetTracker::new(pattern.len());
+            let mut window = Vec::new();
+            let mut found = Vec::new();
+            for chunk in text.chunks(chunk_len) {
+                window.extend_from_slice(chunk);
+                let offset = tracker.window_offset() as usize;
+                assert_eq!(window, &text[offset..offset + window.len()]);
+                let mut matches = finder.find_iter(&window);
+                matches.advance_to(tracker.search_start());
+                for m in matches {
+                    found.push(tracker.record(m));
+                }
+                let carry = tracker.advance(window.len());
+                window.drain(..window.len() - carry);
+            }
+            assert_eq!(found, expected, "{} {}", ::Bytestring(pattern), chunk_len);
+        }
+    }
+}
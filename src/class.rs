@@ -0,0 +1,165 @@
+
+use std::ops::Range;
+
+use haystack::{skip, sub, Haystack};
+use {decomposition_by, find_from_by, Hrp, SearchState};
+
+/// A pattern where each position accepts one of a set of alternative
+/// elements, like `GA[AG]TTC`.
+///
+/// Equality with a class is not an equivalence relation, so the pattern can
+/// not be preprocessed as a whole. Instead, the longest run of positions that
+/// accept a single element, the *anchor*, is searched for with the
+/// Galil-Seiferas algorithm, and each occurrence of the anchor is verified
+/// against the whole pattern.
+///
+/// Preprocessing is O(m) time and O(1) space. A search is O(n) time for the
+/// anchor, and O(m c) time to verify each occurrence of the anchor, where
+/// c is the size of the largest class; this is O(n m c) in the worst case,
+/// when the anchor is short and frequent, or when there is no anchor because
+/// all positions have several alternatives.
+///
+/// ```
+/// use galil_seiferas::ClassPattern;
+///
+/// // GARTTC, where R is A or G
+/// let positions: &[&[u8]] = &[b"G", b"A", b"AG", b"T", b"T", b"C"];
+/// let pattern = ClassPattern::new(positions);
+/// assert_eq!(pattern.find(b"CCGAGTTCAA"), Some(2));
+/// assert_eq!(pattern.find(b"CCGACTTCAA"), None);
+/// ```
+#[derive(Debug)]
+pub struct ClassPattern<'a, T: 'a> {
+    positions: &'a [&'a [T]],
+    anchor: Range<usize>,
+    /// The decomposition of the anchor, see `decomposition_by`
+    u_len: usize,
+    hrp1: Option<Hrp>,
+}
+
+impl<'a, T: Eq> ClassPattern<'a, T> {
+    /// Preprocess a pattern with the alternatives `positions[i]` at position
+    /// `i`.
+    ///
+    /// A position without alternatives never matches.
+    pub fn new(positions: &'a [&'a [T]]) -> Self {
+        let mut anchor = 0..0;
+        let mut run_start = 0;
+        for (i, alternatives) in positions.iter().enumerate() {
+            if alternatives.len() != 1 {
+                run_start = i + 1;
+            } else if i + 1 - run_start > anchor.len() {
+                anchor = run_start..i + 1;
+            }
+        }
+        let literals = sub(Literals(positions), anchor.clone());
+        let (u_len, hrp1) = decomposition_by(literals, &T::eq);
+        ClassPattern { positions, anchor, u_len, hrp1 }
+    }
+
+    /// The length of the pattern
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Return true if the pattern is empty
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Return true if `text` starts with a match of the pattern.
+    fn is_prefix_of(&self, text: &[T]) -> bool {
+        text.len() >= self.len() &&
+            self.positions.iter().zip(text).all(|(alternatives, elt)| alternatives.contains(elt))
+    }
+
+    /// If a match exists in `text`, return the offset to the start of the
+    /// first match inside `Some(_)`. If not, return `None`.
+    pub fn find(&self, text: &[T]) -> Option<usize> {
+        let m = self.len();
+        if text.len() < m {
+            return None;
+        }
+        if self.anchor.is_empty() {
+            return (0..text.len() - m + 1).find(|&i| self.is_prefix_of(&text[i..]));
+        }
+        // Search the part of the text where the anchor of a match can be
+        let searched = sub(text, self.anchor.start..text.len() - (m - self.anchor.end));
+        let literals = sub(Literals(self.positions), self.anchor.clone());
+        let u = sub(literals, 0..self.u_len);
+        let v = skip(literals, self.u_len);
+        let mut state = SearchState::default();
+        while let Some(i) = find_from_by(searched, u, v, self.hrp1, &mut state, &T::eq) {
+            if self.is_prefix_of(&text[i..]) {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> Clone for ClassPattern<'a, T> {
+    fn clone(&self) -> Self {
+        ClassPattern {
+            positions: self.positions,
+            anchor: self.anchor.clone(),
+            u_len: self.u_len,
+            hrp1: self.hrp1,
+        }
+    }
+}
+
+/// A view of the single alternatives of the positions of a pattern
+struct Literals<'a, T: 'a>(&'a [&'a [T]]);
+
+impl<'a, T> Copy for Literals<'a, T> { }
+impl<'a, T> Clone for Literals<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Haystack for Literals<'a, T> {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        &self.0[index][0]
+    }
+}
+
+#[test]
+fn test_class_pattern() {
+    let positions: &[&[u8]] = &[b"aA", b"b", b"c", b"b", b"", b"c"];
+    let pattern = ClassPattern::new(positions);
+    assert_eq!(pattern.anchor, 1..4);
+    assert_eq!(pattern.find(b"abcbxc"), None);
+
+    let positions: &[&[u8]] = &[b"aA", b"b", b"a", b"b", b"cC"];
+    let pattern = ClassPattern::new(positions);
+    assert_eq!(pattern.find(b"abababAbabC"), Some(6));
+    assert_eq!(pattern.find(b"ababC"), Some(0));
+    assert_eq!(pattern.find(b"babab"), None);
+    assert_eq!(pattern.find(b"abab"), None);
+
+    let positions: &[&[u8]] = &[b"aA", b"bB"];
+    assert_eq!(ClassPattern::new(positions).find(b"xaxAB"), Some(3));
+    assert_eq!(ClassPattern::new(&[]).find(b"ab"), Some(0));
+
+    // Compare with a brute force search
+    let text = b"abcacbbcabacbabaacbcabacbbbcaabcbca";
+    let classes: &[&[u8]] = &[b"a", b"b", b"ab", b"bc", b"c", b"abc"];
+    for &a in classes {
+        for &b in classes {
+            for &c in classes {
+                let positions = [a, b, c, a, a];
+                let pattern = ClassPattern::new(&positions);
+                let expected = (0..text.len() - positions.len() + 1).find(|&i| {
+                    positions.iter().zip(&text[i..]).all(|(alts, elt)| alts.contains(elt))
+                });
+                assert_eq!(pattern.find(text), expected);
+            }
+        }
+    }
+}
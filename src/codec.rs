@@ -0,0 +1,142 @@
+
+use std::io;
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::Decoder;
+
+use analysis::PatternInfo;
+use finder::Finder;
+use {search_from_by, SearchState};
+
+/// A `tokio_util` codec that splits a byte stream into frames that are
+/// terminated by a (multi-byte) delimiter.
+///
+/// The frames are decoded without the delimiter. At the end of the stream,
+/// a last unterminated frame is decoded too, if it is not empty.
+///
+/// The search of each frame is resumed where it stopped each time more input
+/// arrives, like `GrowingBufferFinder`, so the input is searched in a single
+/// pass and framing is linear time even for highly periodic delimiters,
+/// however the input is split into arrivals.
+///
+/// ```
+/// extern crate bytes;
+/// extern crate galil_seiferas;
+/// extern crate tokio_util;
+///
+/// use bytes::BytesMut;
+/// use tokio_util::codec::Decoder;
+/// use galil_seiferas::DelimiterCodec;
+///
+/// let mut codec = DelimiterCodec::new(b"\r\n\r\n");
+/// let mut buffer = BytesMut::from(&b"GET / HTTP/1.1\r\n\r"[..]);
+/// assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+/// buffer.extend_from_slice(b"\nbody");
+/// assert_eq!(codec.decode(&mut buffer).unwrap().unwrap(), &b"GET / HTTP/1.1"[..]);
+/// assert_eq!(buffer, &b"body"[..]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DelimiterCodec {
+    finder: Finder<'static, u8>,
+    info: PatternInfo,
+    /// The state of the search of the current frame
+    state: SearchState,
+}
+
+impl DelimiterCodec {
+    /// Create a codec that splits frames on `delimiter`.
+    ///
+    /// **Panics** if `delimiter` is empty.
+    pub fn new(delimiter: &[u8]) -> Self {
+        assert!(!delimiter.is_empty(), "DelimiterCodec: the delimiter must not be empty");
+        let finder = Finder::from_vec(delimiter.to_vec());
+        let info = finder.info();
+        DelimiterCodec { finder, info, state: SearchState::default() }
+    }
+
+    /// The delimiter
+    pub fn delimiter(&self) -> &[u8] {
+        self.finder.pattern()
+    }
+}
+
+impl Decoder for DelimiterCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        Ok(self.decode_by(src, &u8::eq))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                self.state.restart(0);
+                Ok(Some(src.split()))
+            }
+        }
+    }
+}
+
+impl DelimiterCodec {
+    /// Decode the next frame, comparing the bytes with `equal`; the search
+    /// resumes from `self.state`, which is reset when a frame is split off.
+    fn decode_by<F>(&mut self, src: &mut BytesMut, equal: &F) -> Option<BytesMut>
+        where F: Fn(&u8, &u8) -> bool,
+    {
+        let (u, v) = self.finder.pattern().split_at(self.info.u_len());
+        let start = search_from_by(&src[..], u, v, self.info.hrp(), &mut self.state, equal)?;
+        let frame = src.split_to(start);
+        src.advance(self.info.len());
+        self.state.restart(0);
+        Some(frame)
+    }
+}
+
+#[test]
+fn test_delimiter_codec() {
+    let mut codec = DelimiterCodec::new(b"abab");
+    let mut buffer = BytesMut::new();
+    let mut frames = Vec::new();
+    for &byte in b"xabaabababy-ababab" {
+        buffer.extend_from_slice(&[byte]);
+        while let Some(frame) = codec.decode(&mut buffer).unwrap() {
+            frames.push(frame);
+        }
+    }
+    while let Some(frame) = codec.decode_eof(&mut buffer).unwrap() {
+        frames.push(frame);
+    }
+    assert_eq!(frames, vec![&b"xaba"[..], b"aby-", b"ab"]);
+    assert_eq!(codec.delimiter(), b"abab");
+}
+
+#[test]
+fn test_delimiter_codec_linear() {
+    use std::cell::Cell;
+
+    // A periodic delimiter that almost occurs at each position
+    let delimiter = [&[b'a'; 63][..], b"b"].concat();
+    let mut codec = DelimiterCodec::new(&delimiter);
+    // Count the comparisons with the input; the debug checks of the
+    // preprocessing compare the delimiter with itself
+    let own = codec.delimiter().as_ptr_range();
+    let count = Cell::new(0);
+    let equal = |a: &u8, b: &u8| {
+        if !own.contains(&(a as *const u8)) || !own.contains(&(b as *const u8)) {
+            count.set(count.get() + 1);
+        }
+        a == b
+    };
+    let mut buffer = BytesMut::new();
+    let n = 10_000;
+    for _ in 0..n {
+        buffer.extend_from_slice(b"a");
+        assert_eq!(codec.decode_by(&mut buffer, &equal), None);
+    }
+    buffer.extend_from_slice(b"b");
+    assert_eq!(codec.decode_by(&mut buffer, &equal).unwrap().len(), n - 63);
+    assert!(count.get() <= 4 * (n + 1), "{} comparisons", count.get());
+}
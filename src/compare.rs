@@ -0,0 +1,226 @@
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use alloc::vec::Vec;
+
+use crochemore::Crochemore;
+use engine::SearchEngine;
+use finder::Finder;
+use kmp::Kmp;
+use simd::SimdTwoWay;
+use two_way::TwoWay;
+
+/// The measurements of one engine, created by `compare_engines`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EngineReport {
+    /// The name of the engine, like `"two-way"`
+    pub engine: &'static str,
+    /// The number of (non-overlapping) matches
+    pub matches: usize,
+    /// The time of the preprocessing and the search
+    pub time: Duration,
+    /// The number of element comparisons during preprocessing, if the
+    /// engine can be instrumented
+    pub preprocessing_comparisons: Option<u64>,
+    /// The number of element comparisons between the text and the pattern,
+    /// if the engine can be instrumented
+    pub comparisons: Option<u64>,
+    /// The number of times the alignment of the pattern in the text changed
+    /// between comparisons, if the engine can be instrumented
+    pub shifts: Option<u64>,
+}
+
+/// The names of the engines, in the order of the reports
+const ENGINES: [&str; 5] = ["galil-seiferas", "two-way", "crochemore", "kmp", "simd-two-way"];
+
+/// Search `text` for all the (non-overlapping) matches of `pattern` with each
+/// of the engines of this crate, and report their measurements.
+///
+/// Each engine searches twice: once timed, and once with instrumented
+/// elements for counting comparisons and shifts (except `SimdTwoWay`, which
+/// can't be instrumented).
+///
+/// ```
+/// use galil_seiferas::compare_engines;
+///
+/// for report in compare_engines(b"abracadabra", b"abra") {
+///     assert_eq!(report.matches, 2);
+///     println!("{}: {:?}, {:?} comparisons", report.engine, report.time, report.comparisons);
+/// }
+/// ```
+pub fn compare_engines(text: &[u8], pattern: &[u8]) -> Vec<EngineReport> {
+    compare_engines_corpus(Some((text, pattern)))
+}
+
+/// Like `compare_engines`, for each text and pattern in `corpus`; the
+/// measurements are summed over the corpus.
+pub fn compare_engines_corpus<'a, I>(corpus: I) -> Vec<EngineReport>
+    where I: IntoIterator<Item = (&'a [u8], &'a [u8])>,
+{
+    let mut reports = ENGINES.iter().map(|&engine| EngineReport {
+        engine,
+        matches: 0,
+        time: Duration::default(),
+        preprocessing_comparisons: Some(0),
+        comparisons: Some(0),
+        shifts: Some(0),
+    }).collect::<Vec<_>>();
+    reports[4].preprocessing_comparisons = None;
+    reports[4].comparisons = None;
+    reports[4].shifts = None;
+
+    let mut table = Vec::new();
+    for (text, pattern) in corpus {
+        table.resize(pattern.len(), 0);
+        let timings = [
+            time(|| count(Finder::new(pattern), text)),
+            time(|| count(TwoWay::new(pattern), text)),
+            time(|| count(Crochemore::new(pattern), text)),
+            time(|| count(Kmp::new(pattern, &mut table), text)),
+            time(|| count(SimdTwoWay::new(pattern), text)),
+        ];
+        for (report, &(matches, elapsed)) in reports.iter_mut().zip(&timings) {
+            report.matches += matches;
+            report.time += elapsed;
+        }
+
+        let counters = Counters::default();
+        let text = probes(text, true, &counters);
+        let pattern = probes(pattern, false, &counters);
+        let probed_runs: [&dyn Fn() -> usize; 4] = [
+            &|| count(Finder::new(&pattern), &text),
+            &|| count(TwoWay::new(&pattern), &text),
+            &|| count(Crochemore::new(&pattern), &text),
+            &|| count(Kmp::new(&pattern, &mut vec![0; pattern.len()]), &text),
+        ];
+        for (report, run) in reports.iter_mut().zip(&probed_runs) {
+            counters.reset();
+            run();
+            add(&mut report.preprocessing_comparisons, counters.preprocessing.get());
+            add(&mut report.comparisons, counters.comparisons.get());
+            add(&mut report.shifts, counters.shifts.get());
+        }
+    }
+    reports
+}
+
+fn time<F: FnOnce() -> usize>(f: F) -> (usize, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn count<T, E: SearchEngine<T>>(engine: E, text: &[T]) -> usize {
+    engine.find_iter(text).count()
+}
+
+fn add(total: &mut Option<u64>, value: u64) {
+    if let Some(ref mut total) = *total {
+        *total += value;
+    }
+}
+
+/// Comparison counters shared by the probes
+#[derive(Default)]
+struct Counters {
+    preprocessing: Cell<u64>,
+    comparisons: Cell<u64>,
+    shifts: Cell<u64>,
+    /// The alignment of the last comparison between the text and the
+    /// pattern, text index minus pattern index
+    alignment: Cell<Option<isize>>,
+}
+
+impl Counters {
+    fn reset(&self) {
+        self.preprocessing.set(0);
+        self.comparisons.set(0);
+        self.shifts.set(0);
+        self.alignment.set(None);
+    }
+}
+
+/// An element that counts its comparisons
+struct Probe<'c> {
+    value: u8,
+    index: usize,
+    in_text: bool,
+    counters: &'c Counters,
+}
+
+fn probes<'c>(elements: &[u8], in_text: bool, counters: &'c Counters) -> Vec<Probe<'c>> {
+    elements.iter().enumerate()
+        .map(|(index, &value)| Probe { value, index, in_text, counters })
+        .collect()
+}
+
+impl<'c> Probe<'c> {
+    fn record(&self, other: &Self) {
+        let counters = self.counters;
+        let (text, pattern) = match (self.in_text, other.in_text) {
+            (true, false) => (self, other),
+            (false, true) => (other, self),
+            _ => {
+                counters.preprocessing.set(counters.preprocessing.get() + 1);
+                return;
+            }
+        };
+        counters.comparisons.set(counters.comparisons.get() + 1);
+        let alignment = text.index as isize - pattern.index as isize;
+        match counters.alignment.get() {
+            Some(last) if last != alignment => counters.shifts.set(counters.shifts.get() + 1),
+            _ => { }
+        }
+        counters.alignment.set(Some(alignment));
+    }
+}
+
+impl<'c> PartialEq for Probe<'c> {
+    fn eq(&self, other: &Self) -> bool {
+        self.record(other);
+        self.value == other.value
+    }
+}
+
+impl<'c> Eq for Probe<'c> { }
+
+impl<'c> PartialOrd for Probe<'c> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'c> Ord for Probe<'c> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.record(other);
+        self.value.cmp(&other.value)
+    }
+}
+
+#[test]
+fn test_compare_engines() {
+    let reports = compare_engines(b"aaaaaaaaaa", b"aab");
+    let names = reports.iter().map(|report| report.engine).collect::<Vec<_>>();
+    assert_eq!(names, ENGINES);
+    for report in &reports {
+        assert_eq!(report.matches, 0);
+    }
+    // After the first two, KMP compares each text element with b, then
+    // twice with a, after a shift by one.
+    let kmp = &reports[3];
+    assert_eq!(kmp.preprocessing_comparisons, Some(3));
+    assert_eq!(kmp.comparisons, Some(27));
+    assert_eq!(kmp.shifts, Some(8));
+    assert_eq!(reports[4].comparisons, None);
+
+    let corpus = [(&b"abcabc"[..], &b"bc"[..]), (b"aaaa", b"aa")];
+    let reports = compare_engines_corpus(corpus.iter().cloned());
+    for report in &reports {
+        assert_eq!(report.matches, 4, "{}", report.engine);
+        if report.engine != "simd-two-way" {
+            assert!(report.comparisons.unwrap() >= 6);
+        }
+    }
+}
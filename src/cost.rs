@@ -0,0 +1,156 @@
+
+use analysis::{analyze, PatternInfo};
+use GS_K;
+
+/// A heuristic estimate of the cost of searching for a byte string pattern,
+/// created by `estimate_cost`.
+///
+/// The costs are in element comparisons per text element, for the search of
+/// `gs_find` and `Finder`. They are meant for comparing patterns, for example
+/// to choose between engines or to decide if a text is worth indexing; they
+/// are not exact.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CostEstimate {
+    info: PatternInfo,
+    distinct_bytes: usize,
+    match_probability: f64,
+    expected: f64,
+}
+
+impl CostEstimate {
+    /// The structure of the pattern, see `analyze`.
+    pub fn info(&self) -> PatternInfo {
+        self.info
+    }
+
+    /// The number of distinct bytes in the pattern
+    pub fn distinct_bytes(&self) -> usize {
+        self.distinct_bytes
+    }
+
+    /// The probability that two bytes picked at random from the pattern are
+    /// equal; it is 1 if the pattern has only one distinct byte, and it is
+    /// small if the bytes of the pattern are spread over a large alphabet.
+    pub fn match_probability(&self) -> f64 {
+        self.match_probability
+    }
+
+    /// The expected number of comparisons per text element, for a random
+    /// text with the same distribution of bytes as the pattern.
+    ///
+    /// This is close to 1 for patterns over a large alphabet, and it grows
+    /// with the length of the pattern where its bytes are alike.
+    pub fn expected_comparisons(&self) -> f64 {
+        self.expected
+    }
+
+    /// The approximate number of comparisons per text element in the worst
+    /// case, for a text that is chosen to make the search slow.
+    ///
+    /// The search makes a bounded number of comparisons per text element, so
+    /// this does not grow with the length of the pattern.
+    pub fn worst_case_comparisons(&self) -> f64 {
+        self.info.len().min(GS_K) as f64
+    }
+}
+
+/// Estimate the cost of searching for `pattern`, from its structure and the
+/// spread of its bytes.
+///
+/// The search compares v, of the decomposition of the pattern into u v, at
+/// each position where it is not shifted further; and u, where v matches.
+/// The estimate assumes that the comparisons of each position stop at the
+/// first mismatch, with the probability `match_probability` of a match.
+///
+/// This computes in **O(m)** time and **O(1)** space.
+///
+/// ```
+/// use galil_seiferas::estimate_cost;
+///
+/// let dna = estimate_cost(b"GATTACAGATTACA");
+/// let text = estimate_cost(b"Galil-Seiferas");
+/// assert!(dna.expected_comparisons() > text.expected_comparisons());
+/// assert!(text.expected_comparisons() < 1.2);
+/// ```
+pub fn estimate_cost(pattern: &[u8]) -> CostEstimate {
+    let info = analyze(pattern);
+    let mut counts = [0usize; 256];
+    for &byte in pattern {
+        counts[byte as usize] += 1;
+    }
+    let m = pattern.len() as f64;
+    let mut distinct_bytes = 0;
+    let mut match_probability = 0.;
+    for &count in counts.iter().filter(|&&count| count > 0) {
+        distinct_bytes += 1;
+        match_probability += (count as f64 / m) * (count as f64 / m);
+    }
+    // The expected number of comparisons of a prefix of `len` elements, and
+    // the probability that all of them match.
+    let compare = |len: usize| {
+        let mut comparisons = 0.;
+        let mut reached = 1.;
+        for _ in 0..len {
+            comparisons += reached;
+            reached *= match_probability;
+        }
+        (comparisons, reached)
+    };
+    let (v_comparisons, v_matches) = compare(info.v_len());
+    let (u_comparisons, _) = compare(info.u_len());
+    CostEstimate {
+        info,
+        distinct_bytes,
+        match_probability,
+        expected: v_comparisons + v_matches * u_comparisons,
+    }
+}
+
+#[test]
+fn test_estimate_cost() {
+    use std::cell::Cell;
+    use {Pattern, SearchState};
+
+    let empty = estimate_cost(b"");
+    assert_eq!(empty.distinct_bytes(), 0);
+    assert_eq!(empty.expected_comparisons(), 0.);
+    assert_eq!(empty.worst_case_comparisons(), 0.);
+
+    let same = estimate_cost(b"aaaa");
+    assert_eq!(same.distinct_bytes(), 1);
+    assert_eq!(same.match_probability(), 1.);
+    assert_eq!(same.expected_comparisons(), 4.);
+    assert_eq!(same.worst_case_comparisons(), 3.);
+
+    let spread = estimate_cost(b"abcd");
+    assert_eq!(spread.distinct_bytes(), 4);
+    assert_eq!(spread.match_probability(), 0.25);
+    assert!(spread.expected_comparisons() < same.expected_comparisons());
+
+    // Compare with the search for all the matches in a pseudorandom text
+    // with the distribution of bytes of the pattern
+    let mut state = 0x2545_f491_u32;
+    for pattern in &[&b"ab"[..], b"abcd", b"aaab", b"abaababaab", b"GATTACA", b"needle"] {
+        let n = 20_000;
+        let text = (0..n).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            pattern[state as usize % pattern.len()]
+        }).collect::<Vec<_>>();
+        let count = Cell::new(0);
+        let p = Pattern::preprocess_using(pattern, |a: &u8, b: &u8| {
+            count.set(count.get() + 1);
+            a == b
+        });
+        count.set(0);
+        let mut search = SearchState::default();
+        while p.find_from(&text, &mut search).is_some() { }
+        let measured = count.get() as f64 / n as f64;
+        let estimate = estimate_cost(pattern);
+        assert!(measured < estimate.expected_comparisons() * 1.25 &&
+                measured > estimate.expected_comparisons() * 0.75,
+                "{}: {} {:?}", ::Bytestring(pattern), measured, estimate);
+        assert!(measured < estimate.worst_case_comparisons() + 0.5);
+    }
+}
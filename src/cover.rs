@@ -0,0 +1,102 @@
+
+use alloc::vec::Vec;
+
+use kmp::borders;
+
+/// Return the length of the shortest cover of `x`, or zero if `x` is empty.
+///
+/// A cover (or quasiperiod) of `x` is a prefix of `x` whose occurrences in
+/// `x` together cover every position of `x`; `x` is always a cover of
+/// itself. For example, `aba` is the shortest cover of `abaababa`.
+///
+/// This computes in **O(m)** time and space.
+///
+/// ```
+/// use galil_seiferas::shortest_cover;
+///
+/// assert_eq!(shortest_cover(b"abaababa"), 3);
+/// assert_eq!(shortest_cover(b"abcab"), 5);
+/// ```
+pub fn shortest_cover<T: Eq>(x: &[T]) -> usize {
+    prefix_covers(x).0[x.len()]
+}
+
+/// Return the lengths of all the covers of `x`, in increasing order; see
+/// `shortest_cover`.
+///
+/// This computes in **O(m)** time and space.
+///
+/// ```
+/// use galil_seiferas::covers;
+///
+/// assert_eq!(covers(b"abaababaaba"), [3, 6, 11]);
+/// ```
+pub fn covers<T: Eq>(x: &[T]) -> Vec<usize> {
+    let m = x.len();
+    let (cover, borders) = prefix_covers(x);
+    // The covers of x are the borders of x whose shortest cover is the
+    // shortest cover of x (Moore and Smyth, 1994)
+    let mut covers = Vec::new();
+    let mut len = m;
+    while len > 0 {
+        if cover[len] == cover[m] {
+            covers.push(len);
+        }
+        len = borders[len - 1];
+    }
+    covers.reverse();
+    covers
+}
+
+/// Return the length of the shortest cover of each prefix `x[..i]` at index
+/// `i`, and the borders of the prefixes, like in `Kmp`.
+fn prefix_covers<T: Eq>(x: &[T]) -> (Vec<usize>, Vec<usize>) {
+    let m = x.len();
+    let mut border = ::alloc::vec![0; m];
+    borders(x, &mut border);
+    let mut cover = ::alloc::vec![0; m + 1];
+    // last[c] is the length of the longest prefix so far whose shortest
+    // cover is c long
+    let mut last = ::alloc::vec![0; m + 1];
+    for i in 1..m + 1 {
+        // x[..i] is covered by the cover of its border, if an occurrence
+        // of it ends at i - c or later (Breslauer, 1992)
+        let b = border[i - 1];
+        let c = cover[b];
+        cover[i] = if b > 0 && last[c] >= i - c { c } else { i };
+        last[cover[i]] = i;
+    }
+    (cover, border)
+}
+
+#[test]
+fn test_covers() {
+    fn brute_force_covers(x: &[u8]) -> Vec<usize> {
+        (1..x.len() + 1).filter(|&len| {
+            let mut covered = 0;
+            for i in 0..x.len() - len + 1 {
+                if x[i..].starts_with(&x[..len]) {
+                    if i > covered {
+                        return false;
+                    }
+                    covered = i + len;
+                }
+            }
+            covered == x.len()
+        }).collect()
+    }
+
+    assert_eq!(shortest_cover::<u8>(b""), 0);
+    assert_eq!(covers::<u8>(b""), []);
+    assert_eq!(covers(b"aaaa"), [1, 2, 3, 4]);
+
+    // every ternary word up to length 9
+    for len in 1..10 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| b'a' + (n / 3u32.pow(i) % 3) as u8).collect::<Vec<_>>();
+            let expected = brute_force_covers(&x);
+            assert_eq!(covers(&x), expected, "{}", ::Bytestring(&x));
+            assert_eq!(shortest_cover(&x), expected[0]);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use analysis::analyze;
+use finder::Finder;
+use iter::{Matches, Options};
+
+/// Return an iterator of the parts of `text` that are covered by a match of
+/// `pattern`, including overlapping matches.
+///
+/// The ranges are the union of the ranges of all the matches: they are
+/// in order, and they neither overlap nor touch. The empty pattern covers
+/// nothing. Use `Coverage::covered_len` for the total length.
+///
+/// All the matches are found in a single **O(n)** pass.
+///
+/// ```
+/// use galil_seiferas::gs_coverage;
+///
+/// let text = b"xabababxxaba";
+/// let ranges = gs_coverage(text, b"aba").collect::<Vec<_>>();
+/// assert_eq!(ranges, [1..6, 9..12]);
+/// assert_eq!(gs_coverage(text, b"aba").covered_len(), 8);
+/// ```
+pub fn gs_coverage<'p, 't, T: Eq>(text: &'t [T], pattern: &'p [T]) -> Coverage<'p, 't, T> {
+    let options = Options { overlapping: true, ..Options::default() };
+    Coverage::new(Matches::with_info(pattern, analyze(pattern), text, options), pattern.len())
+}
+
+/// An iterator of the ranges of the text that are covered by matches,
+/// created by `gs_coverage` or `Finder::coverage`.
+#[derive(Clone)]
+pub struct Coverage<'p, 't, T: 'p + 't> {
+    matches: Matches<'p, 't, T>,
+    /// The length of the pattern
+    len: usize,
+    /// The start of the next range, if it is found
+    pending: Option<usize>,
+}
+
+impl<'p, 't, T: Eq> Coverage<'p, 't, T> {
+    fn new(matches: Matches<'p, 't, T>, len: usize) -> Self {
+        Coverage { matches, len, pending: None }
+    }
+
+    pub(crate) fn from_finder(finder: &'p Finder<T>, text: &'t [T]) -> Self {
+        let options = Options { overlapping: true, ..Options::default() };
+        Coverage::new(Matches::new(finder, text, options), finder.pattern().len())
+    }
+
+    /// Return the total length of the rest of the ranges.
+    pub fn covered_len(self) -> usize {
+        self.map(|range| range.len()).sum()
+    }
+}
+
+impl<'p, 't, T: Eq> Iterator for Coverage<'p, 't, T> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        if self.len == 0 {
+            return None;
+        }
+        let start = match self.pending.take() {
+            Some(start) => start,
+            None => self.matches.next_start()?,
+        };
+        let mut end = start + self.len;
+        while let Some(next) = self.matches.next_start() {
+            if next <= end {
+                end = next + self.len;
+            } else {
+                self.pending = Some(next);
+                break;
+            }
+        }
+        Some(start..end)
+    }
+}
+
+impl<'p, 't, T: Eq> FusedIterator for Coverage<'p, 't, T> { }
+
+#[test]
+fn test_coverage() {
+    assert_eq!(gs_coverage(b"abc", b"").count(), 0);
+    assert_eq!(gs_coverage(b"", b"a").count(), 0);
+    assert_eq!(gs_coverage(b"aaaa", b"aa").collect::<Vec<_>>(), vec![0..4]);
+    // Adjacent matches are merged
+    assert_eq!(gs_coverage(b"abab", b"ab").collect::<Vec<_>>(), vec![0..4]);
+    let finder = Finder::new(b"aba");
+    assert_eq!(finder.coverage(b"abaxababa").collect::<Vec<_>>(), [0..3, 4..9]);
+
+    // Every binary text of length < 11
+    for len in 0..11 {
+        for bits in 0..1u32 << len {
+            let text = (0..len).map(|i| b'a' + ((bits >> i) & 1) as u8).collect::<Vec<_>>();
+            for pattern in &[&b"a"[..], b"ab", b"aa", b"aba", b"abaab", b"bbb"] {
+                let mut covered = vec![false; text.len()];
+                for i in 0..text.len() {
+                    if text[i..].starts_with(pattern) {
+                        for c in &mut covered[i..i + pattern.len()] {
+                            *c = true;
+                        }
+                    }
+                }
+                let mut expected = covered.clone();
+                for range in gs_coverage(&text, pattern) {
+                    assert!(range.start == 0 || !covered[range.start - 1]);
+                    assert!(range.end == text.len() || !covered[range.end]);
+                    for c in &mut expected[range] {
+                        assert!(*c);
+                        *c = false;
+                    }
+                }
+                assert!(expected.iter().all(|&c| !c));
+                assert_eq!(gs_coverage(&text, pattern).covered_len(),
+                           covered.iter().filter(|&&c| c).count());
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+
+use std::cmp::max;
+
+use engine::SearchEngine;
+use two_way::MaxSuffix;
+
+/// Crochemore's string matching algorithm for ordered alphabets.
+///
+/// It is a constant space, linear time simulation of Knuth-Morris-Pratt:
+/// instead of a table of the periods of the prefixes of the pattern, it
+/// computes the maximal suffix of the matched prefix on the fly, which gives
+/// either the exact period of the prefix or a lower bound that is at least a
+/// third of its length. The pattern needs no preprocessing, but the elements
+/// need an ordering: `T: Ord`.
+///
+/// Reference: M. Crochemore, *String-matching on ordered alphabets*,
+/// Theoretical Computer Science (1992)
+///
+/// ```
+/// use galil_seiferas::{Crochemore, SearchEngine};
+///
+/// let engine = Crochemore::new(b"abaab");
+/// assert_eq!(engine.find(b"abaabaab"), Some(0));
+/// assert_eq!(engine.find_at(b"abaabaab", 1), Some(3));
+/// ```
+#[derive(Debug)]
+pub struct Crochemore<'a, T: 'a> {
+    pattern: &'a [T],
+}
+
+impl<'a, T> Copy for Crochemore<'a, T> { }
+impl<'a, T> Clone for Crochemore<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: Ord> Crochemore<'a, T> {
+    /// Create the engine for `pattern`; this does no preprocessing.
+    pub fn new(pattern: &'a [T]) -> Self {
+        Crochemore { pattern }
+    }
+}
+
+impl<'a, T: Ord> SearchEngine<T> for Crochemore<'a, T> {
+    fn pattern(&self) -> &[T] {
+        self.pattern
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        let x = self.pattern;
+        let (n, m) = (text.len(), x.len());
+        if n < m || start > n - m {
+            return None;
+        }
+        let mut pos = start;
+        // The length of the prefix of the pattern that is known to match,
+        // and the maximal suffix of (a prefix of) that prefix.
+        let mut i = 0;
+        let mut suffix = MaxSuffix::new();
+        while pos <= n - m {
+            while i < m && x[i] == text[pos + i] {
+                i += 1;
+            }
+            if i == m {
+                return Some(pos);
+            }
+            if i == 0 {
+                pos += 1;
+                continue;
+            }
+            suffix.extend(x, i, T::cmp);
+            let (s, p) = (suffix.start, suffix.period);
+            if x[..s] == x[p..p + s] {
+                // p is the period of x[..i]: shift by p and keep the part
+                // that is still matched, with its maximal suffix.
+                pos += p;
+                i -= p;
+                if suffix.candidate - p > s {
+                    suffix.candidate -= p;
+                } else {
+                    // x[..i] is shorter than 2 p; recompute it.
+                    suffix = MaxSuffix::new();
+                }
+            } else {
+                // The period of x[..i] is greater than all of these.
+                pos += max(max(s + 1, i - s - p + 1), p);
+                i = 0;
+                suffix = MaxSuffix::new();
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn test_crochemore() {
+    let text = b"abaababaabaababaababaabaababaabaab";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 14) {
+            let pattern = &text[start..end];
+            let engine = Crochemore::new(pattern);
+            let starts = (0..text.len() + 1)
+                .filter(|&i| text[i..].starts_with(pattern))
+                .collect::<Vec<_>>();
+            let found = (0..text.len() + 1)
+                .filter_map(|i| engine.find_at(text, i))
+                .collect::<::std::collections::BTreeSet<_>>();
+            assert_eq!(found.into_iter().collect::<Vec<_>>(), starts, "{:?}", pattern);
+        }
+    }
+    let engine = Crochemore::new(b"aaaab");
+    assert_eq!(engine.find(b"aaaaaaaaaaaaab"), Some(9));
+    assert_eq!(engine.find(b"aaaaaaaaaaaaaa"), None);
+    assert_eq!(Crochemore::new(b"").find_at(b"ab", 2), Some(2));
+}
@@ -0,0 +1,163 @@
+use std::cmp::min;
+use std::fmt;
+use std::time::Instant;
+
+use finder::Finder;
+use SearchState;
+
+/// The number of text positions that are searched between the checks of
+/// the clock
+const CHECK_INTERVAL: usize = 1 << 16;
+
+/// The state of a search that ran out of time, for resuming it with
+/// `find_with_deadline_from`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResumeState {
+    state: SearchState,
+    /// The length of the prefix of the text that was searched
+    searched: usize,
+}
+
+impl ResumeState {
+    /// The state at the start of the text
+    pub fn new() -> Self {
+        ResumeState { state: SearchState::default(), searched: 0 }
+    }
+
+    /// The length of the prefix of the text that was searched; a match
+    /// that ends inside it would already have been found.
+    pub fn searched_len(&self) -> usize {
+        self.searched
+    }
+}
+
+impl Default for ResumeState {
+    fn default() -> Self {
+        ResumeState::new()
+    }
+}
+
+/// The error of a search that reached its deadline, returned by
+/// `find_with_deadline`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimedOut {
+    /// Where the search stopped; pass it to `find_with_deadline_from` to
+    /// continue.
+    pub resume_state: ResumeState,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the search reached its deadline after searching {} elements",
+               self.resume_state.searched)
+    }
+}
+
+impl ::std::error::Error for TimedOut { }
+
+/// Find the first match of `pattern` in `text` like `gs_find`, but stop with
+/// `TimedOut` if the search is not done by `deadline`.
+///
+/// The text is searched a block at a time, and the clock is checked after
+/// each block, so the search stops soon after the deadline even for the
+/// slowest texts. At least one block is searched, so repeated calls always
+/// make progress.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use galil_seiferas::find_with_deadline;
+///
+/// let text = "ab".repeat(1 << 20) + "abc";
+/// let deadline = Instant::now() + Duration::from_secs(10);
+/// assert_eq!(find_with_deadline(text.as_bytes(), b"abc", deadline), Ok(Some(1 << 21)));
+/// ```
+pub fn find_with_deadline<T: Eq>(text: &[T], pattern: &[T], deadline: Instant)
+    -> Result<Option<usize>, TimedOut>
+{
+    find_with_deadline_from(text, pattern, deadline, ResumeState::new())
+}
+
+/// Continue the search of `find_with_deadline` from `resume_state`, with a
+/// new `deadline`.
+///
+/// The text and the pattern must be the same as in the search that timed
+/// out. The pattern is preprocessed again, in **O(m)** time.
+///
+/// ```
+/// use std::time::Instant;
+/// use galil_seiferas::{find_with_deadline, find_with_deadline_from};
+///
+/// let text = vec![0u8; 1 << 20];
+/// // The deadline has passed already, so the search stops after one block
+/// let timed_out = find_with_deadline(&text, &[1], Instant::now()).unwrap_err();
+/// let mut resume_state = timed_out.resume_state;
+/// assert!(resume_state.searched_len() < text.len());
+/// // Keep going in the time slots of an event loop, for example
+/// loop {
+///     match find_with_deadline_from(&text, &[1], Instant::now(), resume_state) {
+///         Ok(found) => { assert_eq!(found, None); break; }
+///         Err(timed_out) => resume_state = timed_out.resume_state,
+///     }
+/// }
+/// ```
+pub fn find_with_deadline_from<T: Eq>(text: &[T], pattern: &[T], deadline: Instant,
+                                      resume_state: ResumeState)
+    -> Result<Option<usize>, TimedOut>
+{
+    let finder = Finder::new(pattern);
+    let info = finder.info();
+    let pattern = finder.as_pattern(&info);
+    let m = info.len();
+    let ResumeState { mut state, mut searched } = resume_state;
+    loop {
+        // Search the matches that start in the next block; the search of
+        // the prefix of the text is resumed when the prefix grows.
+        searched = min(searched.max(m) + CHECK_INTERVAL, text.len());
+        if let Some(i) = pattern.search_from(&text[..searched], &mut state) {
+            return Ok(Some(i));
+        }
+        if searched == text.len() {
+            return Ok(None);
+        }
+        if Instant::now() >= deadline {
+            return Err(TimedOut { resume_state: ResumeState { state, searched } });
+        }
+    }
+}
+
+#[test]
+fn test_find_with_deadline() {
+    use std::time::Duration;
+
+    let later = Instant::now() + Duration::from_secs(3600);
+    let text = "abaababaab".repeat(3 * CHECK_INTERVAL / 10) + "abaabaab" + "aaabaaabaaabaabbbb";
+    let text = text.as_bytes();
+    // the last pattern has a nonempty u
+    for pattern in &[&b"abaabaab"[..], b"ab", b"", b"abc", b"aaabaaabaaabaabbbb", text] {
+        let expected = ::gs_find(text, *pattern);
+        assert_eq!(find_with_deadline(text, pattern, later), Ok(expected));
+
+        // With a passed deadline, resume until the search is done
+        let mut resume_state = ResumeState::new();
+        let mut timeouts = 0;
+        let found = loop {
+            match find_with_deadline_from(text, pattern, Instant::now(), resume_state) {
+                Ok(found) => break found,
+                Err(timed_out) => {
+                    assert!(timed_out.resume_state.searched_len() >
+                            resume_state.searched_len());
+                    resume_state = timed_out.resume_state;
+                    timeouts += 1;
+                }
+            }
+        };
+        assert_eq!(found, expected, "{}", ::Bytestring(pattern));
+        if expected.is_none_or(|i| i > CHECK_INTERVAL) {
+            assert!(timeouts > 0);
+        }
+    }
+    assert_eq!(find_with_deadline(b"", b"", Instant::now()), Ok(Some(0)));
+    assert_eq!(find_with_deadline(b"ab", b"abc", Instant::now()), Ok(None));
+}
@@ -0,0 +1,123 @@
+
+use haystack::{gs_find_in, Haystack};
+
+/// Numbers whose differences can be compared for equality.
+pub trait Delta {
+    /// The difference type
+    type Output: Eq;
+
+    /// Return the difference `next - self`.
+    fn delta(&self, next: &Self) -> Self::Output;
+}
+
+macro_rules! int_delta {
+    ($($t:ty)*) => {
+        $(
+            impl Delta for $t {
+                type Output = $t;
+
+                fn delta(&self, next: &$t) -> $t {
+                    next.wrapping_sub(*self)
+                }
+            }
+        )*
+    }
+}
+
+int_delta!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+/// Floating point differences are compared by their bit patterns.
+impl Delta for f32 {
+    type Output = u32;
+
+    fn delta(&self, next: &f32) -> u32 {
+        (next - self).to_bits()
+    }
+}
+
+/// Floating point differences are compared by their bit patterns.
+impl Delta for f64 {
+    type Output = u64;
+
+    fn delta(&self, next: &f64) -> u64 {
+        (next - self).to_bits()
+    }
+}
+
+/// A view of the differences between consecutive elements of `values`,
+/// which does not allocate.
+///
+/// Element `i` of the view is `values[i + 1] - values[i]`, so the view is
+/// one element shorter than `values`.
+#[derive(Debug)]
+pub struct Deltas<'a, T: 'a> {
+    values: &'a [T],
+}
+
+impl<'a, T> Copy for Deltas<'a, T> { }
+impl<'a, T> Clone for Deltas<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Deltas<'a, T> {
+    /// Create a view of the differences of `values`.
+    pub fn new(values: &'a [T]) -> Self {
+        Deltas { values }
+    }
+}
+
+impl<'a, T: Delta> Haystack for Deltas<'a, T> {
+    type Item = T::Output;
+
+    fn len(&self) -> usize {
+        self.values.len().saturating_sub(1)
+    }
+
+    fn get(&self, index: usize) -> T::Output {
+        self.values[index].delta(&self.values[index + 1])
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> T::Output {
+        get!(self.values, index).delta(get!(self.values, index + 1))
+    }
+}
+
+/// Find the first position where `pattern` occurs in `text` up to a constant
+/// offset, that is, where `text[i + j] - pattern[j]` is the same for all
+/// `j`.
+///
+/// This searches the differences of consecutive elements using `Deltas`,
+/// in **O(n)** time and **O(1)** space. Integer differences wrap around on
+/// overflow; floating point differences must be exactly equal.
+///
+/// ```
+/// use galil_seiferas::find_offset_invariant;
+///
+/// let readings = [3, 5, 4, 20, 22, 21, 8];
+/// assert_eq!(find_offset_invariant(&readings, &[100, 102, 101]), Some(0));
+/// assert_eq!(find_offset_invariant(&readings[1..], &[100, 102, 101]), Some(2));
+/// ```
+pub fn find_offset_invariant<T: Delta>(text: &[T], pattern: &[T]) -> Option<usize> {
+    if pattern.len() <= 1 {
+        return if text.len() >= pattern.len() { Some(0) } else { None };
+    }
+    gs_find_in(Deltas::new(text), Deltas::new(pattern))
+}
+
+#[test]
+fn test_find_offset_invariant() {
+    let text = [1i64, 4, 2, 2, 7, -3, 0, 5, 5, 10, 0];
+    assert_eq!(find_offset_invariant(&text, &[0, 5, -5]), Some(3));
+    assert_eq!(find_offset_invariant(&text, &[100, 105, 105, 110]), Some(6));
+    assert_eq!(find_offset_invariant(&text, &[0, 0, 0]), None);
+    assert_eq!(find_offset_invariant(&text, &[42]), Some(0));
+    assert_eq!(find_offset_invariant(&text[..0], &[42]), None);
+    assert_eq!(find_offset_invariant(&text[..0], &[]), Some(0));
+
+    let text = [i64::MIN, i64::MAX, 0];
+    assert_eq!(find_offset_invariant(&text, &[0, -1]), Some(0));
+
+    let text = [0.5f64, 1.5, 1.0, 3.0, 2.5];
+    assert_eq!(find_offset_invariant(&text, &[10.0, 9.5]), Some(1));
+    assert_eq!(Deltas::new(&text).len(), 4);
+}
@@ -0,0 +1,109 @@
+
+use std::iter::FusedIterator;
+
+use finder::Finder;
+
+/// A string matching algorithm with a preprocessed pattern.
+///
+/// The pattern is preprocessed when the engine is created, by each engine's
+/// own constructor; the engine can then search any number of texts. This
+/// trait is implemented by `Finder`, the Galil-Seiferas engine, and it lets
+/// other algorithms be used and compared through the same interface.
+///
+/// ```
+/// use galil_seiferas::{Finder, SearchEngine};
+///
+/// fn count<E: SearchEngine<u8>>(engine: E, text: &[u8]) -> usize {
+///     engine.find_iter(text).count()
+/// }
+///
+/// let engine = Finder::new(b"ab");
+/// assert_eq!(count(&engine, b"abcabcab"), 3);
+/// ```
+pub trait SearchEngine<T> {
+    /// The pattern
+    fn pattern(&self) -> &[T];
+
+    /// Return the start of the first match in `text` that starts at or
+    /// after `start`, if any.
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize>;
+
+    /// Return the start of the first match in `text`, if any.
+    fn find(&self, text: &[T]) -> Option<usize> {
+        self.find_at(text, 0)
+    }
+
+    /// Return an iterator of the starts of the non-overlapping matches in
+    /// `text`.
+    ///
+    /// For a trait object `engine: &dyn SearchEngine<T>`, call this as
+    /// `(&engine).find_iter(text)`.
+    fn find_iter<'e, 't>(&'e self, text: &'t [T]) -> EngineMatches<'e, 't, T, Self>
+        where Self: Sized,
+    {
+        EngineMatches { engine: self, text, pos: 0 }
+    }
+}
+
+impl<T, E: SearchEngine<T> + ?Sized> SearchEngine<T> for &E {
+    fn pattern(&self) -> &[T] {
+        (**self).pattern()
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        (**self).find_at(text, start)
+    }
+}
+
+impl<'a, T: Eq> SearchEngine<T> for Finder<'a, T> {
+    fn pattern(&self) -> &[T] {
+        Finder::pattern(self)
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        let rest = text.get(start..)?;
+        Finder::find(self, rest).map(|m| start + m.start())
+    }
+}
+
+/// An iterator of the starts of the non-overlapping matches of an engine,
+/// created by `SearchEngine::find_iter`.
+pub struct EngineMatches<'e, 't, T: 't, E: 'e + ?Sized> {
+    engine: &'e E,
+    text: &'t [T],
+    /// The position to search from
+    pos: usize,
+}
+
+impl<'e, 't, T, E: SearchEngine<T> + ?Sized> Iterator for EngineMatches<'e, 't, T, E> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let start = self.engine.find_at(self.text, self.pos)?;
+        // An empty pattern matches at each position
+        self.pos = start + self.engine.pattern().len().max(1);
+        Some(start)
+    }
+}
+
+impl<'e, 't, T, E: SearchEngine<T> + ?Sized> FusedIterator for EngineMatches<'e, 't, T, E> { }
+
+impl<'e, 't, T, E: ?Sized> Clone for EngineMatches<'e, 't, T, E> {
+    fn clone(&self) -> Self {
+        EngineMatches { engine: self.engine, text: self.text, pos: self.pos }
+    }
+}
+
+#[test]
+fn test_search_engine() {
+    let engine = Finder::new(b"aba");
+    let text = b"abababa.aba";
+    assert_eq!(engine.find_at(text, 0), Some(0));
+    assert_eq!(engine.find_at(text, 1), Some(2));
+    assert_eq!(engine.find_at(text, 9), None);
+    assert_eq!(engine.find_at(text, 12), None);
+    assert_eq!(SearchEngine::find_iter(&engine, text).collect::<Vec<_>>(), vec![0, 4, 8]);
+
+    let engine: &dyn SearchEngine<u8> = &Finder::new(b"");
+    assert_eq!((&engine).find_iter(b"ab").collect::<Vec<_>>(), vec![0, 1, 2]);
+}
@@ -0,0 +1,57 @@
+
+use std::io;
+use std::path::Path;
+
+use alloc::vec::Vec;
+
+use rayon::iter::{ParallelBridge, ParallelIterator};
+
+use finder::Finder;
+use io::search_file;
+
+/// Search the files in `paths` in parallel, using the rayon thread pool.
+///
+/// For each file, `callback` is called with the path and the offsets of the
+/// matches, or the error that stopped the search of that file. The callback
+/// is called from the worker threads, in no particular order.
+///
+/// ```no_run
+/// use std::sync::Mutex;
+/// use galil_seiferas::{search_files, Finder};
+///
+/// let finder = Finder::new(b"TODO");
+/// let found = Mutex::new(Vec::new());
+/// search_files(&finder, &["src/lib.rs", "src/iter.rs"], |path, result| {
+///     if let Ok(offsets) = result {
+///         found.lock().unwrap().push((path.to_owned(), offsets));
+///     }
+/// });
+/// ```
+pub fn search_files<I, F>(finder: &Finder<u8>, paths: I, callback: F)
+    where I: IntoIterator,
+          I::IntoIter: Send,
+          I::Item: AsRef<Path> + Send,
+          F: Fn(&Path, io::Result<Vec<u64>>) + Sync + Send,
+{
+    paths.into_iter().par_bridge().for_each(|path| {
+        let path = path.as_ref();
+        callback(path, search_file(finder, path));
+    });
+}
+
+#[test]
+fn test_search_files() {
+    use std::sync::Mutex;
+
+    let finder = Finder::new(b"search_files");
+    let results = Mutex::new(Vec::new());
+    let paths = [file!(), "does-not-exist.rs"];
+    search_files(&finder, &paths, |path, result| {
+        results.lock().unwrap().push((path.to_owned(), result.map(|offsets| offsets.len())));
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|result| result.1.is_ok());
+    assert!(results[0].1.is_err());
+    assert_eq!(results[1].0, Path::new(file!()));
+    assert!(results[1].1.as_ref().unwrap() > &3);
+}
@@ -0,0 +1,124 @@
+
+use std::cell::Cell;
+use std::ops::Range;
+
+use haystack::{gs_find_in_by, Haystack};
+
+/// A view of a slice without the elements for which `skip` returns true.
+///
+/// The view does not allocate; it keeps a cursor that maps its indices to
+/// indices in the slice, and walks the slice from the last accessed element.
+/// The searches only move back a short distance, at most the pattern length
+/// in kept elements, so searching the view is efficient when the runs of
+/// skipped elements are short.
+///
+/// Search the view with `gs_find_in` using a reference to it, `&Filtered`,
+/// and use `original_index` to map a match position back to the slice.
+pub struct Filtered<'a, T: 'a, F> {
+    items: &'a [T],
+    skip: F,
+    len: usize,
+    /// An index in the view and the corresponding index in `items`
+    cursor: Cell<(usize, usize)>,
+}
+
+impl<'a, T, F> Filtered<'a, T, F>
+    where F: Fn(&T) -> bool,
+{
+    /// Create a view of `items` without the elements for which `skip`
+    /// returns true.
+    ///
+    /// This computes the length of the view in one pass over `items`.
+    pub fn new(items: &'a [T], skip: F) -> Self {
+        let len = items.iter().filter(|elt| !skip(elt)).count();
+        let first = items.iter().position(|elt| !skip(elt)).unwrap_or(0);
+        Filtered {
+            items,
+            skip,
+            len,
+            cursor: Cell::new((0, first)),
+        }
+    }
+
+    /// Return the index in the slice of the element at `index` in the view.
+    ///
+    /// **Panics** if `index` is out of bounds.
+    pub fn original_index(&self, index: usize) -> usize {
+        assert!(index < self.len, "Filtered: index {} out of bounds", index);
+        let (mut i, mut j) = self.cursor.get();
+        while i < index {
+            j += 1;
+            if !(self.skip)(&self.items[j]) {
+                i += 1;
+            }
+        }
+        while i > index {
+            j -= 1;
+            if !(self.skip)(&self.items[j]) {
+                i -= 1;
+            }
+        }
+        self.cursor.set((i, j));
+        j
+    }
+}
+
+impl<'a, T, F> Haystack for &Filtered<'a, T, F>
+    where F: Fn(&T) -> bool,
+{
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        &self.items[self.original_index(index)]
+    }
+}
+
+/// Find the first match of `pattern` in `text`, as if the elements of `text`
+/// for which `skip` returns true were absent; return the range of the match
+/// in `text`, which includes any skipped elements inside the match.
+///
+/// See `Filtered` for the complexity.
+///
+/// ```
+/// use galil_seiferas::gs_find_filtered;
+///
+/// let text = b"de_ad_be_ef";
+/// assert_eq!(gs_find_filtered(text, b"adbe", |&b| b == b'_'), Some(3..8));
+/// ```
+pub fn gs_find_filtered<T, F>(text: &[T], pattern: &[T], skip: F) -> Option<Range<usize>>
+    where T: Eq,
+          F: Fn(&T) -> bool,
+{
+    let filtered = Filtered::new(text, skip);
+    let start = gs_find_in_by(&filtered, pattern, T::eq)?;
+    if pattern.is_empty() {
+        let original = if start < filtered.len { filtered.original_index(start) } else { text.len() };
+        return Some(original..original);
+    }
+    let end = filtered.original_index(start + pattern.len() - 1) + 1;
+    Some(filtered.original_index(start)..end)
+}
+
+#[test]
+fn test_gs_find_filtered() {
+    let is_space = |&b: &u8| b == b' ';
+    assert_eq!(gs_find_filtered(b"  a b  ab c", b"abc", is_space), Some(7..11));
+    assert_eq!(gs_find_filtered(b"  a b  ab c", b"ab", is_space), Some(2..5));
+    assert_eq!(gs_find_filtered(b"  a b  ab c", b"bab", is_space), Some(4..9));
+    assert_eq!(gs_find_filtered(b"  a b  ab c", b"", is_space), Some(2..2));
+    assert_eq!(gs_find_filtered(b"   ", b"", is_space), Some(3..3));
+    assert_eq!(gs_find_filtered(b"   ", b"a", is_space), None);
+    assert_eq!(gs_find_filtered(b"a b", b"a b", is_space), None);
+
+    // Compare with searching a copy without the skipped elements
+    let text = b"aab.aa.ba.a.b.aab.aaab..aa.a.b.aa..ab";
+    let cleaned = text.iter().cloned().filter(|&b| b != b'.').collect::<Vec<_>>();
+    let filtered = Filtered::new(text, |&b: &u8| b == b'.');
+    for pattern in &[&b"aaba"[..], b"abaaab", b"aabaaab", b"baaa", b"abb"] {
+        assert_eq!(::gs_find_in(&filtered, *pattern), ::gs_find(&cleaned, *pattern));
+    }
+}
@@ -0,0 +1,526 @@
+
+use std::ops::Range;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "bytes")]
+use bytes::Bytes;
+
+use analysis::{analyze, analyze_slice, PatternInfo};
+#[cfg(feature = "bytes")]
+use bytes_ext::{BytesMatches, BytesSplit};
+use coverage::Coverage;
+use iter::{ContextMatches, MatchRuns, Matches, Options, Segments};
+use Pattern;
+
+/// A match of a pattern in a text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Match {
+    start: usize,
+    end: usize,
+    period: usize,
+}
+
+impl Match {
+    pub(crate) fn new(start: usize, len: usize, period: usize) -> Self {
+        Match { start, end: start + len, period }
+    }
+
+    /// The start offset of the match in the text
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The end offset (exclusive) of the match in the text
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The range of the match in the text
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The length of the match
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Return `true` if the match is empty
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The smallest period of the pattern that was matched.
+    ///
+    /// Another match can not start in `start() + 1 .. start() + period`.
+    pub fn pattern_period(&self) -> usize {
+        self.period
+    }
+}
+
+/// A preprocessed pattern that finds matches in texts.
+///
+/// Like `Pattern` this separates the pattern preprocessing from the search,
+/// and it also knows the pattern's period.
+///
+/// The finder either borrows its pattern or (with crate feature `alloc`)
+/// owns it; use `into_owned` for a finder that is not tied to a lifetime.
+///
+/// A finder can also be created in a constant expression with `new_const`,
+/// for example to make a table of finders in a static:
+///
+/// ```
+/// use galil_seiferas::Finder;
+///
+/// static FINDERS: [Finder<u8>; 2] = [
+///     Finder::new_const(b"\x7fELF"),
+///     Finder::new_const(b"MZ"),
+/// ];
+///
+/// assert_eq!(FINDERS[1].find(b"..MZ..").map(|m| m.start()), Some(2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Finder<'a, T: 'a> {
+    pattern: Storage<'a, T>,
+    /// The preprocessing; `None` if it is deferred to each search
+    info: Option<PatternInfo>,
+}
+
+/// The pattern of a `Finder`, borrowed or owned like a `Cow`.
+#[derive(Clone, Debug)]
+enum Storage<'a, T: 'a> {
+    Borrowed(&'a [T]),
+    #[cfg(feature = "alloc")]
+    Owned(Vec<T>),
+}
+
+impl<'a, T> Storage<'a, T> {
+    fn as_slice(&self) -> &[T] {
+        match *self {
+            Storage::Borrowed(slice) => slice,
+            #[cfg(feature = "alloc")]
+            Storage::Owned(ref vec) => vec,
+        }
+    }
+}
+
+impl<'a, T: Eq> Finder<'a, T> {
+    /// Preprocess the string `pattern` for searching.
+    pub fn new(pattern: &'a [T]) -> Self {
+        Finder {
+            pattern: Storage::Borrowed(pattern),
+            info: Some(analyze(pattern)),
+        }
+    }
+
+    /// Create a finder for `pattern` in a constant expression.
+    ///
+    /// The preprocessing can not be evaluated at compile time, so it is
+    /// instead deferred to each search (which is what `gs_find` does too).
+    /// Prefer `new` when the finder is created at runtime.
+    pub const fn new_const(pattern: &'a [T]) -> Self {
+        Finder {
+            pattern: Storage::Borrowed(pattern),
+            info: None,
+        }
+    }
+
+    /// The smallest period of the pattern.
+    pub fn pattern_period(&self) -> usize {
+        self.info().period()
+    }
+
+    /// The pattern
+    pub fn pattern(&self) -> &[T] {
+        self.pattern.as_slice()
+    }
+
+    /// Return `true` if the pattern is periodic, which means that its
+    /// smallest period fits at least twice in the pattern.
+    pub fn is_periodic(&self) -> bool {
+        self.info().is_periodic()
+    }
+
+    /// Return a finder for the part `range` of the pattern, for example a
+    /// prefix or a suffix of it.
+    ///
+    /// The preprocessing of this finder is reused where it is valid for the
+    /// part: the decomposition of the pattern stays valid for most prefixes
+    /// and suffixes, and only their periods are computed.
+    ///
+    /// **Panics** if `range` is out of bounds of the pattern.
+    ///
+    /// ```
+    /// use galil_seiferas::Finder;
+    ///
+    /// let finder = Finder::new(b"HTTP/1.1 200 OK");
+    /// let status_line = finder.slice(0..12);
+    /// assert_eq!(status_line.pattern(), b"HTTP/1.1 200");
+    /// assert_eq!(status_line.find(b"..HTTP/1.1 200 \r\n").map(|m| m.start()), Some(2));
+    /// ```
+    pub fn slice(&self, range: Range<usize>) -> Finder<'_, T> {
+        let pattern = self.pattern.as_slice();
+        Finder {
+            pattern: Storage::Borrowed(&pattern[range.clone()]),
+            info: self.info.map(|info| analyze_slice(pattern, &info, range)),
+        }
+    }
+
+    /// If a match exists where the pattern is a substring of `text`, return
+    /// the match inside `Some(_)`. If not, return `None`.
+    pub fn find(&self, text: &[T]) -> Option<Match> {
+        let info = self.info();
+        self.as_pattern(&info).find_in(text).map(|start| to_match(&info, start))
+    }
+
+    /// Return an iterator of the non-overlapping matches in `text`.
+    pub fn find_iter<'s, 't>(&'s self, text: &'t [T]) -> Matches<'s, 't, T> {
+        Matches::new(self, text, Options::default())
+    }
+
+    /// Return an iterator of all the matches in `text`, including overlapping
+    /// matches, grouped into runs of matches that are one pattern period
+    /// apart.
+    ///
+    /// This is useful for periodic patterns, where the number of matches can
+    /// be proportional to the length of the text.
+    pub fn find_runs<'s, 't>(&'s self, text: &'t [T]) -> MatchRuns<'s, 't, T> {
+        MatchRuns::new(self, text)
+    }
+
+    /// Return an iterator of the parts of `text` that are covered by a match,
+    /// including overlapping matches; see `gs_coverage`.
+    pub fn coverage<'s, 't>(&'s self, text: &'t [T]) -> Coverage<'s, 't, T> {
+        Coverage::from_finder(self, text)
+    }
+
+    /// Return an iterator that splits `text` into segments that are either
+    /// a (non-overlapping) match or a gap between matches.
+    ///
+    /// The segments cover the whole text in order; gaps are never empty.
+    ///
+    /// ```
+    /// use galil_seiferas::{Finder, Segment};
+    ///
+    /// let finder = Finder::new(b"ab");
+    /// let segments = finder.segments(b"xabababy").collect::<Vec<_>>();
+    /// assert_eq!(segments, [Segment::Gap(b"x"), Segment::Match(b"ab"),
+    ///                       Segment::Match(b"ab"), Segment::Match(b"ab"),
+    ///                       Segment::Gap(b"y")]);
+    /// ```
+    pub fn segments<'s, 't>(&'s self, text: &'t [T]) -> Segments<'s, 't, T> {
+        Segments::new(self, text)
+    }
+
+    /// Return an iterator of the matches in `text`, each with up to `before`
+    /// elements of leading and `after` elements of trailing context.
+    ///
+    /// The context is clamped at the start and end of the text.
+    ///
+    /// ```
+    /// use galil_seiferas::Finder;
+    ///
+    /// let finder = Finder::new(b"needle");
+    /// let text = b"hay needle hay";
+    /// let found = finder.find_iter_with_context(text, 2, 10).next().unwrap();
+    /// assert_eq!(found.before(), b"y ");
+    /// assert_eq!(found.matched(), b"needle");
+    /// assert_eq!(found.after(), b" hay");
+    /// ```
+    pub fn find_iter_with_context<'s, 't>(&'s self, text: &'t [T], before: usize, after: usize)
+        -> ContextMatches<'s, 't, T>
+    {
+        ContextMatches::new(self, text, before, after)
+    }
+
+    /// Return the preprocessing of the pattern, computing it if it is deferred
+    pub(crate) fn info(&self) -> PatternInfo {
+        match self.info {
+            Some(info) => info,
+            None => analyze(self.pattern.as_slice()),
+        }
+    }
+
+    pub(crate) fn as_pattern(&self, info: &PatternInfo) -> Pattern<'_, T, fn(&T, &T) -> bool> {
+        let (u, v) = self.pattern.as_slice().split_at(info.u_len());
+        Pattern { u, v, hrp1: info.hrp(), equal: T::eq }
+    }
+}
+
+pub(crate) fn to_match(info: &PatternInfo, start: usize) -> Match {
+    Match::new(start, info.len(), info.period())
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq> Finder<'static, T> {
+    /// Preprocess the string `pattern` for searching; the finder takes
+    /// ownership of the pattern.
+    pub fn from_vec(pattern: Vec<T>) -> Self {
+        let info = analyze(&pattern);
+        Finder {
+            pattern: Storage::Owned(pattern),
+            info: Some(info),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Clone> Finder<'a, T> {
+    /// Return a finder that owns its pattern, copying the pattern if it
+    /// is borrowed. The preprocessing is not repeated.
+    pub fn into_owned(self) -> Finder<'static, T> {
+        let pattern = match self.pattern {
+            Storage::Borrowed(slice) => slice.to_vec(),
+            Storage::Owned(vec) => vec,
+        };
+        Finder {
+            pattern: Storage::Owned(pattern),
+            info: self.info,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Eq> Finder<'a, T> {
+    /// Return the start offsets of the non-overlapping matches in `text`.
+    ///
+    /// ```
+    /// use galil_seiferas::Finder;
+    ///
+    /// assert_eq!(Finder::new(b"aa").find_all(b"aaaaa"), [0, 2]);
+    /// ```
+    pub fn find_all(&self, text: &[T]) -> Vec<usize> {
+        self.find_iter(text).map(|m| m.start()).collect()
+    }
+
+    /// Split `text` at the non-overlapping matches, and return the parts
+    /// between the matches.
+    ///
+    /// Like `str::split`, there is one more part than there are matches,
+    /// and parts can be empty.
+    ///
+    /// ```
+    /// use galil_seiferas::Finder;
+    ///
+    /// let parts = Finder::new(b", ").split_to_vec(b"a, b, , c");
+    /// assert_eq!(parts, [&b"a"[..], b"b", b"", b"c"]);
+    /// ```
+    pub fn split_to_vec<'t>(&self, text: &'t [T]) -> Vec<&'t [T]> {
+        let mut parts = Vec::new();
+        let mut last = 0;
+        for m in self.find_iter(text) {
+            parts.push(&text[last..m.start()]);
+            last = m.end();
+        }
+        parts.push(&text[last..]);
+        parts
+    }
+
+    /// Return a copy of `text` where each non-overlapping match is replaced
+    /// by `replacement`.
+    ///
+    /// ```
+    /// use galil_seiferas::Finder;
+    ///
+    /// let replaced = Finder::new(b"cat").replace(b"cats and cat", b"dog");
+    /// assert_eq!(replaced, b"dogs and dog");
+    /// ```
+    pub fn replace(&self, text: &[T], replacement: &[T]) -> Vec<T>
+        where T: Clone,
+    {
+        let mut result = Vec::with_capacity(text.len());
+        let mut last = 0;
+        for m in self.find_iter(text) {
+            result.extend_from_slice(&text[last..m.start()]);
+            result.extend_from_slice(replacement);
+            last = m.end();
+        }
+        result.extend_from_slice(&text[last..]);
+        result
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a> Finder<'a, u8> {
+    /// Return the first match in `text` as a slice of the text, which shares
+    /// its buffer.
+    ///
+    /// The text is anything that converts to `Bytes` without copying, like
+    /// `Bytes` itself, a frozen `BytesMut` or a `Vec<u8>`.
+    ///
+    /// ```
+    /// extern crate bytes;
+    /// extern crate galil_seiferas;
+    ///
+    /// use bytes::Bytes;
+    /// use galil_seiferas::Finder;
+    ///
+    /// let text = Bytes::from_static(b"GET / HTTP/1.1");
+    /// let version = Finder::new(b"HTTP/").find_bytes(text.clone()).unwrap();
+    /// assert_eq!(version, &b"HTTP/"[..]);
+    /// ```
+    pub fn find_bytes<B: Into<Bytes>>(&self, text: B) -> Option<Bytes> {
+        self.find_iter_bytes(text).next()
+    }
+
+    /// Return an iterator of the non-overlapping matches in `text`, as
+    /// slices of the text; see `find_bytes`.
+    pub fn find_iter_bytes<'s, B: Into<Bytes>>(&'s self, text: B) -> BytesMatches<'s, 'a> {
+        BytesMatches::new(self, text.into())
+    }
+
+    /// Return an iterator that splits `text` at the non-overlapping matches,
+    /// of the parts between the matches, as slices of the text.
+    ///
+    /// Like `split_to_vec`, there is one more part than there are matches,
+    /// and parts can be empty.
+    ///
+    /// ```
+    /// extern crate bytes;
+    /// extern crate galil_seiferas;
+    ///
+    /// use bytes::BytesMut;
+    /// use galil_seiferas::Finder;
+    ///
+    /// let mut buffer = BytesMut::new();
+    /// buffer.extend_from_slice(b"key: value");
+    /// let finder = Finder::new(b": ");
+    /// let mut parts = finder.split_bytes(buffer.freeze());
+    /// assert_eq!(parts.next().unwrap(), &b"key"[..]);
+    /// assert_eq!(parts.next().unwrap(), &b"value"[..]);
+    /// assert_eq!(parts.next(), None);
+    /// ```
+    pub fn split_bytes<'s, B: Into<Bytes>>(&'s self, text: B) -> BytesSplit<'s, 'a> {
+        BytesSplit::new(self, text.into())
+    }
+}
+
+#[test]
+fn test_finder() {
+    let finder = Finder::new(b"abcab");
+    assert_eq!(finder.pattern_period(), 3);
+    let m = finder.find(b"xxabcabcab").unwrap();
+    assert_eq!(m.range(), 2..7);
+    assert_eq!(m.len(), 5);
+    assert_eq!(m.pattern_period(), 3);
+    assert_eq!(finder.find(b"abcac"), None);
+
+    let finder = Finder::new(b"");
+    assert_eq!(finder.find(b"abc").map(|m| m.range()), Some(0..0));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_finder_into_owned() {
+    let finder = {
+        let pattern = String::from("abab");
+        Finder::new(pattern.as_bytes()).into_owned()
+    };
+    let handle = ::std::thread::spawn(move || finder.find(b"aababab").map(|m| m.range()));
+    assert_eq!(handle.join().unwrap(), Some(1..5));
+
+    let finder = Finder::from_vec(vec![1, 2, 1]);
+    assert_eq!(finder.pattern_period(), 2);
+    assert_eq!(finder.into_owned().find(&[1, 2, 1, 2, 1]).map(|m| m.start()), Some(0));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_finder_collect() {
+    let finder = Finder::new("aba".as_bytes());
+    assert_eq!(finder.find_all(b"abababa.aba"), [0, 4, 8]);
+    assert_eq!(finder.split_to_vec(b"abababa.aba"), [&b""[..], b"b", b".", b""]);
+    assert_eq!(finder.replace(b"abababa.aba", b"X"), b"XbX.X");
+
+    // Like str::split and str::replace
+    let text = "xyz";
+    let empty = Finder::new(&[]);
+    let parts = empty.split_to_vec(text.as_bytes());
+    assert_eq!(parts, text.split("").map(str::as_bytes).collect::<Vec<_>>());
+    assert_eq!(empty.replace(text.as_bytes(), b"-"), text.replace("", "-").as_bytes());
+    assert_eq!(empty.find_all(b"ab"), [0, 1, 2]);
+    assert_eq!(finder.split_to_vec(b""), [&b""[..]]);
+}
+
+#[test]
+fn test_finder_const() {
+    static FINDER: Finder<u8> = Finder::new_const(b"aab");
+    const FINDERS: &[Finder<i32>] = &[Finder::new_const(&[1, 2]), Finder::new_const(&[])];
+    assert_eq!(FINDER.pattern_period(), 3);
+    assert_eq!(FINDER.find(b"aaaab").map(|m| m.range()), Some(2..5));
+    assert_eq!(FINDERS[0].find(&[2, 1, 2]).map(|m| m.start()), Some(1));
+    assert_eq!(FINDERS[1].find(&[2, 1, 2]).map(|m| m.start()), Some(0));
+}
+
+#[test]
+fn test_finder_accessors() {
+    let finder = Finder::new(b"abcabcab");
+    assert_eq!(finder.pattern(), b"abcabcab");
+    assert_eq!(finder.pattern_period(), 3);
+    assert!(finder.is_periodic());
+    let finder2 = finder.clone();
+    assert_eq!(finder2.pattern(), finder.pattern());
+    assert!(!Finder::new(b"abcab").is_periodic());
+    assert!(!Finder::new_const(b"").is_periodic());
+}
+
+#[test]
+fn test_finder_send_sync() {
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() { }
+    assert_send_sync::<Finder<u8>>();
+    assert_send_sync::<Finder<'static, &str>>();
+    assert_send_sync::<Match>();
+
+    let finder = Arc::new(Finder::new(b"ab"));
+    let threads = (0..4).map(|i| {
+        let finder = finder.clone();
+        thread::spawn(move || finder.find(&b"xxxxab"[i..]).map(|m| m.start()))
+    }).collect::<Vec<_>>();
+    for (i, t) in threads.into_iter().enumerate() {
+        assert_eq!(t.join().unwrap(), Some(4 - i));
+    }
+}
+
+#[test]
+#[cfg(feature = "defmt")]
+fn test_defmt_format() {
+    fn assert_format<T: defmt::Format>() { }
+    assert_format::<Match>();
+    assert_format::<PatternInfo>();
+    assert_format::<::Hrp>();
+    assert_format::<::DecompositionError>();
+    assert_format::<::SearchPoll>();
+    assert_format::<::OffsetTracker>();
+}
+
+#[test]
+fn test_finder_slice() {
+    use verify::verify_decomposition;
+
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbbaaabaaabaaabaabbbb";
+    // the last patterns have a nonempty u
+    let patterns = [&b"abaababaab"[..], b"aaaaaaab", b"aaabaaabaaabaabbbb", b"abcabcabcabd"];
+    for pattern in &patterns {
+        let finder = Finder::new(pattern);
+        let m = pattern.len();
+        let prefixes = (0..m + 1).map(|i| 0..i);
+        let suffixes = (0..m + 1).map(|i| i..m);
+        for range in prefixes.chain(suffixes).chain(Some(1..m - 1)) {
+            let slice = finder.slice(range.clone());
+            let info = slice.info();
+            assert_eq!(slice.pattern(), &pattern[range.clone()]);
+            assert_eq!(info.period(), analyze(slice.pattern()).period());
+            let (u, v) = slice.pattern().split_at(info.u_len());
+            assert_eq!(verify_decomposition(::GS_K, u, v), Ok(()));
+            let expected = Finder::new(slice.pattern()).find_iter(text).collect::<Vec<_>>();
+            assert_eq!(slice.find_iter(text).collect::<Vec<_>>(), expected,
+                       "{} {:?}", ::Bytestring(pattern), range);
+        }
+    }
+}
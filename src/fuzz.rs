@@ -0,0 +1,119 @@
+//! Fuzzing with `arbitrary`: `Arbitrary` implementations for the finder and
+//! for structured test cases.
+//!
+//! With these, a `cargo fuzz` target can take a `Finder` or a `SearchCase`
+//! directly, instead of splitting the raw input into a text and a pattern.
+//!
+//! ```
+//! extern crate arbitrary;
+//! extern crate galil_seiferas;
+//!
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use galil_seiferas::Finder;
+//!
+//! # fn main() {
+//! // The body of a fuzz target
+//! let data = b"abcab";
+//! let finder = Finder::arbitrary_take_rest(Unstructured::new(data)).unwrap();
+//! assert_eq!(finder.find(b"..abcab").map(|m| m.start()), Some(2));
+//! # }
+//! ```
+//!
+//! This module needs the `arbitrary` feature; `SearchCase` also needs the
+//! `alloc` feature.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use finder::Finder;
+
+/// The finder of a pattern that is borrowed from the fuzzer's input.
+impl<'a> Arbitrary<'a> for Finder<'a, u8> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Finder::new(u.arbitrary()?))
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        Ok(Finder::new(<&'a [u8]>::arbitrary_take_rest(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&'a [u8]>::size_hint(depth)
+    }
+}
+
+/// A text, a pattern, and the position of the first match of the pattern in
+/// the text.
+///
+/// The arbitrary cases are made from an arbitrary text and pattern, and in
+/// about half of them the pattern is inserted at an arbitrary position of
+/// the text, so that many of them have matches. The expected position is
+/// computed with a simple quadratic search.
+///
+/// ```
+/// extern crate arbitrary;
+/// extern crate galil_seiferas;
+///
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use galil_seiferas::fuzz::SearchCase;
+/// use galil_seiferas::gs_find;
+///
+/// # fn main() {
+/// // The body of a fuzz target
+/// let data = b"\x03abc\x01b\x01";
+/// let case = SearchCase::<u8>::arbitrary(&mut Unstructured::new(data)).unwrap();
+/// assert_eq!(gs_find(&case.text, &case.pattern), case.expected);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchCase<T> {
+    /// The text
+    pub text: Vec<T>,
+    /// The pattern
+    pub pattern: Vec<T>,
+    /// The position of the first match, if any
+    pub expected: Option<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Arbitrary<'a> + Clone + Eq> Arbitrary<'a> for SearchCase<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut text: Vec<T> = u.arbitrary()?;
+        let pattern: Vec<T> = u.arbitrary()?;
+        if u.arbitrary()? {
+            let i = u.int_in_range(0..=text.len())?;
+            text.splice(i..i, pattern.iter().cloned());
+        }
+        let expected = (0..text.len() + 1).find(|&i| text[i..].starts_with(&pattern));
+        Ok(SearchCase { text, pattern, expected })
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_search_case() {
+    let data = (0..4096u32).map(|i| (i.wrapping_mul(2654435761) >> 29) as u8)
+                           .collect::<Vec<_>>();
+    let mut u = Unstructured::new(&data);
+    let mut found = 0;
+    while !u.is_empty() {
+        let case = SearchCase::<bool>::arbitrary(&mut u).unwrap();
+        assert_eq!(::gs_find(&case.text, &case.pattern), case.expected);
+        found += case.expected.is_some() as usize;
+    }
+    assert!(found > 0);
+}
+
+#[test]
+fn test_arbitrary_finder() {
+    let finder = Finder::arbitrary_take_rest(Unstructured::new(b"abab")).unwrap();
+    assert_eq!(finder.pattern(), b"abab");
+    assert_eq!(finder.pattern_period(), 2);
+    let mut u = Unstructured::new(b"abcabc\x03");
+    let finder = Finder::arbitrary(&mut u).unwrap();
+    assert!(b"abcabc".starts_with(finder.pattern()));
+    assert_eq!(finder.find(b"abcabc").map(|m| m.start()), Some(0));
+}
@@ -0,0 +1,191 @@
+
+use std::ops::Range;
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use finder::Finder;
+use iter::{Matches, Options};
+
+/// A pattern of exact segments that are separated by gaps of bounded length,
+/// like `A .{0,4} B`.
+///
+/// ```
+/// use galil_seiferas::GappedPattern;
+///
+/// // "GET", then 1 to 16 bytes, then "HTTP/1.", then 1 byte, then "\r\n"
+/// let pattern = GappedPattern::new(b"GET")
+///     .followed_by(1, 16, b"HTTP/1.")
+///     .followed_by(1, 1, b"\r\n");
+/// assert_eq!(pattern.find(b"..GET /index.html HTTP/1.1\r\n"), Some(2..28));
+/// assert_eq!(pattern.find(b"..GET /a/very/long/path HTTP/1.1\r\n"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GappedPattern<'a, T: 'a> {
+    segments: Vec<Finder<'a, T>>,
+    /// The minimum and maximum gap before each segment but the first
+    gaps: Vec<(usize, usize)>,
+}
+
+impl<'a, T: Eq> GappedPattern<'a, T> {
+    /// Create a pattern that starts with the segment `first`.
+    ///
+    /// **Panics** if `first` is empty.
+    pub fn new(first: &'a [T]) -> Self {
+        assert!(!first.is_empty(), "GappedPattern: the segments must not be empty");
+        GappedPattern {
+            segments: ::alloc::vec![Finder::new(first)],
+            gaps: Vec::new(),
+        }
+    }
+
+    /// Add the segment `segment`, after a gap of `min_gap` to `max_gap`
+    /// (inclusive) elements.
+    ///
+    /// **Panics** if `segment` is empty or if `min_gap > max_gap`.
+    pub fn followed_by(mut self, min_gap: usize, max_gap: usize, segment: &'a [T]) -> Self {
+        assert!(!segment.is_empty(), "GappedPattern: the segments must not be empty");
+        assert!(min_gap <= max_gap, "GappedPattern: min_gap must not exceed max_gap");
+        self.segments.push(Finder::new(segment));
+        self.gaps.push((min_gap, max_gap));
+        self
+    }
+
+    /// Find the match that ends first in `text`, and among the matches that
+    /// end there, the one that starts first; return its range.
+    ///
+    /// The occurrences of all the segments are found in one forward pass, and
+    /// each occurrence is checked against the gap constraints as it is
+    /// found, using the reachable ends of the previous segment that are kept
+    /// in a sliding window. The time is O(n) per segment, and the memory is
+    /// O(max gap + segment length) per segment.
+    pub fn find(&self, text: &[T]) -> Option<Range<usize>> {
+        let options = Options { overlapping: true, ..Options::default() };
+        let mut states = self.segments.iter().map(|finder| {
+            let mut matches = Matches::new(finder, text, options);
+            SegmentState {
+                next: matches.next_start(),
+                matches,
+                pending: VecDeque::new(),
+                window: VecDeque::new(),
+            }
+        }).collect::<Vec<_>>();
+
+        loop {
+            // Take the occurrence that starts first; an occurrence only
+            // depends on occurrences of the previous segment that start
+            // before it.
+            let (start, k) = states.iter().enumerate()
+                .filter_map(|(k, state)| state.next.map(|start| (start, k)))
+                .min()?;
+            states[k].next = states[k].matches.next_start();
+            let chain_start = if k == 0 {
+                Some(start)
+            } else {
+                let state = &mut states[k];
+                state.advance(self.gaps[k - 1], start);
+                state.window.front().map(|&(_, chain_start)| chain_start)
+            };
+            if let Some(chain_start) = chain_start {
+                let end = start + self.segments[k].pattern().len();
+                if k + 1 == states.len() {
+                    return Some(chain_start..end);
+                }
+                let next = &mut states[k + 1];
+                if let Some(next_start) = next.next {
+                    next.pending.push_back((end, chain_start));
+                    next.advance(self.gaps[k], next_start);
+                }
+            }
+        }
+    }
+}
+
+/// The search state of one segment of a `GappedPattern`
+struct SegmentState<'p, 't, T: 'p + 't> {
+    matches: Matches<'p, 't, T>,
+    /// The start of the next occurrence of the segment
+    next: Option<usize>,
+    /// The reachable ends of the previous segment and their match starts,
+    /// that are too close to the next occurrence
+    pending: VecDeque<(usize, usize)>,
+    /// The reachable ends of the previous segment and their match starts,
+    /// that are within the gap of the next occurrence; the match starts are
+    /// increasing, so that the first is the smallest.
+    window: VecDeque<(usize, usize)>,
+}
+
+impl<'p, 't, T> SegmentState<'p, 't, T> {
+    /// Update the window for an occurrence at `start`; `start` must not
+    /// decrease between calls.
+    fn advance(&mut self, (min_gap, max_gap): (usize, usize), start: usize) {
+        while let Some(&(end, chain_start)) = self.pending.front() {
+            if end + min_gap > start {
+                break;
+            }
+            self.pending.pop_front();
+            while self.window.back().is_some_and(|&(_, s)| s >= chain_start) {
+                self.window.pop_back();
+            }
+            self.window.push_back((end, chain_start));
+        }
+        while self.window.front().is_some_and(|&(end, _)| end + max_gap < start) {
+            self.window.pop_front();
+        }
+    }
+}
+
+#[test]
+fn test_gapped_pattern() {
+    let pattern = GappedPattern::new(b"ab").followed_by(0, 2, b"cd");
+    assert_eq!(pattern.find(b"abcd"), Some(0..4));
+    assert_eq!(pattern.find(b"ab..cd"), Some(0..6));
+    assert_eq!(pattern.find(b"ab...cd"), None);
+    assert_eq!(pattern.find(b"ab...abcd"), Some(5..9));
+    assert_eq!(pattern.find(b"ab.ab.cd"), Some(3..8));
+    assert_eq!(pattern.find(b"cdab"), None);
+
+    // The middle segment must be chosen so that the last one is in reach
+    let pattern = GappedPattern::new(b"a").followed_by(1, 1, b"b").followed_by(3, 3, b"c");
+    assert_eq!(pattern.find(b"a.b.b...c"), None);
+    assert_eq!(pattern.find(b"a.b.b..c"), None);
+    assert_eq!(pattern.find(b"a.b...c"), Some(0..7));
+    assert_eq!(pattern.find(b"a.ba.b...c"), Some(3..10));
+    let pattern = GappedPattern::new(b"a").followed_by(0, 3, b"b").followed_by(3, 3, b"c");
+    assert_eq!(pattern.find(b"abb.b...c"), Some(0..9));
+
+    // Compare with a brute force search
+    let text = b"abaabbabaaabbbabababbaaabbabbbaaab";
+    let brute_force = |gaps: &[(usize, usize)], segments: &[&[u8]]| {
+        fn matches_from(text: &[u8], pos: usize, gaps: &[(usize, usize)],
+                        segments: &[&[u8]]) -> Option<usize> {
+            if !text[pos..].starts_with(segments[0]) {
+                return None;
+            }
+            let end = pos + segments[0].len();
+            if segments.len() == 1 {
+                return Some(end);
+            }
+            (gaps[0].0..gaps[0].1 + 1)
+                .filter(|&gap| end + gap <= text.len())
+                .filter_map(|gap| matches_from(text, end + gap, &gaps[1..], &segments[1..]))
+                .min()
+        }
+        (0..text.len())
+            .filter_map(|start| matches_from(text, start, gaps, segments).map(|end| start..end))
+            .min_by_key(|range| (range.end, range.start))
+    };
+    let segments: &[&[u8]] = &[b"ab", b"b", b"aa", b"bab", b"a"];
+    for &(min, max) in &[(0, 0), (0, 1), (1, 3), (2, 5), (4, 4)] {
+        for i in 0..segments.len() {
+            for j in 0..segments.len() {
+                let pattern = GappedPattern::new(segments[i])
+                    .followed_by(min, max, segments[j])
+                    .followed_by(max - min, max, segments[i]);
+                let gaps = [(min, max), (max - min, max)];
+                assert_eq!(pattern.find(text),
+                           brute_force(&gaps, &[segments[i], segments[j], segments[i]]));
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use analysis::PatternInfo;
+use finder::{to_match, Finder, Match};
+use SearchState;
+
+/// A finder for a buffer that the caller keeps appending to, which reports
+/// each match once, as soon as the buffer contains it.
+///
+/// The finder remembers how far the buffer was searched; each `poll`
+/// resumes the search there, so only the matches that end in the newly
+/// appended part are reported, and the whole buffer is searched in **O(n)**
+/// time in total, however it is split into appends. The matches do not
+/// overlap, like `Finder::find_iter`.
+///
+/// ```
+/// use galil_seiferas::GrowingBufferFinder;
+///
+/// let mut finder = GrowingBufferFinder::new(b"\r\n");
+/// let mut buffer = Vec::new();
+/// let mut lines = Vec::new();
+/// for chunk in &[&b"GET / HTTP/1.1\r"[..], b"\nHost: a", b"\r\n\r\n"] {
+///     buffer.extend_from_slice(chunk);
+///     while let Some(m) = finder.poll(&buffer) {
+///         lines.push(m.start());
+///     }
+/// }
+/// assert_eq!(lines, [14, 23, 25]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GrowingBufferFinder<'a, T: 'a> {
+    finder: Finder<'a, T>,
+    info: PatternInfo,
+    state: SearchState,
+    /// The length of the buffer at the last poll
+    len: usize,
+}
+
+impl<'a, T: Eq> GrowingBufferFinder<'a, T> {
+    /// Preprocess `pattern`, for an empty buffer.
+    pub fn new(pattern: &'a [T]) -> Self {
+        GrowingBufferFinder::from_finder(Finder::new(pattern))
+    }
+
+    /// Use the preprocessing of `finder`, for an empty buffer.
+    pub fn from_finder(finder: Finder<'a, T>) -> Self {
+        let info = finder.info();
+        GrowingBufferFinder { finder, info, state: SearchState::default(), len: 0 }
+    }
+
+    /// The pattern
+    pub fn pattern(&self) -> &[T] {
+        self.finder.pattern()
+    }
+
+    /// The length of the buffer at the last poll
+    pub fn searched_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the next match in `buffer`, if any.
+    ///
+    /// Call it until it returns `None`, then again after appending to the
+    /// buffer. The buffer must be the same buffer as in the previous polls,
+    /// with elements appended at the end; use `reset` when it is cleared.
+    ///
+    /// **Panics** if `buffer` is shorter than at the previous poll.
+    pub fn poll(&mut self, buffer: &[T]) -> Option<Match> {
+        assert!(buffer.len() >= self.len,
+                "GrowingBufferFinder::poll: the buffer shrank from length {} to {}",
+                self.len, buffer.len());
+        self.len = buffer.len();
+        let start = self.finder.as_pattern(&self.info).search_from(buffer, &mut self.state)?;
+        let end = start + self.info.len();
+        if self.state.pos < end {
+            self.state.restart(end);
+        }
+        Some(to_match(&self.info, start))
+    }
+
+    /// Start again, for an empty buffer.
+    pub fn reset(&mut self) {
+        self.state.restart(0);
+        self.len = 0;
+    }
+}
+
+#[test]
+fn test_growing_buffer_finder() {
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbbaaabaaabaaabaabbbb";
+    // the last pattern has a nonempty u
+    for pattern in &[&b"abaab"[..], b"aab", b"aabaab", b"baabaabaab", b"", b"aaabaaabaaabaabbbb"] {
+        let finder = Finder::new(pattern);
+        let expected = finder.find_iter(text).collect::<Vec<_>>();
+        for &step in &[1, 2, 3, 7, text.len()] {
+            let mut growing = GrowingBufferFinder::new(pattern);
+            let mut found = Vec::new();
+            let mut len = 0;
+            while len < text.len() {
+                len = (len + step).min(text.len());
+                let previous_len = growing.searched_len();
+                while let Some(m) = growing.poll(&text[..len]) {
+                    // Each match is reported as soon as it is in the buffer
+                    assert!(m.end() > previous_len || m.end() == 0);
+                    assert!(m.end() <= len);
+                    found.push(m);
+                }
+            }
+            assert_eq!(found, expected, "{} {}", ::Bytestring(pattern), step);
+            growing.reset();
+            assert_eq!(growing.poll(&text[..pattern.len()]).map(|m| m.start()),
+                       finder.find(&text[..pattern.len()]).map(|m| m.start()));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "the buffer shrank")]
+fn test_growing_buffer_finder_shrink() {
+    let mut growing = GrowingBufferFinder::new(b"ab");
+    growing.poll(b"xxx");
+    growing.poll(b"xx");
+}
@@ -0,0 +1,310 @@
+
+use std::borrow::Borrow;
+use std::ops::Range;
+
+use {decomposition_by, find_from_by, SearchState};
+
+/// A sequence of elements with random access, that can be searched.
+///
+/// This is implemented for slices and for views that compute their elements
+/// on the fly, for example `Deltas`. Search them with `gs_find_in`.
+///
+/// The elements are returned by value; for a slice, the element is a
+/// reference into the slice.
+pub trait Haystack {
+    /// The element type
+    type Item;
+
+    /// Return the number of elements
+    fn len(&self) -> usize;
+
+    /// Return true if there are no elements
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the element at `index`.
+    ///
+    /// The search algorithms only call this with `index < self.len()`; it
+    /// may panic otherwise.
+    fn get(&self, index: usize) -> Self::Item;
+
+    /// Return the element at `index` without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()`.
+    #[doc(hidden)]
+    unsafe fn get_unchecked(&self, index: usize) -> Self::Item {
+        self.get(index)
+    }
+
+    /// Hint that the elements in `range` will be accessed soon; indices out
+    /// of bounds are ignored.
+    #[doc(hidden)]
+    fn prefetch(&self, range: Range<usize>) {
+        let _ = range;
+    }
+}
+
+impl<'a, T> Haystack for &'a [T] {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        &self[index]
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> &'a T {
+        ::unchecked_index::get_unchecked(*self, index)
+    }
+
+    fn prefetch(&self, range: Range<usize>) {
+        prefetch_slice(self, range);
+    }
+}
+
+impl<'a, T, const N: usize> Haystack for &'a [T; N] {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        &self[index]
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> &'a T {
+        let slice: &'a [T] = *self;
+        Haystack::get_unchecked(&slice, index)
+    }
+
+    fn prefetch(&self, range: Range<usize>) {
+        prefetch_slice(*self, range);
+    }
+}
+
+/// Prefetch the cache lines of `slice[range]`, on targets where it is
+/// supported and with crate feature `prefetch`.
+fn prefetch_slice<T>(slice: &[T], range: Range<usize>) {
+    #[cfg(all(feature = "prefetch", target_arch = "x86_64"))]
+    {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        use std::mem::size_of;
+
+        /// The size of a cache line, in bytes (a common size)
+        const CACHE_LINE: usize = 64;
+
+        let end = range.end.min(slice.len());
+        if range.start >= end {
+            return;
+        }
+        let bytes = slice[range.start..end].as_ptr() as *const i8;
+        let len = (end - range.start) * size_of::<T>();
+        let mut offset = 0;
+        while offset < len {
+            // SSE is always available on x86-64, and the address is in
+            // the slice.
+            unsafe {
+                _mm_prefetch::<_MM_HINT_T0>(bytes.add(offset));
+            }
+            offset += CACHE_LINE;
+        }
+    }
+    #[cfg(not(all(feature = "prefetch", target_arch = "x86_64")))]
+    let _ = (slice, range);
+}
+
+/// A view of the part `start .. start + len` of a haystack.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Sub<H> {
+    hay: H,
+    start: usize,
+    len: usize,
+}
+
+/// Return a view of `hay[range]`.
+pub(crate) fn sub<H: Haystack>(hay: H, range: Range<usize>) -> Sub<H> {
+    debug_assert!(range.start <= range.end && range.end <= hay.len());
+    Sub { hay, start: range.start, len: range.end - range.start }
+}
+
+/// Return a view of `hay[start..]`.
+pub(crate) fn skip<H: Haystack>(hay: H, start: usize) -> Sub<H> {
+    let len = hay.len();
+    sub(hay, start..len)
+}
+
+impl<H: Haystack> Sub<H> {
+    /// Return a view of `self[range]`, of the same type.
+    pub(crate) fn narrow(self, range: Range<usize>) -> Self {
+        debug_assert!(range.start <= range.end && range.end <= self.len);
+        Sub { hay: self.hay, start: self.start + range.start, len: range.end - range.start }
+    }
+}
+
+impl<H: Haystack> Haystack for Sub<H> {
+    type Item = H::Item;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> H::Item {
+        assert!(index < self.len);
+        self.hay.get(self.start + index)
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> H::Item {
+        debug_assert!(index < self.len);
+        self.hay.get_unchecked(self.start + index)
+    }
+
+    fn prefetch(&self, range: Range<usize>) {
+        let end = range.end.min(self.len);
+        if range.start < end {
+            self.hay.prefetch(self.start + range.start..self.start + end);
+        }
+    }
+}
+
+/// A view of a slice in reverse order, that can be searched as a `Haystack`
+/// without making a reversed copy.
+///
+/// Index `i` of the view is index `len - 1 - i` of the slice; use
+/// `original_range` to map a match in the view back to the slice.
+///
+/// ```
+/// use galil_seiferas::{gs_find_in, Reversed};
+///
+/// let text = b"stressed";
+/// let view = Reversed::new(text);
+/// let found = gs_find_in(view, &b"dess"[..]).unwrap();
+/// assert_eq!(view.original_range(found, 4), 4..8);
+/// ```
+#[derive(Debug)]
+pub struct Reversed<'a, T: 'a> {
+    slice: &'a [T],
+}
+
+impl<'a, T> Reversed<'a, T> {
+    /// Create a reversed view of `slice`
+    pub fn new(slice: &'a [T]) -> Self {
+        Reversed { slice }
+    }
+
+    /// The slice, in its original order
+    pub fn as_slice(&self) -> &'a [T] {
+        self.slice
+    }
+
+    /// Return the range in the slice of the part of the view that starts at
+    /// `start` and is `len` long.
+    ///
+    /// **Panics** if the part is not inside the view.
+    pub fn original_range(&self, start: usize, len: usize) -> Range<usize> {
+        assert!(start <= self.slice.len() && len <= self.slice.len() - start,
+                "Reversed::original_range: {}..{} is out of bounds for length {}",
+                start, start.saturating_add(len), self.slice.len());
+        let end = self.slice.len() - start;
+        end - len..end
+    }
+}
+
+impl<'a, T> Copy for Reversed<'a, T> { }
+impl<'a, T> Clone for Reversed<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Haystack for Reversed<'a, T> {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        self.slice.len()
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        &self.slice[self.slice.len() - 1 - index]
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> &'a T {
+        debug_assert!(index < self.slice.len());
+        ::unchecked_index::get_unchecked(self.slice, self.slice.len() - 1 - index)
+    }
+}
+
+/// This is the Galil-Seiferas string matching algorithm, for any haystacks.
+///
+/// If a match exists where `pattern` is a substring of `text`, return the
+/// offset to the start of the match inside `Some(_)`. If not, return `None`.
+///
+/// ```
+/// use galil_seiferas::{gs_find_in, Deltas};
+///
+/// let text = [10, 12, 11, 15, 16, 14];
+/// let pattern = [0, 4, 5];
+/// assert_eq!(gs_find_in(Deltas::new(&text), Deltas::new(&pattern)), Some(2));
+/// ```
+pub fn gs_find_in<H, P>(text: H, pattern: P) -> Option<usize>
+    where H: Haystack + Copy,
+          P: Haystack<Item = H::Item> + Copy,
+          H::Item: Eq,
+{
+    gs_find_in_by(text, pattern, <H::Item as PartialEq>::eq)
+}
+
+/// This is the Galil-Seiferas string matching algorithm, for any haystacks.
+///
+/// If a match exists where `pattern` is a substring of `text`, return the
+/// offset to the start of the match inside `Some(_)`. If not, return `None`.
+///
+/// The elements of `text` and `pattern` are compared as `T` using `equal`,
+/// which must be an equivalence relation.
+pub fn gs_find_in_by<T, H, P, F>(text: H, pattern: P, equal: F) -> Option<usize>
+    where H: Haystack + Copy,
+          H::Item: Borrow<T>,
+          P: Haystack + Copy,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    let (u_len, hrp1) = decomposition_by(pattern, &equal);
+    let u = sub(pattern, 0..u_len);
+    let v = skip(pattern, u_len);
+    find_from_by(text, u, v, hrp1, &mut SearchState::default(), &equal)
+}
+
+#[test]
+fn test_gs_find_in() {
+    let text = b"abcabcabd";
+    assert_eq!(gs_find_in(&text[..], &b"cabd"[..]), Some(5));
+    assert_eq!(gs_find_in(text, b"abd"), Some(6));
+    assert_eq!(gs_find_in(text, b"abe"), None);
+    assert_eq!(gs_find_in(skip(text, 1), b"abc"), Some(2));
+    assert_eq!(gs_find_in(sub(text, 0..8), b"abd"), None);
+    assert_eq!(gs_find_in_by(text, b"ABD", |a: &u8, b: &u8| a.eq_ignore_ascii_case(b)),
+               Some(6));
+}
+
+#[test]
+fn test_reversed() {
+    let text = b"abcabcabd";
+    let view = Reversed::new(text);
+    assert_eq!((0..view.len()).map(|i| *view.get(i)).collect::<Vec<_>>(), b"dbacbacba");
+    assert_eq!(gs_find_in(view, &b"bacb"[..]), Some(1));
+    assert_eq!(view.original_range(1, 4), 4..8);
+    assert_eq!(view.original_range(9, 0), 0..0);
+    // The last cab, at 5..8
+    assert_eq!(gs_find_in(view, Reversed::new(b"cab")), Some(1));
+    assert_eq!(gs_find_in(view, Reversed::new(b"abd")), Some(0));
+    assert_eq!(gs_find_in(Reversed::new(&text[..0]), &b""[..]), Some(0));
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_reversed_original_range_oob() {
+    Reversed::new(b"abc").original_range(2, 2);
+}
@@ -0,0 +1,70 @@
+
+use std::io::{self, Write};
+
+use alloc::vec::Vec;
+
+use finder::Finder;
+use iter::Segment;
+
+/// Return a copy of `text` where each (non-overlapping) match of `pattern`
+/// is wrapped in ANSI escape sequences.
+///
+/// `style` is the parameter string of the SGR escape sequence, for example
+/// `"1;31"` for bold red. Each match is preceded by `"\x1b[<style>m"` and
+/// followed by the reset sequence `"\x1b[0m"`. An empty pattern leaves the
+/// text unchanged.
+///
+/// ```
+/// use galil_seiferas::highlight;
+///
+/// assert_eq!(highlight("one two one", "one", "1;31"),
+///            "\x1b[1;31mone\x1b[0m two \x1b[1;31mone\x1b[0m");
+/// ```
+pub fn highlight(text: &str, pattern: &str, style: &str) -> String {
+    let mut output = Vec::with_capacity(text.len());
+    write_highlighted(&mut output, text, pattern, style).unwrap();
+    // Matches of a string are on char boundaries, so the escapes are only
+    // inserted between chars.
+    String::from_utf8(output).unwrap()
+}
+
+/// Write `text` to `writer`, with each (non-overlapping) match of `pattern`
+/// wrapped in ANSI escape sequences.
+///
+/// See `highlight` for the escape sequences.
+///
+/// ```
+/// use galil_seiferas::write_highlighted;
+///
+/// let mut output = Vec::new();
+/// write_highlighted(&mut output, "a needle in a haystack", "needle", "7").unwrap();
+/// assert_eq!(output, b"a \x1b[7mneedle\x1b[0m in a haystack");
+/// ```
+pub fn write_highlighted<W: Write>(mut writer: W, text: &str, pattern: &str, style: &str)
+    -> io::Result<()>
+{
+    if pattern.is_empty() {
+        return writer.write_all(text.as_bytes());
+    }
+    let finder = Finder::new(pattern.as_bytes());
+    for segment in finder.segments(text.as_bytes()) {
+        match segment {
+            Segment::Gap(gap) => writer.write_all(gap)?,
+            Segment::Match(matched) => {
+                write!(writer, "\x1b[{}m", style)?;
+                writer.write_all(matched)?;
+                writer.write_all(b"\x1b[0m")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_highlight() {
+    assert_eq!(highlight("aaaa", "aa", "1"), "\x1b[1maa\x1b[0m\x1b[1maa\x1b[0m");
+    assert_eq!(highlight("åäö", "ä", "4"), "å\x1b[4mä\x1b[0mö");
+    assert_eq!(highlight("åäö", "", "4"), "åäö");
+    assert_eq!(highlight("åäö", "x", "4"), "åäö");
+    assert_eq!(highlight("", "x", "4"), "");
+}
@@ -0,0 +1,344 @@
+//! Searching in readers, writers and files.
+//!
+//! This module gathers the helpers that need the `std` feature: the
+//! streaming searches of `Read` and `BufRead` inputs, the search and
+//! replace `Write` adapter and the file searches. They are also exported at
+//! the crate root.
+//!
+//! The offsets of the matches in readers and files are `u64`, so that inputs
+//! that are larger than the address space can be searched on 32-bit targets.
+
+use std::cmp::max;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use finder::Finder;
+
+#[cfg(feature = "rayon")]
+pub use files::search_files;
+pub use highlight::write_highlighted;
+pub use lines::{grep_lines, GrepLines};
+pub use records::RecordReader;
+pub use replace::ReplacingWriter;
+
+/// The number of bytes to read at a time
+const READ_SIZE: usize = 64 * 1024;
+
+/// Search the file at `path` and return the offsets of the (non-overlapping)
+/// matches of the finder's pattern.
+///
+/// The file is read in blocks like in `search_reader`, so files of any size
+/// are searched with bounded memory.
+pub fn search_file<P: AsRef<Path>>(finder: &Finder<u8>, path: P) -> io::Result<Vec<u64>> {
+    search_reader(finder, File::open(path)?)
+}
+
+/// Search `reader` to the end and return the offsets of the (non-overlapping)
+/// matches of the finder's pattern.
+///
+/// The input is read in blocks; only the last m − 1 bytes of each block are
+/// kept for the next, so inputs of any size are searched with bounded memory.
+/// Use `reader_matches` to stream the matches instead of collecting them.
+///
+/// ```
+/// use galil_seiferas::Finder;
+/// use galil_seiferas::io::search_reader;
+///
+/// let input = &b"one two one"[..];
+/// assert_eq!(search_reader(&Finder::new(b"one"), input).unwrap(), [0, 8]);
+/// ```
+pub fn search_reader<R: Read>(finder: &Finder<u8>, reader: R) -> io::Result<Vec<u64>> {
+    reader_matches(finder, reader).collect()
+}
+
+/// Return an iterator of the offsets of the (non-overlapping) matches of the
+/// finder's pattern in `reader`.
+///
+/// The input is read in blocks as the iterator is advanced, like in
+/// `search_reader`, and matches that span two blocks are found. Both the
+/// memory use and the offsets are independent of `usize`, so a stream of any
+/// length can be searched, also on 32-bit targets. After an error, the
+/// iterator ends.
+///
+/// ```
+/// use std::io::{self, Read};
+/// use galil_seiferas::Finder;
+/// use galil_seiferas::io::reader_matches;
+///
+/// let input = io::repeat(b'.').take(1 << 20).chain(&b"needle"[..]);
+/// let finder = Finder::new(b"needle");
+/// let first = reader_matches(&finder, input).next().unwrap().unwrap();
+/// assert_eq!(first, 1 << 20);
+/// ```
+pub fn reader_matches<'f, R: Read>(finder: &'f Finder<'f, u8>, reader: R)
+    -> ReaderMatches<'f, R>
+{
+    let m = finder.pattern().len();
+    ReaderMatches {
+        finder,
+        reader,
+        buffer: Vec::with_capacity(max(READ_SIZE, 2 * m)),
+        base: 0,
+        found: VecDeque::new(),
+        eof: false,
+    }
+}
+
+/// Resume the search of `reader_matches` from `checkpoint`, with `reader`
+/// positioned at `checkpoint.input_offset()` of the input.
+///
+/// The finder must have the same pattern as the search that made the
+/// checkpoint. The resumed search returns the matches that the original
+/// search had not returned yet.
+///
+/// ```
+/// use std::io::Read;
+/// use galil_seiferas::Finder;
+/// use galil_seiferas::io::{reader_matches, reader_matches_from};
+///
+/// let input = b"one two one two one";
+/// let finder = Finder::new(b"one");
+/// let mut matches = reader_matches(&finder, &input[..]);
+/// assert_eq!(matches.next().unwrap().unwrap(), 0);
+/// let checkpoint = matches.checkpoint();
+///
+/// // For example after a restart
+/// let offset = checkpoint.input_offset() as usize;
+/// let resumed = reader_matches_from(&finder, &input[offset..], checkpoint);
+/// assert_eq!(resumed.map(Result::unwrap).collect::<Vec<_>>(), [8, 16]);
+/// ```
+pub fn reader_matches_from<'f, R: Read>(finder: &'f Finder<'f, u8>, reader: R,
+                                        checkpoint: Checkpoint)
+    -> ReaderMatches<'f, R>
+{
+    let mut matches = reader_matches(finder, reader);
+    matches.buffer.extend_from_slice(&checkpoint.carried);
+    matches.base = checkpoint.base;
+    matches.found = checkpoint.pending.into();
+    matches.eof = checkpoint.eof;
+    matches
+}
+
+/// Return an iterator of the offsets of the (non-overlapping) matches of the
+/// finder's pattern in the file at `path`; see `reader_matches`.
+pub fn file_matches<'f, P: AsRef<Path>>(finder: &'f Finder<'f, u8>, path: P)
+    -> io::Result<ReaderMatches<'f, File>>
+{
+    Ok(reader_matches(finder, File::open(path)?))
+}
+
+/// An iterator of the offsets of the matches in a reader, created by
+/// `reader_matches`.
+#[derive(Debug)]
+pub struct ReaderMatches<'f, R> {
+    finder: &'f Finder<'f, u8>,
+    reader: R,
+    buffer: Vec<u8>,
+    /// The offset of the buffer in the input
+    base: u64,
+    /// The matches in the buffer that are not yet returned
+    found: VecDeque<u64>,
+    eof: bool,
+}
+
+/// The state of the search of a `ReaderMatches`, for resuming it with
+/// `reader_matches_from`.
+///
+/// The state is the offset in the input, the end of the input that was
+/// read but not searched to the end, and the matches that were found but
+/// not returned, so it is small. With the `serde` feature, it implements
+/// `Serialize` and `Deserialize`, so that a long search can be resumed in
+/// another process.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Checkpoint {
+    /// The offset of `carried` in the input
+    base: u64,
+    /// The end of the input that was read, which can be the start of a match
+    carried: Vec<u8>,
+    /// The matches that were found but not returned
+    pending: Vec<u64>,
+    eof: bool,
+}
+
+impl Checkpoint {
+    /// The offset in the input where the reader of the resumed search
+    /// starts.
+    pub fn input_offset(&self) -> u64 {
+        self.base + self.carried.len() as u64
+    }
+}
+
+impl<'f, R> ReaderMatches<'f, R> {
+    /// Return the state of the search, for resuming it with
+    /// `reader_matches_from`.
+    ///
+    /// The next match of the resumed search is the next match of this one.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            base: self.base,
+            carried: self.buffer.clone(),
+            pending: self.found.iter().cloned().collect(),
+            eof: self.eof,
+        }
+    }
+}
+
+impl<'f, R: Read> ReaderMatches<'f, R> {
+    /// Read the next block, search it, and keep the end of the buffer that
+    /// can be the start of a match.
+    fn fill(&mut self) -> io::Result<()> {
+        let m = self.finder.pattern().len();
+        let keep = m.saturating_sub(1);
+        let buffer = &mut self.buffer;
+        let len = buffer.len();
+        buffer.resize(buffer.capacity(), 0);
+        let n = loop {
+            match self.reader.read(&mut buffer[len..]) {
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    buffer.truncate(len);
+                    return Err(e);
+                }
+                Ok(n) => break n,
+            }
+        };
+        buffer.truncate(len + n);
+        self.eof = n == 0;
+
+        let mut pos = 0;
+        for found in self.finder.find_iter(buffer) {
+            // The empty pattern matches at the end of the buffer; it is found
+            // again at the start of the next.
+            if found.start() == buffer.len() && !self.eof {
+                break;
+            }
+            self.found.push_back(self.base + found.start() as u64);
+            pos = found.end();
+        }
+        // A match that starts before the last m - 1 bytes would be complete
+        // in the buffer, so it would have been found.
+        let drop = max(pos, buffer.len() - keep.min(buffer.len()));
+        buffer.drain(..drop);
+        self.base += drop as u64;
+        Ok(())
+    }
+}
+
+impl<'f, R: Read> Iterator for ReaderMatches<'f, R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<io::Result<u64>> {
+        loop {
+            if let Some(offset) = self.found.pop_front() {
+                return Some(Ok(offset));
+            }
+            if self.eof {
+                return None;
+            }
+            if let Err(error) = self.fill() {
+                self.eof = true;
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_search_reader() {
+    /// A reader that returns at most `n` bytes at a time
+    struct Trickle<'a>(&'a [u8], usize);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(self.1);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    let text = "abaab".repeat(READ_SIZE / 3);
+    let finder = Finder::new(b"aaba");
+    let expected = finder.find_iter(text.as_bytes()).map(|m| m.start() as u64)
+                                                    .collect::<Vec<_>>();
+    assert_eq!(expected.len(), READ_SIZE / 3 - 1);
+    for &n in &[1, 3, 1000, READ_SIZE] {
+        assert_eq!(search_reader(&finder, Trickle(text.as_bytes(), n)).unwrap(), expected);
+    }
+    let empty = Finder::new(b"");
+    assert_eq!(search_reader(&empty, Trickle(b"abc", 1)).unwrap(), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_reader_matches() {
+    /// A reader that fails after its input
+    struct Failing<'a>(&'a [u8]);
+
+    impl<'a> Read for Failing<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("failing"));
+            }
+            self.0.read(buf)
+        }
+    }
+
+    let finder = Finder::new(b"ab");
+    let mut matches = reader_matches(&finder, Failing(b"abcab"));
+    assert_eq!(matches.next().unwrap().unwrap(), 0);
+    assert_eq!(matches.next().unwrap().unwrap(), 3);
+    assert!(matches.next().unwrap().is_err());
+    assert!(matches.next().is_none());
+}
+
+#[test]
+fn test_reader_matches_from() {
+    let text = "abaab".repeat(READ_SIZE / 2);
+    let input = text.as_bytes();
+    for pattern in &[&b"aaba"[..], b"", b"b", b"abaababaab"] {
+        let finder = Finder::new(pattern);
+        let expected = search_reader(&finder, input).unwrap();
+        let mut matches = reader_matches(&finder, input);
+        let mut found = Vec::new();
+        loop {
+            // Resume after each match
+            let checkpoint = matches.checkpoint();
+            let offset = checkpoint.input_offset() as usize;
+            matches = reader_matches_from(&finder, &input[offset..], checkpoint);
+            match matches.next() {
+                Some(offset) => found.push(offset.unwrap()),
+                None => break,
+            }
+            if found.len() > 3 {
+                found.extend(matches.by_ref().map(Result::unwrap));
+            }
+        }
+        assert_eq!(found, expected);
+    }
+}
+
+// This searches 4 GiB, so it is slow; run it with --ignored
+#[test]
+#[ignore]
+fn test_reader_matches_past_4_gib() {
+    let finder = Finder::new(b"ab");
+    let len = (1 << 32) + 10;
+    let input = io::repeat(b'a').take(len).chain(&b"ba"[..]);
+    let found = reader_matches(&finder, input).collect::<io::Result<Vec<_>>>().unwrap();
+    assert_eq!(found, [len - 1]);
+}
+
+#[test]
+fn test_search_file() {
+    // The definition and the pattern itself
+    let finder = Finder::new(b"fn test_search_file()");
+    assert_eq!(search_file(&finder, file!()).unwrap().len(), 2);
+    assert!(search_file(&finder, "does-not-exist.rs").is_err());
+}
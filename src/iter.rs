@@ -0,0 +1,602 @@
+
+use std::iter::{FusedIterator, StepBy};
+use std::ops::Range;
+
+use analysis::PatternInfo;
+use finder::{to_match, Finder, Match};
+use two_way::{two_way_find_from, TwoWayInfo};
+use {text_has_prefix, Pattern, SearchState};
+
+/// A predicate on a match: `text`, start and end of the match.
+pub type BoundaryFn<T> = fn(&[T], usize, usize) -> bool;
+
+/// Search options that are shared by the searchers and their iterators.
+pub(crate) struct Options<T> {
+    pub(crate) start: usize,
+    pub(crate) end: Option<usize>,
+    pub(crate) anchored: bool,
+    pub(crate) overlapping: bool,
+    pub(crate) boundary: Option<BoundaryFn<T>>,
+    /// Search with the Two-Way algorithm instead, using this preprocessing
+    pub(crate) two_way: Option<TwoWayInfo>,
+    /// The prefetch distance, see `GsSearcherBuilder::prefetch`
+    pub(crate) prefetch: usize,
+}
+
+impl<T> Default for Options<T> {
+    fn default() -> Self {
+        Options {
+            start: 0,
+            end: None,
+            anchored: false,
+            overlapping: false,
+            boundary: None,
+            two_way: None,
+            prefetch: 0,
+        }
+    }
+}
+
+impl<T> Copy for Options<T> { }
+impl<T> Clone for Options<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+/// An iterator over the matches of a pattern in a text.
+///
+/// Created with `Finder::find_iter` or `GsSearcher::find_iter`.
+///
+/// The search is resumed from where the last match was found, so iterating
+/// all the matches is a single linear time pass over the text.
+pub struct Matches<'p, 't, T: 'p + 't> {
+    pattern: Pattern<'p, T, fn(&T, &T) -> bool>,
+    needle: &'p [T],
+    info: PatternInfo,
+    text: &'t [T],
+    /// End of the searched part of the text
+    end: usize,
+    state: SearchState,
+    options: Options<T>,
+}
+
+impl<'p, 't, T: Eq> Matches<'p, 't, T> {
+    pub(crate) fn new(finder: &'p Finder<T>, text: &'t [T], options: Options<T>) -> Self {
+        Matches::with_info(finder.pattern(), finder.info(), text, options)
+    }
+
+    /// Create the iterator for `needle`, which is preprocessed in `info`.
+    pub(crate) fn with_info(needle: &'p [T], info: PatternInfo, text: &'t [T],
+                            options: Options<T>) -> Self
+    {
+        let end = options.end.map_or(text.len(), |end| end.min(text.len()));
+        let (u, v) = needle.split_at(info.u_len());
+        Matches {
+            pattern: Pattern { u, v, hrp1: info.hrp(), equal: T::eq },
+            needle,
+            info,
+            text,
+            end,
+            state: SearchState { prefetch: options.prefetch, ..SearchState::at(options.start) },
+            options,
+        }
+    }
+
+    /// Find the next match that is not checked against the boundary predicate
+    fn next_candidate(&mut self) -> Option<usize> {
+        let text = &self.text[..self.end];
+        if self.options.anchored {
+            let start = self.options.start;
+            if self.state.pos != start || start > text.len() ||
+                !text_has_prefix_checked(&text[start..], &self.pattern)
+            {
+                return None;
+            }
+            self.state.restart(text.len() + 1);
+            return Some(start);
+        }
+        if let Some(ref info) = self.options.two_way {
+            // The memory of Two-Way is kept in state.j, which a restart clears
+            let found = two_way_find_from(info, self.needle, text, &mut self.state);
+            if found.is_none() {
+                self.state.restart(text.len() + 1);
+            }
+            return found;
+        }
+        self.pattern.find_from(text, &mut self.state)
+    }
+}
+
+fn text_has_prefix_checked<T: Eq>(text: &[T], pattern: &Pattern<T, fn(&T, &T) -> bool>)
+    -> bool
+{
+    let (u, v) = (pattern.u, pattern.v);
+    text.len() >= u.len() + v.len() &&
+        text_has_prefix(text, u, &pattern.equal) &&
+        text_has_prefix(&text[u.len()..], v, &pattern.equal)
+}
+
+impl<'p, 't, T: Eq> Matches<'p, 't, T> {
+    /// Skip ahead, so that the next match starts at or after `pos`.
+    ///
+    /// The search continues from `pos` without scanning the skipped part;
+    /// this takes **O(1)** time. If `pos` is not after the position where
+    /// the search would resume anyway, nothing changes.
+    ///
+    /// ```
+    /// use galil_seiferas::Finder;
+    ///
+    /// let finder = Finder::new(b"<");
+    /// let text = b"<a> <!-- <b> --> <c>";
+    /// let mut matches = finder.find_iter(text);
+    /// assert_eq!(matches.next().map(|m| m.start()), Some(0));
+    /// assert_eq!(matches.next().map(|m| m.start()), Some(4));
+    /// // Skip the comment
+    /// matches.advance_to(16);
+    /// assert_eq!(matches.next().map(|m| m.start()), Some(17));
+    /// ```
+    pub fn advance_to(&mut self, pos: usize) {
+        if pos > self.state.pos {
+            self.state.restart(pos);
+        }
+    }
+
+    /// Find the start of the next match
+    pub(crate) fn next_start(&mut self) -> Option<usize> {
+        loop {
+            let start = self.next_candidate()?;
+            let end = start + self.info.len();
+            if let Some(boundary) = self.options.boundary {
+                if !boundary(self.text, start, end) {
+                    continue;
+                }
+            }
+            if !self.options.overlapping && self.state.pos < end {
+                self.state.restart(end);
+            }
+            return Some(start);
+        }
+    }
+}
+
+impl<'p, 't, T: Eq> Iterator for Matches<'p, 't, T> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        self.next_start().map(|start| to_match(&self.info, start))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Matches are at least one period apart, or one pattern length
+        // if they don't overlap.
+        let m = self.info.len();
+        let last_start = match self.end.checked_sub(m) {
+            Some(last) if self.state.pos <= last => last,
+            _ => return (0, Some(0)),
+        };
+        if self.options.anchored {
+            return (0, Some(1));
+        }
+        let stride = if self.options.overlapping { self.info.period() } else { m };
+        (0, Some((last_start - self.state.pos) / stride.max(1) + 1))
+    }
+
+    fn count(mut self) -> usize {
+        let mut count = 0;
+        while self.next_start().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    fn last(mut self) -> Option<Match> {
+        let mut last = None;
+        while let Some(start) = self.next_start() {
+            last = Some(start);
+        }
+        last.map(|start| to_match(&self.info, start))
+    }
+}
+
+impl<'p, 't, T: Eq> FusedIterator for Matches<'p, 't, T> { }
+
+impl<'p, 't, T> Clone for Matches<'p, 't, T> {
+    fn clone(&self) -> Self {
+        Matches {
+            pattern: self.pattern,
+            needle: self.needle,
+            info: self.info,
+            text: self.text,
+            end: self.end,
+            state: self.state,
+            options: self.options,
+        }
+    }
+}
+
+/// A run of overlapping matches that are each one pattern period apart.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchRun {
+    start: usize,
+    count: usize,
+    stride: usize,
+}
+
+impl MatchRun {
+    /// The start of the first match in the run
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The number of matches in the run
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The distance between the starts of consecutive matches in the run;
+    /// this is the period of the pattern (or 1 for the empty pattern).
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// An iterator of the start offsets of the matches in the run
+    pub fn starts(&self) -> StepBy<Range<usize>> {
+        (self.start..self.start + self.count * self.stride).step_by(self.stride)
+    }
+}
+
+/// An iterator of runs of overlapping matches, created by
+/// `Finder::find_runs`.
+///
+/// Every match is part of exactly one run, and consecutive matches that are
+/// exactly one pattern period apart are in the same run.
+#[derive(Clone)]
+pub struct MatchRuns<'p, 't, T: 'p + 't> {
+    matches: Matches<'p, 't, T>,
+    pending: Option<usize>,
+}
+
+impl<'p, 't, T: Eq> MatchRuns<'p, 't, T> {
+    pub(crate) fn new(finder: &'p Finder<T>, text: &'t [T]) -> Self {
+        let options = Options { overlapping: true, ..Options::default() };
+        MatchRuns {
+            matches: Matches::new(finder, text, options),
+            pending: None,
+        }
+    }
+}
+
+impl<'p, 't, T: Eq> Iterator for MatchRuns<'p, 't, T> {
+    type Item = MatchRun;
+
+    fn next(&mut self) -> Option<MatchRun> {
+        let start = match self.pending.take() {
+            Some(start) => start,
+            None => self.matches.next_start()?,
+        };
+        let stride = self.matches.info.period().max(1);
+        let mut count = 1;
+        while let Some(next) = self.matches.next_start() {
+            if next == start + count * stride {
+                count += 1;
+            } else {
+                self.pending = Some(next);
+                break;
+            }
+        }
+        Some(MatchRun { start, count, stride })
+    }
+}
+
+impl<'p, 't, T: Eq> FusedIterator for MatchRuns<'p, 't, T> { }
+
+/// A part of the text: either a match or a gap between matches.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Segment<'t, T: 't> {
+    /// Text that is not part of a match
+    Gap(&'t [T]),
+    /// A match of the pattern
+    Match(&'t [T]),
+}
+
+/// An iterator of the matches and the gaps between them, created by
+/// `Finder::segments`.
+#[derive(Clone)]
+pub struct Segments<'p, 't, T: 'p + 't> {
+    matches: Matches<'p, 't, T>,
+    /// End of the last segment
+    pos: usize,
+    /// Start of the next match, if it is already found
+    next_match: Option<usize>,
+}
+
+impl<'p, 't, T: Eq> Segments<'p, 't, T> {
+    pub(crate) fn new(finder: &'p Finder<T>, text: &'t [T]) -> Self {
+        Segments {
+            matches: Matches::new(finder, text, Options::default()),
+            pos: 0,
+            next_match: None,
+        }
+    }
+}
+
+impl<'p, 't, T: Eq> Iterator for Segments<'p, 't, T> {
+    type Item = Segment<'t, T>;
+
+    fn next(&mut self) -> Option<Segment<'t, T>> {
+        let text = self.matches.text;
+        if self.next_match.is_none() {
+            self.next_match = self.matches.next_start();
+        }
+        let gap_end = self.next_match.unwrap_or(text.len());
+        if self.pos < gap_end {
+            let gap = &text[self.pos..gap_end];
+            self.pos = gap_end;
+            return Some(Segment::Gap(gap));
+        }
+        let start = self.next_match.take()?;
+        self.pos = start + self.matches.info.len();
+        Some(Segment::Match(&text[start..self.pos]))
+    }
+}
+
+impl<'p, 't, T: Eq> FusedIterator for Segments<'p, 't, T> { }
+
+/// A match together with its surrounding context in the text.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ContextMatch<'t, T: 't> {
+    text: &'t [T],
+    matched: Match,
+    context_start: usize,
+    context_end: usize,
+}
+
+impl<'t, T> Copy for ContextMatch<'t, T> { }
+impl<'t, T> Clone for ContextMatch<'t, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'t, T> ContextMatch<'t, T> {
+    /// The match
+    pub fn as_match(&self) -> Match {
+        self.matched
+    }
+
+    /// The context before the match
+    pub fn before(&self) -> &'t [T] {
+        &self.text[self.context_start..self.matched.start()]
+    }
+
+    /// The matched part of the text
+    pub fn matched(&self) -> &'t [T] {
+        &self.text[self.matched.range()]
+    }
+
+    /// The context after the match
+    pub fn after(&self) -> &'t [T] {
+        &self.text[self.matched.end()..self.context_end]
+    }
+
+    /// The range of the text that includes the match and its context
+    pub fn context_range(&self) -> Range<usize> {
+        self.context_start..self.context_end
+    }
+
+    /// The match and its context
+    pub fn context(&self) -> &'t [T] {
+        &self.text[self.context_range()]
+    }
+}
+
+/// An iterator of matches with context, created by
+/// `Finder::find_iter_with_context`.
+#[derive(Clone)]
+pub struct ContextMatches<'p, 't, T: 'p + 't> {
+    matches: Matches<'p, 't, T>,
+    before: usize,
+    after: usize,
+}
+
+impl<'p, 't, T: Eq> ContextMatches<'p, 't, T> {
+    pub(crate) fn new(finder: &'p Finder<T>, text: &'t [T], before: usize, after: usize)
+        -> Self
+    {
+        ContextMatches {
+            matches: Matches::new(finder, text, Options::default()),
+            before,
+            after,
+        }
+    }
+}
+
+impl<'p, 't, T: Eq> Iterator for ContextMatches<'p, 't, T> {
+    type Item = ContextMatch<'t, T>;
+
+    fn next(&mut self) -> Option<ContextMatch<'t, T>> {
+        let matched = self.matches.next()?;
+        let text = self.matches.text;
+        Some(ContextMatch {
+            text,
+            matched,
+            context_start: matched.start().saturating_sub(self.before),
+            context_end: matched.end().saturating_add(self.after).min(text.len()),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.matches.size_hint()
+    }
+}
+
+impl<'p, 't, T: Eq> FusedIterator for ContextMatches<'p, 't, T> { }
+
+#[test]
+fn test_matches() {
+    let finder = Finder::new(b"aba");
+    let starts = |text: &[u8]| finder.find_iter(text).map(|m| m.start()).collect::<Vec<_>>();
+    assert_eq!(starts(b"abababa"), vec![0, 4]);
+    assert_eq!(starts(b"aabaxaba"), vec![1, 5]);
+    assert_eq!(starts(b"ab"), vec![]);
+
+    let finder = Finder::new(b"");
+    assert_eq!(finder.find_iter(b"ab").map(|m| m.start()).collect::<Vec<_>>(),
+               vec![0, 1, 2]);
+}
+
+#[test]
+fn test_matches_traits() {
+    let finder = Finder::new(b"aa");
+    let text = b"aaaaaaab";
+    let iter = finder.find_iter(text);
+    assert_eq!(iter.size_hint(), (0, Some(4)));
+    assert_eq!(iter.clone().count(), 3);
+    assert_eq!(iter.clone().last().map(|m| m.start()), Some(4));
+
+    let mut iter2 = iter.clone();
+    iter2.next();
+    assert_eq!(iter2.size_hint(), (0, Some(3)));
+    assert_eq!(iter2.by_ref().count(), 2);
+    assert_eq!(iter2.next(), None);
+    assert_eq!(iter2.next(), None);
+
+    let overlapping = ::GsSearcherBuilder::new().overlapping(true).build(b"aba");
+    let text = b"ababababab";
+    let iter = overlapping.find_iter(text);
+    assert_eq!(iter.size_hint(), (0, Some(4)));
+    assert_eq!(iter.count(), 4);
+
+    let empty = Finder::new(b"");
+    assert_eq!(empty.find_iter(b"abc").size_hint(), (0, Some(4)));
+    assert_eq!(empty.find_iter(b"abc").count(), 4);
+}
+
+#[test]
+fn test_matches_advance_to() {
+    let finder = Finder::new(b"aba");
+    let text = b"abababa.aba.ababa";
+    let starts = |iter: Matches<u8>| iter.map(|m| m.start()).collect::<Vec<_>>();
+    for pos in 0..text.len() + 2 {
+        let mut iter = finder.find_iter(text);
+        iter.advance_to(pos);
+        // The non-overlapping matches of text[pos..]
+        let from_pos = (pos..text.len() + 1).filter(|&i| text[i..].starts_with(b"aba"))
+            .fold(Vec::new(), |mut found: Vec<usize>, i| {
+                if found.last().is_none_or(|&last| i >= last + 3) {
+                    found.push(i);
+                }
+                found
+            });
+        assert_eq!(starts(iter), from_pos, "{}", pos);
+    }
+
+    // Advancing to a position before the resume position does nothing
+    let mut iter = finder.find_iter(text);
+    iter.next();
+    iter.next();
+    iter.advance_to(1);
+    assert_eq!(starts(iter), [8, 12]);
+}
+
+#[test]
+fn test_match_runs() {
+    let finder = Finder::new(b"abab");
+    let text = b"abababab_ababab_ab_abab";
+    let runs = finder.find_runs(text).collect::<Vec<_>>();
+    assert_eq!(runs, vec![MatchRun { start: 0, count: 3, stride: 2 },
+                          MatchRun { start: 9, count: 2, stride: 2 },
+                          MatchRun { start: 19, count: 1, stride: 2 }]);
+    assert_eq!(runs[0].starts().collect::<Vec<_>>(), vec![0, 2, 4]);
+
+    let finder = Finder::new(b"abc");
+    let runs = finder.find_runs(b"abcabc_abc").collect::<Vec<_>>();
+    assert_eq!(runs, vec![MatchRun { start: 0, count: 2, stride: 3 },
+                          MatchRun { start: 7, count: 1, stride: 3 }]);
+
+    let text = "a".repeat(1000);
+    let finder = Finder::new(b"aaa");
+    let runs = finder.find_runs(text.as_bytes()).collect::<Vec<_>>();
+    assert_eq!(runs, vec![MatchRun { start: 0, count: 998, stride: 1 }]);
+}
+
+#[test]
+fn test_segments() {
+    let finder = Finder::new(b"aba");
+    let segments = finder.segments(b"abababa--aba").collect::<Vec<_>>();
+    assert_eq!(segments, vec![Segment::Match(b"aba"), Segment::Gap(b"b"),
+                              Segment::Match(b"aba"), Segment::Gap(b"--"),
+                              Segment::Match(b"aba")]);
+    assert_eq!(finder.segments(b"").count(), 0);
+    assert_eq!(finder.segments(b"ab").collect::<Vec<_>>(), vec![Segment::Gap(b"ab")]);
+
+    let empty = Finder::new(b"");
+    let segments = empty.segments(b"xy").collect::<Vec<_>>();
+    assert_eq!(segments, vec![Segment::Match(b""), Segment::Gap(b"x"),
+                              Segment::Match(b""), Segment::Gap(b"y"),
+                              Segment::Match(b"")]);
+}
+
+#[test]
+fn test_context_matches() {
+    let finder = Finder::new(b"ab");
+    let text = b"xxabyyyabzab";
+    let found = finder.find_iter_with_context(text, 1, 2).collect::<Vec<_>>();
+    assert_eq!(found.len(), 3);
+    assert_eq!((found[0].before(), found[0].matched(), found[0].after()),
+               (&b"x"[..], &b"ab"[..], &b"yy"[..]));
+    assert_eq!(found[0].as_match().start(), 2);
+    assert_eq!(found[1].context(), b"yabza");
+    assert_eq!(found[2].context_range(), 9..12);
+    assert_eq!(found[2].after(), b"");
+
+    let found = finder.find_iter_with_context(text, 100, 0).next().unwrap();
+    assert_eq!(found.before(), b"xx");
+}
+
+#[test]
+fn test_matches_linear() {
+    use std::cell::Cell;
+
+    /// An element that counts its comparisons between the text and the
+    /// pattern (and not the debug checks of the pattern)
+    struct Counted<'c>(u8, bool, &'c Cell<usize>);
+
+    impl<'c> PartialEq for Counted<'c> {
+        fn eq(&self, other: &Self) -> bool {
+            if self.1 != other.1 {
+                self.2.set(self.2.get() + 1);
+            }
+            self.0 == other.0
+        }
+    }
+
+    impl<'c> Eq for Counted<'c> { }
+
+    // Periodic texts with a match at each period, so that the number of
+    // matches is proportional to the length of the text.
+    let count = Cell::new(0);
+    let counted = |s: &[u8], in_text| {
+        s.iter().map(|&x| Counted(x, in_text, &count)).collect::<Vec<_>>()
+    };
+    for &(pattern, period) in &[(&b"aaaaaaaa"[..], &b"a"[..]), (b"abababab", b"ab"),
+                                (b"aabaabaab", b"aab"), (b"abcabcabd", b"abcabcabd"),
+                                (b"baaabaaabaaabaa", b"baaa")]
+    {
+        let n = 10_000;
+        let text = counted(&period.repeat(n / period.len()), true);
+        let pattern = counted(pattern, false);
+        let finder = Finder::new(&pattern);
+        let overlapping = ::GsSearcherBuilder::new().overlapping(true).build(&pattern);
+        let expected = text.windows(pattern.len())
+            .filter(|w| w.iter().zip(&pattern[..]).all(|(a, b)| a.0 == b.0))
+            .count();
+
+        count.set(0);
+        assert_eq!(overlapping.find_iter(&text).count(), expected);
+        assert!(expected >= n / pattern.len() / 2);
+        assert!(count.get() <= 4 * n, "{:?}: {}", period, count.get());
+
+        count.set(0);
+        let found = finder.find_iter(&text).count();
+        assert!(found >= expected / pattern.len());
+        assert!(count.get() <= 4 * n, "{:?}: {}", period, count.get());
+    }
+}
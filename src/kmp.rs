@@ -0,0 +1,227 @@
+
+use engine::SearchEngine;
+
+/// The Knuth-Morris-Pratt string matching algorithm, with its table in a
+/// buffer that is provided by the caller.
+///
+/// The table takes O(m) space, where Galil-Seiferas needs O(1), but the
+/// search loop is simpler, which can be faster for short patterns. The
+/// crate does not allocate: the caller provides the table storage, for
+/// example an array on the stack.
+///
+/// ```
+/// use galil_seiferas::{Kmp, SearchEngine};
+///
+/// let mut table = [0; 16];
+/// let engine = Kmp::new(b"abab", &mut table);
+/// assert_eq!(engine.find_iter(b"abababab").collect::<Vec<_>>(), [0, 4]);
+/// ```
+#[derive(Debug)]
+pub struct Kmp<'a, T: 'a> {
+    pattern: &'a [T],
+    /// `table[i]` is the length of the longest proper border of
+    /// `pattern[..i + 1]`.
+    table: &'a [usize],
+}
+
+impl<'a, T> Copy for Kmp<'a, T> { }
+impl<'a, T> Clone for Kmp<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: Eq> Kmp<'a, T> {
+    /// Preprocess `pattern`, using the first m elements of `table` for the
+    /// failure table.
+    ///
+    /// **Panics** if `table` is shorter than `pattern`.
+    pub fn new(pattern: &'a [T], table: &'a mut [usize]) -> Self {
+        let m = pattern.len();
+        assert!(table.len() >= m, "Kmp: the table must be at least as long as the pattern");
+        let table = &mut table[..m];
+        borders(pattern, table);
+        Kmp { pattern, table }
+    }
+}
+
+/// Fill `table[i]` with the length of the longest proper border of
+/// `pattern[..i + 1]`; `table` must be as long as `pattern`.
+pub(crate) fn borders<T: Eq>(pattern: &[T], table: &mut [usize]) {
+    let m = pattern.len();
+    debug_assert_eq!(table.len(), m);
+    if m > 0 {
+        table[0] = 0;
+    }
+    let mut border = 0;
+    for i in 1..m {
+        while border > 0 && pattern[i] != pattern[border] {
+            border = table[border - 1];
+        }
+        if pattern[i] == pattern[border] {
+            border += 1;
+        }
+        table[i] = border;
+    }
+}
+
+impl<'a, T: Eq> SearchEngine<T> for Kmp<'a, T> {
+    fn pattern(&self) -> &[T] {
+        self.pattern
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        let (x, m) = (self.pattern, self.pattern.len());
+        if start > text.len() {
+            return None;
+        }
+        if m == 0 {
+            return Some(start);
+        }
+        // The length of the prefix of the pattern that is matched
+        let mut j = 0;
+        for (i, elt) in text.iter().enumerate().skip(start) {
+            while j > 0 && x[j] != *elt {
+                j = self.table[j - 1];
+            }
+            if x[j] == *elt {
+                j += 1;
+                if j == m {
+                    return Some(i + 1 - m);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// An online check of whether a buffer that grows at the end ends with a
+/// pattern, for example to detect a delimiter while bytes arrive one at a
+/// time.
+///
+/// The elements are not stored: the matcher keeps the length of the longest
+/// prefix of the pattern that the buffer ends with, and updates it with the
+/// failure table of `Kmp`. Each `push` takes amortized **O(1)** time.
+///
+/// ```
+/// use galil_seiferas::EndsWith;
+///
+/// let mut table = [0; 4];
+/// let mut delimiter = EndsWith::new(b"\r\n\r\n", &mut table);
+/// let ends = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n".iter()
+///     .map(|byte| delimiter.push(byte))
+///     .collect::<Vec<_>>();
+/// assert_eq!(ends.iter().position(|&end| end), Some(ends.len() - 1));
+/// ```
+#[derive(Debug)]
+pub struct EndsWith<'a, T: 'a> {
+    kmp: Kmp<'a, T>,
+    /// The length of the longest prefix of the pattern that the buffer ends
+    /// with
+    matched: usize,
+}
+
+impl<'a, T> Copy for EndsWith<'a, T> { }
+impl<'a, T> Clone for EndsWith<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: Eq> EndsWith<'a, T> {
+    /// Preprocess `pattern`, using the first m elements of `table` for the
+    /// failure table; the buffer is empty.
+    ///
+    /// **Panics** if `table` is shorter than `pattern`.
+    pub fn new(pattern: &'a [T], table: &'a mut [usize]) -> Self {
+        EndsWith::from_kmp(Kmp::new(pattern, table))
+    }
+
+    /// Use the preprocessing of `kmp`; the buffer is empty.
+    pub fn from_kmp(kmp: Kmp<'a, T>) -> Self {
+        EndsWith { kmp, matched: 0 }
+    }
+
+    /// Append `element` to the buffer, and return `true` if the buffer now
+    /// ends with the pattern.
+    pub fn push(&mut self, element: &T) -> bool {
+        let (x, m) = (self.kmp.pattern, self.kmp.pattern.len());
+        if m == 0 {
+            return true;
+        }
+        let mut j = self.matched;
+        if j == m {
+            j = self.kmp.table[m - 1];
+        }
+        while j > 0 && x[j] != *element {
+            j = self.kmp.table[j - 1];
+        }
+        if x[j] == *element {
+            j += 1;
+        }
+        self.matched = j;
+        j == m
+    }
+
+    /// Return `true` if the buffer ends with the pattern.
+    pub fn is_match(&self) -> bool {
+        self.matched == self.kmp.pattern.len()
+    }
+
+    /// The length of the longest prefix of the pattern that the buffer ends
+    /// with.
+    pub fn matched_len(&self) -> usize {
+        self.matched
+    }
+
+    /// Empty the buffer.
+    pub fn reset(&mut self) {
+        self.matched = 0;
+    }
+}
+
+#[test]
+fn test_kmp() {
+    let mut table = [usize::MAX; 12];
+    Kmp::new(b"abaabab", &mut table);
+    assert_eq!(table[..7], [0, 0, 1, 1, 2, 3, 2]);
+    assert_eq!(table[7], usize::MAX);
+
+    let text = b"abaababaabaababaababaabaababaabaab";
+    let mut table = [0; 12];
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 12) {
+            let pattern = &text[start..end];
+            let engine = Kmp::new(pattern, &mut table);
+            let starts = (0..text.len() + 1)
+                .filter(|&i| text[i..].starts_with(pattern))
+                .collect::<Vec<_>>();
+            let found = (0..text.len() + 1)
+                .filter_map(|i| engine.find_at(text, i))
+                .collect::<::std::collections::BTreeSet<_>>();
+            assert_eq!(found.into_iter().collect::<Vec<_>>(), starts);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_kmp_short_table() {
+    Kmp::new(b"abc", &mut [0; 2]);
+}
+
+#[test]
+fn test_ends_with() {
+    let text = b"abaababaabaababaababaabaababaabaab";
+    let mut table = [0; 8];
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 8) {
+            let pattern = &text[start..end];
+            let mut matcher = EndsWith::new(pattern, &mut table);
+            assert_eq!(matcher.is_match(), pattern.is_empty());
+            for i in 0..text.len() {
+                assert_eq!(matcher.push(&text[i]), text[..i + 1].ends_with(pattern));
+                assert_eq!(matcher.is_match(), text[..i + 1].ends_with(pattern));
+                assert!(text[..i + 1].ends_with(&pattern[..matcher.matched_len()]));
+            }
+            matcher.reset();
+            assert_eq!(matcher.matched_len(), 0);
+        }
+    }
+}
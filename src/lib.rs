@@ -40,12 +40,97 @@
 //!
 //! # Crate Features
 //!
-//! The crate is always `no_std`
-
-
-#![cfg_attr(not(test), no_std)]
+//! The crate is `no_std` unless the `std` feature is enabled, and it links
+//! the `alloc` crate only with the `alloc` feature.
+//!
+//! - `alloc`
+//!   - Optional.
+//!   - Use the `alloc` crate, implement `Needle` for `Vec` and enable owned
+//!     `Finder`s, `gs_match_mask` and `GappedPattern`.
+//!   - Enable the `Finder` methods that return vectors: `find_all`,
+//!     `split_to_vec` and `replace`, the covers and seeds of a word:
+//!     `shortest_cover`, `covers` and `shortest_seed`, `palindromic_radii`,
+//!     `periods`, `PeriodTracker`, `gs_find_prefixes`,
+//!     `longest_prefix_match`, `matching_statistics` and
+//!     `TransformedHaystack`.
+//! - `std`
+//!   - Optional, implies `alloc`.
+//!   - Use the `std` crate and enable the I/O helpers in the module `io`
+//!     (`ReplacingWriter`, `RecordReader`, `grep_lines`, `search_reader`
+//!     and `search_file`), and `highlight`, `compare_engines`,
+//!     `FinderCache` and `find_with_deadline`.
+//! - `bytes`
+//!   - Optional.
+//!   - Enable the `Finder` methods for `bytes::Bytes` texts, which return
+//!     the matches and the parts between them as `Bytes` slices that share
+//!     the text's buffer: `find_bytes`, `find_iter_bytes` and `split_bytes`.
+//! - `codec`
+//!   - Optional, implies `std`.
+//!   - Enable `DelimiterCodec`, a `tokio_util` codec for delimited frames.
+//! - `rayon`
+//!   - Optional.
+//!   - Enable `par_find`, which searches the chunks of a large text in
+//!     parallel, and together with `std`, `search_files` for searching
+//!     files in parallel.
+//! - `proptest`
+//!   - Optional.
+//!   - Together with `alloc`, enable the module `strategy` of `proptest`
+//!     strategies for property testing string searches.
+//! - `serde`
+//!   - Optional.
+//!   - Together with `std`, implement `Serialize` and `Deserialize` for
+//!     `io::Checkpoint`, the state of a resumable search of a reader.
+//! - `defmt`
+//!   - Optional.
+//!   - Implement `defmt::Format` for `Match`, `PatternInfo`, `Hrp`,
+//!     `DecompositionError`, `SearchPoll` and `OffsetTracker`, and together
+//!     with `std`, for `ResumeState` and `TimedOut`, for logging from
+//!     embedded targets.
+//! - `arbitrary`
+//!   - Optional.
+//!   - Enable the module `fuzz`, which implements `arbitrary::Arbitrary` for
+//!     `Finder<u8>`, and together with `alloc`, for `fuzz::SearchCase`, a
+//!     text and a pattern with the position of the first match.
+//! - `prefetch`
+//!   - Optional.
+//!   - Enable `GsSearcherBuilder::prefetch`, for issuing software prefetches
+//!     when scanning large texts.
+//! - `alloc-guard`
+//!   - Optional, implies `std`.
+//!   - Enable the module `alloc_guard`, which counts the heap allocations of
+//!     a closure with `assert_no_alloc`, for tests that demonstrate that the
+//!     searches allocate nothing.
+//! - `skip-debug-checks`
+//!   - Optional.
+//!   - Skip the checks of the pattern preprocessing in debug builds, which
+//!     are slow for long patterns. Use it to search large inputs in the
+//!     debug builds of an application.
+
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![cfg_attr(feature = "benchmarks", feature(test))]
 
+#[cfg(not(any(test, feature = "std")))]
+extern crate core as std;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "bytes")]
+extern crate bytes;
+#[cfg(feature = "codec")]
+extern crate tokio_util;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "proptest")]
+extern crate proptest;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "defmt")]
+extern crate defmt;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "alloc-guard")]
+extern crate assert_no_alloc;
+
 #[cfg(test)]
 #[macro_use] extern crate matches;
 #[macro_use] extern crate defmac;
@@ -56,38 +141,251 @@ extern crate unchecked_index;
 /// a measurable impact.
 defmac!(get slice, index => unsafe { ::unchecked_index::get_unchecked(slice, index) });
 
+// Macro for debug-checked and release-unchecked element access in a
+// `Haystack`.
+defmac!(at ref hay, index => unsafe { Haystack::get_unchecked(hay, index) });
+
 #[macro_use]
 #[doc(hidden)]
 mod test_util;
 #[cfg(test)]
 use test_util::Bytestring;
 #[cfg(feature = "test-functions")]
-pub use test_util::brute_force_search;
-
+pub use test_util::{brute_force_search, de_bruijn_sequence, worst_case_text_for};
+
+#[cfg(feature = "alloc-guard")]
+pub mod alloc_guard;
+mod analysis;
+mod array;
+#[cfg(feature = "bytes")]
+mod bytes_ext;
+#[cfg(feature = "std")]
+mod cache;
+mod chunked;
+mod class;
+#[cfg(feature = "codec")]
+mod codec;
+#[cfg(feature = "std")]
+mod compare;
+mod cost;
+#[cfg(feature = "alloc")]
+mod cover;
+mod coverage;
+mod crochemore;
+#[cfg(feature = "std")]
+mod deadline;
+mod delta;
+mod engine;
+#[cfg(all(feature = "std", feature = "rayon"))]
+mod files;
+mod filtered;
+mod finder;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+#[cfg(feature = "alloc")]
+mod gapped;
+mod growing;
+mod haystack;
+#[cfg(feature = "std")]
+mod highlight;
+#[cfg(feature = "std")]
+pub mod io;
+mod iter;
+mod kmp;
+#[cfg(feature = "std")]
+mod lines;
+mod lyndon;
+#[cfg(feature = "alloc")]
+mod mask;
+mod needle;
+mod palindrome;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod patch;
+mod plain;
+mod position;
+#[cfg(feature = "alloc")]
+mod prefix;
+#[cfg(feature = "std")]
+mod records;
+#[cfg(feature = "std")]
+mod replace;
+mod revcomp;
+mod reversal;
+mod ring;
+mod searchable;
+mod searcher;
+mod seed;
+mod simd;
+mod simple;
+mod sliced;
+mod split;
+mod stats;
+#[cfg(all(feature = "alloc", feature = "proptest"))]
+pub mod strategy;
+mod strs;
+mod swar;
+mod tolerance;
+#[cfg(feature = "alloc")]
+mod tracker;
+#[cfg(feature = "alloc")]
+mod transform;
+mod trim;
+mod two_way;
+mod verify;
+mod wide;
+pub use analysis::{analyze, analyze_by, approximate_period, approximate_period_by,
+                   prefix_periods_array, reach_array, PatternInfo};
+#[cfg(feature = "alloc")]
+pub use analysis::{periods, Periods};
+pub use array::ArrayFinder;
+#[cfg(feature = "bytes")]
+pub use bytes_ext::{BytesMatches, BytesSplit};
+#[cfg(feature = "std")]
+pub use cache::{global_finder_cache, gs_find_cached, FinderCache};
+pub use chunked::OffsetTracker;
+pub use class::ClassPattern;
+#[cfg(feature = "codec")]
+pub use codec::DelimiterCodec;
+#[cfg(feature = "std")]
+pub use compare::{compare_engines, compare_engines_corpus, EngineReport};
+pub use cost::{estimate_cost, CostEstimate};
+#[cfg(feature = "alloc")]
+pub use cover::{covers, shortest_cover};
+pub use coverage::{gs_coverage, Coverage};
+pub use crochemore::Crochemore;
+#[cfg(feature = "std")]
+pub use deadline::{find_with_deadline, find_with_deadline_from, ResumeState, TimedOut};
+pub use delta::{find_offset_invariant, Delta, Deltas};
+pub use engine::{EngineMatches, SearchEngine};
+#[cfg(all(feature = "std", feature = "rayon"))]
+pub use files::search_files;
+pub use filtered::{gs_find_filtered, Filtered};
+pub use finder::{Finder, Match};
+#[cfg(feature = "alloc")]
+pub use gapped::GappedPattern;
+pub use growing::GrowingBufferFinder;
+pub use haystack::{gs_find_in, gs_find_in_by, Haystack, Reversed};
+#[cfg(feature = "std")]
+pub use highlight::{highlight, write_highlighted};
+#[cfg(feature = "std")]
+pub use io::{file_matches, reader_matches, reader_matches_from, search_file, search_reader,
+             Checkpoint, ReaderMatches};
+pub use iter::{BoundaryFn, ContextMatch, ContextMatches, MatchRun, MatchRuns, Matches,
+               Segment, Segments};
+pub use kmp::{EndsWith, Kmp};
+#[cfg(feature = "std")]
+pub use lines::{grep_lines, GrepLines};
+pub use lyndon::{is_lyndon, standard_factor};
+#[cfg(feature = "alloc")]
+pub use mask::{gs_match_mask, MatchMask};
+pub use needle::Needle;
+pub use palindrome::{longest_palindromic_prefix, longest_palindromic_suffix};
+#[cfg(feature = "alloc")]
+pub use palindrome::palindromic_radii;
+#[cfg(feature = "rayon")]
+pub use parallel::par_find;
+pub use patch::{gs_find_mut, gs_for_each_match_mut};
+pub use plain::{gs_find_plain, Plain, Primitive};
+pub use position::{find_with_position, line_column, line_columns, LineColumn, LineColumns};
+#[cfg(feature = "alloc")]
+pub use prefix::{gs_find_prefixes, longest_prefix_match, matching_statistics, PrefixMatches};
+#[cfg(feature = "std")]
+pub use records::RecordReader;
+#[cfg(feature = "std")]
+pub use replace::ReplacingWriter;
+pub use revcomp::{find_with_revcomp, Strand};
+pub use reversal::{find_pattern_or_reverse, gs_rfind, gs_rfind_before, Orientation};
+pub use ring::{gs_find_ring, Ring};
+pub use searchable::GsSearchable;
+pub use searcher::{Engine, GsSearcher, GsSearcherBuilder};
+pub use seed::is_seed;
+#[cfg(feature = "alloc")]
+pub use seed::shortest_seed;
+pub use simd::SimdTwoWay;
+pub use simple::SimplePattern;
+pub use sliced::{SearchPoll, SlicedSearch};
+pub use split::split_at_first_match;
+pub use stats::{gs_find_examined, gs_match_stats, MatchStats};
+pub use strs::{CharMatchIndices, StrMatches};
+pub use tolerance::find_with_tolerance;
+#[cfg(feature = "alloc")]
+pub use tracker::PeriodTracker;
+#[cfg(feature = "alloc")]
+pub use transform::{TransformedHaystack, TransformedMatches};
+pub use trim::{trim_end_matches, trim_matches, trim_start_matches};
+pub use two_way::{maximal_suffix, TwoWay};
+pub use verify::{cube_prefix, cube_prefix_by, debug_verify_match, ends_at, ends_at_by,
+                 is_k_simple, verify_decomposition, verify_match, verify_match_by,
+                 DecompositionError};
+
+
+use std::borrow::Borrow;
+
+use haystack::skip;
+#[cfg(all(debug_assertions, not(feature = "skip-debug-checks")))]
+use haystack::sub;
 
 /// Test if `text` starts with `pattern`.
 // One can use either a direct loop here, or use the libcore == for slices
 // the latter will for example call memcmp in some situations.
 // This function is intended for our use case here, where the (prefix of the)
-// pattern is very short or empty
-fn text_has_prefix<T, F>(text: &[T], pattern: &[T], equal: &F) -> bool
-    where F: Fn(&T, &T) -> bool,
+// pattern is usually very short or empty; but u can be as long as 2 per(v),
+// so a long prefix is compared a chunk at a time, without a branch per
+// element. (`gs_find_bytes` uses memcmp.)
+fn text_has_prefix<T, H, P, F>(text: H, pattern: P, equal: &F) -> bool
+    where H: Haystack,
+          H::Item: Borrow<T>,
+          P: Haystack,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
 {
-    longest_common_prefix_from(0, text, pattern, equal) == pattern.len()
+    let m = pattern.len();
+    let mut i = 0;
+    if m >= PREFIX_CHUNK {
+        debug_assert!(m <= text.len());
+        while i + PREFIX_CHUNK <= m {
+            let mut chunk_equal = true;
+            for k in i..i + PREFIX_CHUNK {
+                chunk_equal &= equal(at!(text, k).borrow(), at!(pattern, k).borrow());
+            }
+            if !chunk_equal {
+                return false;
+            }
+            i += PREFIX_CHUNK;
+        }
+    }
+    longest_common_prefix_from(i, text, pattern, equal) == m
 }
 
+/// The length from which `text_has_prefix` compares a chunk at a time
+const PREFIX_CHUNK: usize = 16;
+
 #[test]
 #[should_panic]
 #[cfg(debug_assertions)]
 fn test_has_prefix_oob() {
-    text_has_prefix(b"abc", b"abcd", &PartialEq::eq);
+    text_has_prefix(b"abc", b"abcd", &u8::eq);
 }
 
 #[test]
 fn test_has_prefix() {
     let data = b"some text goes here";
     for i in 0..data.len() + 1 {
-        assert!(text_has_prefix(data, &data[..i], &PartialEq::eq));
+        assert!(text_has_prefix(data, &data[..i], &u8::eq));
+    }
+
+    // long prefixes, compared in chunks
+    let data = "abcdefghijklmnopqrstuvwxyz".repeat(3);
+    let data = data.as_bytes();
+    let mut pattern = data.to_vec();
+    for len in 0..data.len() + 1 {
+        assert!(text_has_prefix(data, &pattern[..len], &u8::eq));
+        for i in 0..len {
+            pattern[i] = b'.';
+            assert!(!text_has_prefix(data, &pattern[..len], &u8::eq));
+            pattern[i] = data[i];
+        }
     }
 }
 
@@ -100,14 +398,18 @@ fn test_has_prefix() {
 /// pattern: aabaabaaaba
 ///              \.....x
 ///           from = 4 \ return value: from + .. = 4 + 6 = 10
-fn longest_common_prefix_from<T, F>(from: usize, text: &[T], pattern: &[T], equal: &F) -> usize
-    where F: Fn(&T, &T) -> bool,
+fn longest_common_prefix_from<T, H, P, F>(from: usize, text: H, pattern: P, equal: &F) -> usize
+    where H: Haystack,
+          H::Item: Borrow<T>,
+          P: Haystack,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
 {
     debug_assert!(pattern.len() <= text.len());
     debug_assert!(from <= pattern.len());
     let mut i = from;
     while i < pattern.len() {
-        if !equal(get!(text, i), get!(pattern, i)) { return i; }
+        if !equal(at!(text, i).borrow(), at!(pattern, i).borrow()) { return i; }
         i += 1;
     }
     i
@@ -117,8 +419,8 @@ fn longest_common_prefix_from<T, F>(from: usize, text: &[T], pattern: &[T], equa
 fn test_longest_common_prefix_from() {
     let a = b"abcabcaabc";
     let b = b"abcabcabc";
-    assert_eq!(longest_common_prefix_from(0, a, b, &PartialEq::eq), 7);
-    assert_eq!(longest_common_prefix_from(0, a, &b[1..], &PartialEq::eq), 0);
+    assert_eq!(longest_common_prefix_from(0, a, b, &u8::eq), 7);
+    assert_eq!(longest_common_prefix_from(0, a, &b[1..], &u8::eq), 0);
 }
 
 /// The value *k* is a “large enough integer” whose usage becomes clear below;
@@ -126,6 +428,10 @@ fn test_longest_common_prefix_from() {
 /// unmodified.
 const GS_K: usize = 3;
 
+/// The number of elements that `search_simple` prefetches at a time
+#[cfg(feature = "prefetch")]
+const PREFETCH_STEP: usize = 64;
+
 /// Highly-repeating-prefix (HRP)
 ///
 /// # Background
@@ -163,20 +469,33 @@ const GS_K: usize = 3;
 /// of x, etc. *Note* that a second HRP also starts from the beginning of x,
 /// it just has a greater period.
 ///
-/// Compute HRP2, if the period for HRP1 is >= hrp2_period
-fn hrp_by<T, F>(mut period: usize, pattern: &[T], hrp2_period: Option<usize>, equal: &F)
+/// The scan resumes from `pattern[..period + j]`, which must already be known
+/// to have period `period`.
+///
+/// `known` is another periodic prefix of `pattern` that is already known (the
+/// rest of the previous HRP2 in `decomposition_by`): compute HRP2 only if the
+/// period for HRP1 is >= its period, and don't compare the elements inside
+/// it again when scanning with its period.
+fn hrp_by<T, P, F>(mut period: usize, mut j: usize, pattern: P, known: Option<Hrp>, equal: &F)
     -> (Option<Hrp>, Option<Hrp>)
-    where F: Fn(&T, &T) -> bool,
+    where P: Haystack + Copy,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
 {
     let k = GS_K;
     let m = pattern.len();
-    let mut j = 0;        // pattern position
     let mut hrp1 = None;
-    let hrp2_period_limit = hrp2_period.unwrap_or(0);
+    let hrp2_period_limit = known.map_or(0, |known| known.period);
 
     while period + j < m {
+        // pattern[..known.len] has period known.period, so we can skip ahead
+        if let Some(known) = known {
+            if period == known.period && known.len > period + j {
+                j = known.len - period;
+            }
+        }
         // find the greatest length (period + j) with the same period
-        j = longest_common_prefix_from(j, pattern, get!(pattern, period..), equal);
+        j = longest_common_prefix_from(j, pattern, skip(pattern, period), equal);
 
         let prefix_length = period + j;
 
@@ -230,10 +549,10 @@ fn hrp_by<T, F>(mut period: usize, pattern: &[T], hrp2_period: Option<usize>, eq
 }
 
 #[cfg(test)]
-fn hrp<T: Eq>(period: usize, pattern: &[T], hrp2_period: Option<usize>)
+fn hrp<T: Eq>(period: usize, pattern: &[T], known: Option<Hrp>)
     -> (Option<Hrp>, Option<Hrp>)
 {
-    hrp_by(period, pattern, hrp2_period, &PartialEq::eq)
+    hrp_by(period, 0, pattern, known, &T::eq)
 }
 
 #[test]
@@ -280,41 +599,30 @@ fn test_hrp_fuzz_1() {
     assert_matches!(hrp1, None);
 }
 
-#[cfg(any(test, debug_assertions))]
-fn find_k_hrp<T, F>(period: usize, x: &[T], equal: &F) -> Option<usize>
-    where F: Fn(&T, &T) -> bool,
-{
-    let mut pos = 0;
-    let mut period = period;
-    while pos < x.len() && period < x.len() {
-        while pos + period < x.len() && equal(&x[pos], &x[pos + period]) {
-            pos += 1;
-        }
-        if pos + period >= GS_K * period {
-            return Some(period);
-        }
-        pos = 0;
-        period += 1;
-    }
-    None
-}
-
 #[test]
 fn test_find_period() {
-    assert_matches!(find_k_hrp(1, b"aab", &PartialEq::eq), None);
-    assert_matches!(find_k_hrp(1, b"aaab", &PartialEq::eq), Some(1));
-    assert_matches!(find_k_hrp(2, b"abababac", &PartialEq::eq), Some(2));
-    assert_matches!(find_k_hrp(1, b"", &PartialEq::eq), None);
+    use verify::find_k_hrp;
+
+    assert_matches!(find_k_hrp(GS_K, 1, b"aab", &u8::eq), None);
+    assert_matches!(find_k_hrp(GS_K, 1, b"aaab", &u8::eq), Some(Hrp { period: 1, len: 3 }));
+    assert_matches!(find_k_hrp(GS_K, 2, b"abababac", &u8::eq), Some(Hrp { period: 2, len: 7 }));
+    assert_matches!(find_k_hrp(GS_K, 1, b"", &u8::eq), None);
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Highly-repeating-prefix
-struct Hrp {
-    period: usize,
-    len: usize,
+///
+/// A prefix that consists of at least k repetitions of its period.
+pub struct Hrp {
+    /// The period of the prefix
+    pub period: usize,
+    /// The length of the prefix
+    pub len: usize,
 }
 
-/// Decompose `pattern` into two words u, v where u is "short" and v is k-simple.
+/// Decompose `pattern` into two words u, v where u is "short" and v is k-simple;
+/// return the length of u and the k-HRP of v, if it exists.
 ///
 /// When *k* >= 3, words satisfy a remarkable combinatorial property:
 /// 
@@ -362,26 +670,40 @@ struct Hrp {
 /// Let u = x[1 .. j] and v = x[j + 1 .. n]. Then the decomposition uv of x
 /// is k-perfect for k >= 3.
 ///
-fn decompose_by<'a, T, F>(pattern: &'a [T], equal: &F) -> (&'a [T], &'a [T], Option<Hrp>)
-    where F: Fn(&T, &T) -> bool,
+fn decomposition_by<T, P, F>(pattern: P, equal: &F) -> (usize, Option<Hrp>)
+    where P: Haystack + Copy,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
 {
     let mut j = 0;
-    let (mut hrp1_opt, mut hrp2_opt) = hrp_by(1, pattern, None, equal);
+    // the position where hrp2_opt was found
+    let mut hrp2_pos = 0;
+    let (mut hrp1_opt, mut hrp2_opt) = hrp_by(1, 0, pattern, None, equal);
     loop {
         if let Some(hrp1) = hrp1_opt {
             if let Some(hrp2) = hrp2_opt {
                 // if x = v1 x' where v1 is a prefix period of x (v1 is HRP1)
                 // x' = x[p..] where p = |v1|
+                let hrp1_end = j + hrp1.len;
                 j += hrp1.period;
 
+                // x' still has the periods of HRP1 and HRP2, up to their ends;
+                // carry that over, so that those parts are not scanned again.
+                let hrp1_j = hrp1_end.saturating_sub(j + hrp1.period);
+                let known = Hrp {
+                    period: hrp2.period,
+                    len: (hrp2_pos + hrp2.len).saturating_sub(j),
+                };
+
                 // size is nondecreasing: so start with the HRP1(x) period.
                 // compute HRP1(x') and (if needed) HRP2(x')
-                let (h1, h2) = hrp_by(hrp1.period, get!(pattern, j..),
-                                      Some(hrp2.period), equal);
+                let (h1, h2) = hrp_by(hrp1.period, hrp1_j, skip(pattern, j), Some(known),
+                                      equal);
                 hrp1_opt = h1;
                 if let Some(ref hrp1) = h1 {
                     if hrp1.period >= hrp2.period {
                         hrp2_opt = h2;
+                        hrp2_pos = j;
                     }
                     continue;
                 }
@@ -389,10 +711,17 @@ fn decompose_by<'a, T, F>(pattern: &'a [T], equal: &F) -> (&'a [T], &'a [T], Opt
         }
         break;
     }
-    let (a, b) = (get!(pattern, ..j), get!(pattern, j..));
-    #[cfg(debug_assertions)]
-    assert_perfect_decomposition(GS_K, a, b, equal);
-    (a, b, hrp1_opt)
+    #[cfg(all(debug_assertions, not(feature = "skip-debug-checks")))]
+    assert_perfect_decomposition(GS_K, sub(pattern, 0..j), skip(pattern, j), equal);
+    (j, hrp1_opt)
+}
+
+/// Decompose `pattern` into two words u, v; see `decomposition_by`.
+fn decompose_by<'a, T, F>(pattern: &'a [T], equal: &F) -> (&'a [T], &'a [T], Option<Hrp>)
+    where F: Fn(&T, &T) -> bool,
+{
+    let (j, hrp1) = decomposition_by(pattern, equal);
+    (get!(pattern, ..j), get!(pattern, j..), hrp1)
 }
 
 #[cfg(test)]
@@ -482,17 +811,35 @@ fn test_decompose_period_mega() {
     assert_matches!(hrp, Some(Hrp { period: HRP1, .. }));
 }
 
+#[test]
+fn test_decompose_exhaustive() {
+    // decompose asserts that the decomposition is perfect in debug builds
+    let mut pattern = Vec::new();
+    for len in 0..15 {
+        for bits in 0..1u32 << len {
+            pattern.clear();
+            pattern.extend((0..len).map(|i| b'a' + (bits >> i & 1) as u8));
+            let (u, v, hrp) = decompose(&pattern);
+            assert_eq!(u.len() + v.len(), len);
+            assert_eq!(hrp, hrp_by(1, 0, v, None, &u8::eq).0);
+        }
+    }
+}
+
 
 /// Assert that the input = u v is a perfect factorization
-#[cfg(debug_assertions)]
-fn assert_perfect_decomposition<T, F>(k: usize, u: &[T], v: &[T], equal: &F)
-    where F: Fn(&T, &T) -> bool,
+#[cfg(all(debug_assertions, not(feature = "skip-debug-checks")))]
+fn assert_perfect_decomposition<T, U, V, F>(k: usize, u: U, v: V, equal: &F)
+    where U: Haystack,
+          V: Haystack + Copy,
+          V::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
 {
     // require that a decomp x = u v
     // that u is "short" and v is k-simple.
     // k-simple means it has at most one k-HRP which also means it has no k-HRP2
     assert!(k >= 3);
-    if let (Some(hrp1), hrp2) = hrp_by(1, v, None, equal) {
+    if let (Some(hrp1), hrp2) = hrp_by(1, 0, v, None, equal) {
         if let Some(hrp2) = hrp2 {
             panic!("Factorization u, v = {} , {} is not k-simple because
                     v's {}-HRP1 is {:?} and {}-HRP2 is {:?}",
@@ -501,11 +848,8 @@ fn assert_perfect_decomposition<T, F>(k: usize, u: &[T], v: &[T], equal: &F)
         }
     }
     // independent check
-    if let Some(prefix_period1) = find_k_hrp(1, v, equal) {
-        // ok, but must not have a second one, or if it has it's a multiple
-        if let Some(prefix_period2) = find_k_hrp(prefix_period1 * 2 + 1, v, equal) {
-            assert_eq!(prefix_period2 % prefix_period1, 0);
-        }
+    if let Err(error) = verify::verify_decomposition_by(k, u.len(), v, equal) {
+        panic!("{}", error);
     }
     // ok
 }
@@ -514,17 +858,27 @@ fn assert_perfect_decomposition<T, F>(k: usize, u: &[T], v: &[T], equal: &F)
 /// is k-simple; which means it has at most one k-HRP.
 ///
 /// `start_pos` is the position to start the search, and it is updated after
-/// the function returns with a match.
-fn search_simple<T, F>(text: &[T], pattern: &[T],
-                       start_pos: &mut usize,
-                       start_j: &mut usize,
-                       hrp1: &Option<Hrp>,
-                       equal: &F)
+/// the function returns, so that the search can be resumed; without a match,
+/// it can be resumed when the text grows at the end.
+///
+/// With crate feature `prefetch`, the text is prefetched `prefetch` elements
+/// ahead of the scan position, if it is nonzero.
+fn search_simple<T, H, P, F>(text: H, pattern: P,
+                             start_pos: &mut usize,
+                             start_j: &mut usize,
+                             hrp1: &Option<Hrp>,
+                             prefetch: usize,
+                             equal: &F)
     -> Option<usize>
-    where F: Fn(&T, &T) -> bool,
+    where H: Haystack + Copy,
+          H::Item: Borrow<T>,
+          P: Haystack + Copy,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
 {
     debug_assert!(pattern.len() <= text.len());
-    debug_assert_eq!(hrp_by(1, pattern, None, &equal), (*hrp1, None));
+    #[cfg(not(feature = "skip-debug-checks"))]
+    debug_assert_eq!(hrp_by(1, 0, pattern, None, &equal), (*hrp1, None));
 
     let n = text.len();
     let m = pattern.len();
@@ -555,8 +909,20 @@ fn search_simple<T, F>(text: &[T], pattern: &[T],
 
     let mut pos = *start_pos; // text position
     let mut j = *start_j;     // pattern position
+    #[cfg(feature = "prefetch")]
+    let mut prefetched = pos; // prefetch again from this position
+    #[cfg(not(feature = "prefetch"))]
+    let _ = prefetch;
     while pos <= n - m {
-        j = longest_common_prefix_from(j, get!(text, pos..), pattern, &equal);
+        #[cfg(feature = "prefetch")]
+        {
+            if prefetch > 0 && pos >= prefetched {
+                let ahead = pos.saturating_add(prefetch);
+                text.prefetch(ahead..ahead.saturating_add(PREFETCH_STEP));
+                prefetched = pos + PREFETCH_STEP;
+            }
+        }
+        j = longest_common_prefix_from(j, skip(text, pos), pattern, &equal);
         let has_match = if j == m { Some(pos) } else { None };
         if has_scope && j >= scope_l && j <= scope_r {
             pos += scope_l / 2;
@@ -571,6 +937,8 @@ fn search_simple<T, F>(text: &[T], pattern: &[T],
             return Some(match_pos);
         }
     }
+    *start_pos = pos;
+    *start_j = j;
     None
 }
 
@@ -578,7 +946,10 @@ fn search_simple<T, F>(text: &[T], pattern: &[T],
 ///
 /// If a match exists where `pattern` is a substring of `text`, return the
 /// offset to the start of the match inside `Some(_)`. If not, return `None`.
-pub fn gs_find<T: Eq>(text: &[T], pattern: &[T]) -> Option<usize> {
+///
+/// The `pattern` can be any `Needle`, for example a slice, an array or a
+/// reference to a single element.
+pub fn gs_find<T: Eq>(text: &[T], pattern: impl Needle<T>) -> Option<usize> {
     gs_find_by(text, pattern, T::eq)
 }
 
@@ -587,11 +958,52 @@ pub fn gs_find<T: Eq>(text: &[T], pattern: &[T]) -> Option<usize> {
 /// If a match exists where `pattern` is a substring of `text`, return the
 /// offset to the start of the match inside `Some(_)`. If not, return `None`.
 ///
-/// Use `equal` as the equality comparison function.
-pub fn gs_find_by<T, F>(text: &[T], pattern: &[T], equal: F) -> Option<usize>
+/// Use `equal` as the equality comparison function. It must be an equivalence
+/// relation; for approximate equality use `find_with_tolerance` instead.
+pub fn gs_find_by<T, F>(text: &[T], pattern: impl Needle<T>, equal: F) -> Option<usize>
     where F: Fn(&T, &T) -> bool,
 {
-    Pattern::preprocess_using(pattern, equal).find_in(text)
+    Pattern::preprocess_using(pattern.as_needle(), equal).find_in(text)
+}
+
+/// This is the Galil-Seiferas string matching algorithm, for byte strings.
+///
+/// This is specialized for bytes: partial matches are compared a word at a
+/// time, which is faster than `gs_find` on most texts.
+///
+/// It is not generic, so all the callers share one copy of the search;
+/// where code size matters, prefer it to `gs_find::<u8>`. See
+/// `gs_find_as_bytes` for searching strings and other byte containers.
+///
+/// If a match exists where `pattern` is a substring of `text`, return the
+/// offset to the start of the match inside `Some(_)`. If not, return `None`.
+///
+/// ```
+/// use galil_seiferas::gs_find_bytes;
+///
+/// assert_eq!(gs_find_bytes(b"bananas", b"nana"), Some(2));
+/// ```
+pub fn gs_find_bytes(text: &[u8], pattern: &[u8]) -> Option<usize> {
+    swar::find_fast(text, pattern)
+}
+
+/// This is `gs_find_bytes`, for anything that can be viewed as a byte slice.
+///
+/// Both `text` and `pattern` can be for example `&str`, `String`, `Vec<u8>`
+/// or `&[u8]`. This is only a thin wrapper, so each instantiation is small.
+///
+/// ```
+/// use galil_seiferas::gs_find_as_bytes;
+///
+/// assert_eq!(gs_find_as_bytes(b"bananas", "nana"), Some(2));
+/// assert_eq!(gs_find_as_bytes("bananas", String::from("s")), Some(6));
+/// ```
+#[inline]
+pub fn gs_find_as_bytes<H, N>(text: H, pattern: N) -> Option<usize>
+    where H: AsRef<[u8]>,
+          N: AsRef<[u8]>,
+{
+    gs_find_bytes(text.as_ref(), pattern.as_ref())
 }
 
 /// A preprocessed pattern.
@@ -604,6 +1016,7 @@ pub struct Pattern<'a, T: 'a, F = ()> {
 }
 
 impl<'a, T, F> Pattern<'a, T, F> {
+    #[cfg(feature = "alloc")]
     fn len(&self) -> usize {
         self.u.len() + self.v.len()
     }
@@ -651,23 +1064,128 @@ impl<'a, T, F> Pattern<'a, T, F>
     /// offset to the start of the match inside `Some(_)`. If not, return
     /// `None`.
     pub fn find_in(&self, text: &[T]) -> Option<usize> {
-        if text.len() < self.len() {
-            return None;
+        self.find_from(text, &mut SearchState::default())
+    }
+
+    /// Search `text` starting from the search `state`, which is updated so
+    /// that the search can be resumed after the match that is returned.
+    pub(crate) fn find_from(&self, text: &[T], state: &mut SearchState) -> Option<usize> {
+        find_from_by(text, self.u, self.v, self.hrp1, state, &self.equal)
+    }
+
+    /// Search `text` starting from the search `state`, like `find_from`;
+    /// without a match, the search can be resumed when the text grows.
+    pub(crate) fn search_from(&self, text: &[T], state: &mut SearchState) -> Option<usize> {
+        search_from_by(text, self.u, self.v, self.hrp1, state, &self.equal)
+    }
+}
+
+/// Search `text` for the pattern u v, where the decomposition u, v and
+/// the k-HRP of v, `hrp1`, are computed by `decomposition_by`.
+///
+/// The search starts from `state` and it is updated so that the search can
+/// be resumed after the match that is returned.
+fn find_from_by<T, H, U, V, F>(text: H, u: U, v: V, hrp1: Option<Hrp>,
+                               state: &mut SearchState, equal: &F)
+    -> Option<usize>
+    where H: Haystack + Copy,
+          H::Item: Borrow<T>,
+          U: Haystack + Copy,
+          U::Item: Borrow<T>,
+          V: Haystack + Copy,
+          V::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    let m = u.len() + v.len();
+    if text.len() < m || state.pos > text.len() - m {
+        return None;
+    }
+    let found = search_from_by(text, u, v, hrp1, state, equal);
+    if found.is_none() {
+        state.pos = text.len() + 1;
+    }
+    found
+}
+
+/// Search `text` for the pattern u v, like `find_from_by`.
+///
+/// Without a match, `state` is left where the search stopped, so that it
+/// can be resumed when more elements are appended to the text.
+fn search_from_by<T, H, U, V, F>(text: H, u: U, v: V, hrp1: Option<Hrp>,
+                                 state: &mut SearchState, equal: &F)
+    -> Option<usize>
+    where H: Haystack + Copy,
+          H::Item: Borrow<T>,
+          U: Haystack + Copy,
+          U::Item: Borrow<T>,
+          V: Haystack + Copy,
+          V::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    let m = u.len() + v.len();
+    if text.len() < m || state.pos > text.len() - m {
+        return None;
+    }
+
+    // This is where the Galil-Seiferas string matching algorithm is
+    // put together; the pattern was decomposed into u, v; now find each
+    // occurence of v in the text, then check if u precedes it.
+    //
+    // Often u is empty, and then every occurence of v is a match.
+    if u.len() == 0 {
+        return search_simple(text, v, &mut state.pos, &mut state.j,
+                             &hrp1, state.prefetch, equal);
+    }
+    while let Some(i) = search_simple(skip(text, u.len()), v, &mut state.pos, &mut state.j,
+                                      &hrp1, state.prefetch, equal)
+    {
+        if text_has_prefix(skip(text, i), u, equal) {
+            return Some(i);
         }
+    }
+    None
+}
 
-        // This is where the Galil-Seiferas string matching algorithm is
-        // put together; the pattern was decomposed into u, v; now find each
-        // occurence of v in the text, then check if u precedes it.
-        let (u, v, hrp1) = (self.u, self.v, self.hrp1);
-        let (mut pos, mut j) = (0, 0);
-        while let Some(i) = search_simple(get!(text, u.len()..), v,
-                                          &mut pos, &mut j, &hrp1, &self.equal)
-        {
-            if text_has_prefix(get!(text, i..), u, &self.equal) {
-                return Some(i);
-            }
+#[test]
+fn test_find_from_resume() {
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbbaaabaaabaaabaabbbb";
+    // the last pattern has a nonempty u
+    for pattern in &[&b"abaab"[..], b"aab", b"aabaab", b"baabaabaab", b"", b"aaabaaabaaabaabbbb"] {
+        let p = Pattern::preprocess_using(pattern, u8::eq);
+        let mut state = SearchState::default();
+        let mut found = Vec::new();
+        while let Some(i) = p.find_from(text, &mut state) {
+            found.push(i);
         }
-        None
+        let expected = (0..text.len() + 1)
+            .filter(|&i| text[i..].starts_with(pattern))
+            .collect::<Vec<_>>();
+        assert_eq!(found, expected);
+        assert_eq!(p.find_from(text, &mut state), None);
+    }
+}
+
+/// The state of a search in progress: the text position of the next
+/// candidate match and the pattern position in v (see `search_simple`),
+/// and the prefetch distance.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct SearchState {
+    pub(crate) pos: usize,
+    pub(crate) j: usize,
+    pub(crate) prefetch: usize,
+}
+
+impl SearchState {
+    /// A state that starts the search at `pos`
+    pub(crate) fn at(pos: usize) -> Self {
+        SearchState { pos, j: 0, prefetch: 0 }
+    }
+
+    /// Restart the search at `pos`
+    pub(crate) fn restart(&mut self, pos: usize) {
+        self.pos = pos;
+        self.j = 0;
     }
 }
 
@@ -708,6 +1226,18 @@ fn test_gs_find_vs_str_find() {
     test_str!("", "aaabaaabaaabaa");
 }
 
+#[test]
+fn test_gs_find_bytes() {
+    let text = String::from("aa\u{e5}bb\u{e5}");
+    assert_eq!(gs_find_as_bytes(&text, "\u{e5}"), Some(2));
+    assert_eq!(gs_find_as_bytes(text.as_bytes(), vec![b'b', b'b']), Some(4));
+    assert_eq!(gs_find_as_bytes(vec![1, 2, 3], [2, 3]), Some(1));
+    assert_eq!(gs_find_as_bytes("", ""), Some(0));
+    assert_eq!(gs_find_as_bytes("a", "ab"), None);
+    assert_eq!(gs_find_bytes(text.as_bytes(), b"b\xc3"), Some(5));
+    assert_eq!(gs_find_bytes(b"", b""), Some(0));
+}
+
 #[test]
 fn test_gs_find2() {
     // found by cargo fuzz; no bug but proved the need of scope_l/scope_r check in hrp
@@ -722,7 +1252,7 @@ fn test_gs_find2() {
 defmac!(assert_find_substring text, range => {
     let text = &text[..];
     let needle = &text[range.clone()];
-    assert!(text_has_prefix(&text[range.start..], needle, &PartialEq::eq),
+    assert!(text_has_prefix(&text[range.start..], needle, &u8::eq),
             "buggy test: not a substring at {:?}", range);
     assert_eq!(Some(range.start), gs_find(text, needle));
 });
@@ -763,7 +1293,7 @@ fn test_find_fuzz_2() {
 mod benches {
     extern crate test;
     use self::test::Bencher;
-    use super::gs_find;
+    use super::{gs_find, gs_find_bytes};
     use super::decompose;
     use super::test_util::brute_force_search;
 
@@ -801,6 +1331,46 @@ mod benches {
         b.bytes = pattern.len() as u64;
     }
 
+    // nested periods: ((a^4 b)^4 c)^4 ..; each HRP2 is the next HRP1
+    defmac!(nested_periodic letters => {
+        let mut s = String::new();
+        for ch in letters.chars() {
+            s.push(ch);
+            s = s.repeat(4);
+        }
+        s
+    });
+
+    #[bench]
+    fn bench_g_decompose_nested_periodic_small(b: &mut Bencher) {
+        let pattern = nested_periodic!("abcd");
+
+        b.iter(|| {
+            decompose(pattern.as_bytes());
+        });
+        b.bytes = pattern.len() as u64;
+    }
+
+    #[bench]
+    fn bench_g_decompose_nested_periodic_large(b: &mut Bencher) {
+        let pattern = nested_periodic!("abcdefghi");
+
+        b.iter(|| {
+            decompose(pattern.as_bytes());
+        });
+        b.bytes = pattern.len() as u64;
+    }
+
+    #[bench]
+    fn bench_g_decompose_periodic_large(b: &mut Bencher) {
+        let pattern = "aaaaacargo".repeat(100_000);
+
+        b.iter(|| {
+            decompose(pattern.as_bytes());
+        });
+        b.bytes = pattern.len() as u64;
+    }
+
     defmac!(haystack n => ("ab".repeat(n - 1) + "bb").repeat(n));
     defmac!(haystack_inv n => (String::from("bb") + &"ab".repeat(n - 1)).repeat(n));
 
@@ -870,6 +1440,18 @@ mod benches {
         b.bytes = haystack.len() as u64;
     }
 
+    #[bench]
+    fn bench_gs_find_bytes_periodic2_large(b: &mut Bencher) {
+        let n = PER_LARGE;
+        let haystack = haystack!(n);
+        let pattern = "ab".repeat(n);
+
+        b.iter(|| {
+            gs_find_bytes(haystack.as_bytes(), pattern.as_bytes())
+        });
+        b.bytes = haystack.len() as u64;
+    }
+
     #[bench]
     fn bench_brute_periodic2_large(b: &mut Bencher) {
         let n = PER_LARGE;
@@ -956,6 +1538,18 @@ mod benches {
         b.bytes = haystack.len() as u64;
     }
 
+    #[bench]
+    fn bench_gs_find_bytes_itself4(b: &mut Bencher) {
+        let haystack = "this is actually a longer text where them xxxx xxxxx\
+            could be tricked by and so on.".repeat(10) + "itself.";
+        let pattern = "itself";
+
+        b.iter(|| {
+            gs_find_bytes(haystack.as_bytes(), pattern.as_bytes())
+        });
+        b.bytes = haystack.len() as u64;
+    }
+
     #[bench]
     fn bench_brute_itself4(b: &mut Bencher) {
         let haystack = "this is actually a longer text where them xxxx xxxxx\
@@ -980,6 +1574,18 @@ mod benches {
         b.bytes = haystack.len() as u64;
     }
 
+    #[bench]
+    fn bench_gs_find_bytes_itself5(b: &mut Bencher) {
+        let haystack = "this is actually a longer text where them itsel itselg\
+            could be tricked by and so on.".repeat(10) + "itself.";
+        let pattern = "itself";
+
+        b.iter(|| {
+            gs_find_bytes(haystack.as_bytes(), pattern.as_bytes())
+        });
+        b.bytes = haystack.len() as u64;
+    }
+
     #[bench]
     fn bench_brute_itself5(b: &mut Bencher) {
         let haystack = "this is actually a longer text where them itsel itselg\
@@ -1000,7 +1606,7 @@ mod benches {
         let haystack: Vec<_> = haystack_s.split("x").collect();
         let needle: Vec<_> = needle_s.split("x").collect();
         b.iter(|| {
-            gs_find(&haystack, &needle)
+            gs_find(&haystack, &needle[..])
         });
     }
 
@@ -1022,7 +1628,7 @@ mod benches {
         let haystack: Vec<_> = (0..n).map(|i| format!("foo{}", i)).collect();
         let needle: Vec<_> = (n - 10..n).map(|i| format!("foo{}", i)).collect();
         b.iter(|| {
-            gs_find(&haystack, &needle)
+            gs_find(&haystack, &needle[..])
         });
     }
 
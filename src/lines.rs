@@ -0,0 +1,139 @@
+
+use std::cmp::max;
+use std::io::{self, BufRead};
+
+use alloc::vec::Vec;
+
+use finder::Finder;
+
+/// Find the matches of `pattern` within each line of `reader`; yield the
+/// line number (from 1) and the byte offset of each match in its line.
+///
+/// Lines are terminated by `"\n"`. The lines are not read whole: only the
+/// last m − 1 bytes of each buffer from the reader are kept, and searched
+/// again together with the next buffer, so lines of any length are searched
+/// with bounded memory. A pattern that contains `"\n"` never matches.
+///
+/// **Panics** if `pattern` is empty.
+///
+/// ```
+/// use galil_seiferas::grep_lines;
+///
+/// let input = &b"one fish\ntwo fish\nred\nfish fish\n"[..];
+/// let found = grep_lines(input, b"fish").collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(found, [(1, 4), (2, 4), (4, 0), (4, 5)]);
+/// ```
+pub fn grep_lines<R: BufRead>(reader: R, pattern: &[u8]) -> GrepLines<R> {
+    assert!(!pattern.is_empty(), "grep_lines: the pattern must not be empty");
+    GrepLines {
+        reader,
+        finder: Finder::from_vec(pattern.to_vec()),
+        window: Vec::new(),
+        pos: 0,
+        line: 1,
+        line_offset: 0,
+        line_end: false,
+    }
+}
+
+/// An iterator of the matches in each line of a reader, created by
+/// `grep_lines`.
+pub struct GrepLines<R> {
+    reader: R,
+    finder: Finder<'static, u8>,
+    /// The part of the current line that is searched
+    window: Vec<u8>,
+    /// The search position in the window
+    pos: usize,
+    /// The current line number
+    line: u64,
+    /// The offset of the window in the current line
+    line_offset: usize,
+    /// True if the window extends to the end of the current line
+    line_end: bool,
+}
+
+impl<R: BufRead> GrepLines<R> {
+    /// Read the next part of the line into the window. Return false at the
+    /// end of the input.
+    fn fill_window(&mut self) -> io::Result<bool> {
+        if self.line_end {
+            self.line += 1;
+            self.line_offset = 0;
+            self.window.clear();
+            self.line_end = false;
+        } else {
+            // A match can start in the last m - 1 bytes of the window
+            let keep = self.finder.pattern().len() - 1;
+            let drop = max(self.pos, self.window.len().saturating_sub(keep));
+            self.window.drain(..drop);
+            self.line_offset += drop;
+        }
+        self.pos = 0;
+        let (used, eof) = {
+            let buf = loop {
+                match self.reader.fill_buf() {
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    result => break result?,
+                }
+            };
+            match buf.iter().position(|&b| b == b'\n') {
+                Some(i) => {
+                    self.window.extend_from_slice(&buf[..i]);
+                    self.line_end = true;
+                    (i + 1, false)
+                }
+                None => {
+                    self.window.extend_from_slice(buf);
+                    (buf.len(), buf.is_empty())
+                }
+            }
+        };
+        self.reader.consume(used);
+        Ok(!eof)
+    }
+}
+
+impl<R: BufRead> Iterator for GrepLines<R> {
+    type Item = io::Result<(u64, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(m) = self.finder.find(&self.window[self.pos..]) {
+                let start = self.pos + m.start();
+                self.pos = start + m.len();
+                return Some(Ok((self.line, self.line_offset + start)));
+            }
+            match self.fill_window() {
+                Ok(true) => { }
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_grep_lines() {
+    use std::io::BufReader;
+
+    let grep = |input: &[u8], pattern: &[u8], capacity| {
+        grep_lines(BufReader::with_capacity(capacity, input), pattern)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+    };
+    let input = b"abcab\nab\n\nxxab\nabab";
+    let expected = vec![(1, 0), (1, 3), (2, 0), (4, 2), (5, 0), (5, 2)];
+    for capacity in 1..10 {
+        assert_eq!(grep(input, b"ab", capacity), expected);
+    }
+    assert_eq!(grep(input, b"b\na", 10), vec![]);
+
+    // A line that is much longer than the buffer
+    let mut long = "xy".repeat(1000).into_bytes();
+    long.extend_from_slice(b"xyz\nxyz");
+    for capacity in 1..8 {
+        assert_eq!(grep(&long, b"xyxyz", capacity), vec![(1, 1998)]);
+        assert_eq!(grep(&long, b"xyz", capacity), vec![(1, 2000), (2, 0)]);
+    }
+}
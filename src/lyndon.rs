@@ -0,0 +1,104 @@
+
+/// Return true if `x` is a Lyndon word.
+///
+/// A Lyndon word is a non-empty word that is strictly less than each of its
+/// proper suffixes (where a proper prefix is less than the word), or
+/// equivalently, that is strictly less than each of its other rotations. A
+/// Lyndon word is primitive, so it has no border.
+///
+/// This computes in **O(m)** time and **O(1)** space.
+///
+/// ```
+/// use galil_seiferas::is_lyndon;
+///
+/// assert!(is_lyndon(b"aabab"));
+/// assert!(!is_lyndon(b"abab"));
+/// assert!(!is_lyndon(b"ba"));
+/// ```
+pub fn is_lyndon<T: Ord>(x: &[T]) -> bool {
+    !x.is_empty() && minimal_suffix(x) == 0
+}
+
+/// Return the split point of the standard factorization of the Lyndon word
+/// `x`, or `None` if `x` is not a Lyndon word or is a single element.
+///
+/// The standard factorization is `x = uv` where `v` is the longest proper
+/// suffix of `x` that is a Lyndon word; then `u` is a Lyndon word too, and
+/// `u < v`. The return value is the length of `u`.
+///
+/// This computes in **O(m)** time and **O(1)** space.
+///
+/// ```
+/// use galil_seiferas::standard_factor;
+///
+/// let x = b"aabab";
+/// assert_eq!(standard_factor(x), Some(3)); // aab, ab
+/// assert_eq!(standard_factor(b"a"), None);
+/// ```
+pub fn standard_factor<T: Ord>(x: &[T]) -> Option<usize> {
+    if x.len() < 2 || !is_lyndon(x) {
+        return None;
+    }
+    // The longest proper Lyndon suffix is the smallest proper suffix
+    Some(1 + minimal_suffix(&x[1..]))
+}
+
+/// Return the start of the lexicographically smallest suffix of `x`, where
+/// a proper prefix is less than the word, or zero if `x` is empty.
+///
+/// This is the last factor of the Lyndon factorization of `x`, which is
+/// computed using Duval's algorithm.
+fn minimal_suffix<T: Ord>(x: &[T]) -> usize {
+    let n = x.len();
+    let mut start = 0;
+    loop {
+        // x[start..j] is a power of a Lyndon word of period j - k, and a
+        // prefix of one more repetition
+        let (mut j, mut k) = (start + 1, start);
+        while j < n && x[k] <= x[j] {
+            k = if x[k] < x[j] { start } else { k + 1 };
+            j += 1;
+        }
+        let period = j - k;
+        // Skip all the repetitions but the last one
+        while start + period <= k {
+            start += period;
+        }
+        if start + period >= n {
+            return start;
+        }
+        start += period;
+    }
+}
+
+#[test]
+fn test_lyndon() {
+    assert!(!is_lyndon::<u8>(b""));
+    assert!(is_lyndon(b"a"));
+    assert!(!is_lyndon(b"aa"));
+    assert_eq!(standard_factor::<u8>(b""), None);
+    assert_eq!(standard_factor(b"ab"), Some(1));
+    assert_eq!(standard_factor(b"aab"), Some(1));
+    assert_eq!(standard_factor(b"abb"), Some(2));
+    assert_eq!(minimal_suffix::<u8>(b""), 0);
+
+    // Every ternary word of length < 9
+    for len in 1..9 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| b'a' + (n / 3u32.pow(i) % 3) as u8).collect::<Vec<_>>();
+            let smallest = (0..x.len()).min_by_key(|&i| &x[i..]).unwrap();
+            assert_eq!(minimal_suffix(&x), smallest, "{}", ::Bytestring(&x));
+            let lyndon = (1..x.len()).all(|i| x[..] < x[i..]);
+            assert_eq!(is_lyndon(&x), lyndon, "{}", ::Bytestring(&x));
+            let expected = if lyndon {
+                (1..x.len()).find(|&i| (i + 1..x.len()).all(|j| x[i..] < x[j..]))
+            } else {
+                None
+            };
+            assert_eq!(standard_factor(&x), expected, "{}", ::Bytestring(&x));
+            if let Some(i) = expected {
+                assert!(is_lyndon(&x[..i]) && x[..i] < x[i..]);
+            }
+        }
+    }
+}
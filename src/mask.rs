@@ -0,0 +1,94 @@
+
+use std::iter::repeat_n;
+
+use alloc::vec::Vec;
+
+use searcher::GsSearcherBuilder;
+
+/// A bit vector with one bit per text position, created by `gs_match_mask`.
+///
+/// The bits are stored in `u64` words, least significant bit first: position
+/// `i` is bit `i % 64` of word `i / 64`. Unused bits in the last word are
+/// zero.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchMask {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl MatchMask {
+    /// The number of positions
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if there are no positions
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return true if a match starts at position `index`.
+    ///
+    /// **Panics** if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "MatchMask::get: index {} out of bounds", index);
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// The number of match starts
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The bits as words
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Return the bits as words
+    pub fn into_words(self) -> Vec<u64> {
+        self.words
+    }
+}
+
+/// Find all the matches of `pattern` in `text`, including overlapping matches,
+/// and return a bit vector that marks each position where a match starts.
+///
+/// The mask is produced in a single **O(n)** pass.
+///
+/// ```
+/// use galil_seiferas::gs_match_mask;
+///
+/// let mask = gs_match_mask(b"abababxab", b"aba");
+/// assert_eq!(mask.len(), 9);
+/// assert_eq!(mask.as_words(), &[0b101]);
+/// ```
+pub fn gs_match_mask<T: Eq>(text: &[T], pattern: &[T]) -> MatchMask {
+    let mut words = repeat_n(0, text.len().div_ceil(64)).collect::<Vec<_>>();
+    // The empty pattern also matches at the end of the text, which has
+    // no position in the mask.
+    let searcher = GsSearcherBuilder::new().overlapping(true).build(pattern);
+    for m in searcher.find_iter(text) {
+        let i = m.start();
+        if i < text.len() {
+            words[i / 64] |= 1 << (i % 64);
+        }
+    }
+    MatchMask { words, len: text.len() }
+}
+
+#[test]
+fn test_match_mask() {
+    let text = "ab".repeat(70);
+    let mask = gs_match_mask(text.as_bytes(), b"abab");
+    assert_eq!(mask.len(), 140);
+    assert_eq!(mask.count_ones(), 69);
+    assert_eq!(mask.as_words(), &[0x5555_5555_5555_5555, 0x5555_5555_5555_5555, 0x155]);
+    assert!(mask.get(136) && !mask.get(137) && !mask.get(138));
+
+    let mask = gs_match_mask(b"abc", b"");
+    assert_eq!(mask.into_words(), vec![0b111]);
+    let mask = gs_match_mask(b"", b"x");
+    assert!(mask.is_empty());
+    assert_eq!(mask.count_ones(), 0);
+}
@@ -0,0 +1,94 @@
+
+use std::slice;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// A pattern that can be searched for.
+///
+/// This is implemented for slices, arrays, vectors and references to single
+/// elements, so that `gs_find` can be called like this:
+///
+/// ```
+/// use galil_seiferas::gs_find;
+///
+/// let text = b"abcabc";
+/// assert_eq!(gs_find(text, b"ca"), Some(2));
+/// assert_eq!(gs_find(text, [b'b', b'c']), Some(1));
+/// assert_eq!(gs_find(text, &b'c'), Some(2));
+/// ```
+pub trait Needle<T> {
+    /// Return the pattern as a slice
+    fn as_needle(&self) -> &[T];
+}
+
+impl<T> Needle<T> for &[T] {
+    fn as_needle(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> Needle<T> for &mut [T] {
+    fn as_needle(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Needle<T> for &[T; N] {
+    fn as_needle(&self) -> &[T] {
+        &self[..]
+    }
+}
+
+impl<T, const N: usize> Needle<T> for [T; N] {
+    fn as_needle(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> Needle<T> for &T {
+    fn as_needle(&self) -> &[T] {
+        slice::from_ref(*self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Needle<T> for &Vec<T> {
+    fn as_needle(&self) -> &[T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Needle<T> for Vec<T> {
+    fn as_needle(&self) -> &[T] {
+        self
+    }
+}
+
+#[test]
+fn test_needle() {
+    use {gs_find, gs_find_by};
+
+    let text = [1, 2, 3, 2, 3, 4];
+    assert_eq!(gs_find(&text, [2, 3]), Some(1));
+    let array = &[2, 3, 4];
+    assert_eq!(gs_find(&text, array), Some(3));
+    assert_eq!(gs_find(&text, &4), Some(5));
+    assert_eq!(gs_find(&text, &text[1..3]), Some(1));
+    assert_eq!(gs_find(&text, []), Some(0));
+    assert_eq!(gs_find::<i32>(&text, &text[4..]), Some(4));
+    assert_eq!(gs_find_by::<i32, _>(&text, [2, 3], |a, b| a == b), Some(1));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_needle_vec() {
+    use {gs_find, gs_find_by};
+
+    let text = [1, 2, 3, 2, 3, 4];
+    let vec = vec![3, 4];
+    assert_eq!(gs_find(&text, &vec), Some(4));
+    assert_eq!(gs_find_by::<i32, _>(&text, &vec, |a, b| a == b), Some(4));
+    assert_eq!(gs_find(&text, vec), Some(4));
+}
@@ -0,0 +1,133 @@
+
+
+#[cfg(feature = "alloc")]
+use std::cmp::min;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use analysis::longest_suffix_prefix_by;
+use haystack::Reversed;
+
+/// Return the length of the longest prefix of `x` that is a palindrome.
+///
+/// The prefix `x[..len]` is a palindrome if it is equal to its reverse,
+/// which is the suffix of the reverse of `x` that is `len` long; so this is
+/// a Galil-Seiferas search for the longest such suffix, in **O(m)** time and
+/// **O(1)** space.
+///
+/// ```
+/// use galil_seiferas::longest_palindromic_prefix;
+///
+/// assert_eq!(longest_palindromic_prefix(b"abacabad"), 7);
+/// assert_eq!(longest_palindromic_prefix(b"abc"), 1);
+/// ```
+pub fn longest_palindromic_prefix<T: Eq>(x: &[T]) -> usize {
+    longest_suffix_prefix_by(Reversed::new(x), x, &T::eq)
+}
+
+/// Return the length of the longest suffix of `x` that is a palindrome.
+///
+/// This computes in **O(m)** time and **O(1)** space, like
+/// `longest_palindromic_prefix`.
+///
+/// ```
+/// use galil_seiferas::longest_palindromic_suffix;
+///
+/// assert_eq!(longest_palindromic_suffix(b"banana"), 5);
+/// ```
+pub fn longest_palindromic_suffix<T: Eq>(x: &[T]) -> usize {
+    longest_suffix_prefix_by(x, Reversed::new(x), &T::eq)
+}
+
+/// Return the lengths of the maximal palindromes of `x` at each center, with
+/// Manacher's algorithm.
+///
+/// There are 2m + 1 centers: center `c` is on the element `x[c / 2]` if `c`
+/// is odd, and between the elements `x[c / 2 - 1]` and `x[c / 2]` if `c` is
+/// even. If `r` is the length at `c`, the palindrome is
+/// `x[(c - r) / 2..(c + r) / 2]`.
+///
+/// This computes in **O(m)** time.
+///
+/// ```
+/// use galil_seiferas::palindromic_radii;
+///
+/// let radii = palindromic_radii(b"abba");
+/// assert_eq!(radii, [0, 1, 0, 1, 4, 1, 0, 1, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn palindromic_radii<T: Eq>(x: &[T]) -> Vec<usize> {
+    // The search is in x with a separator between each element and at the
+    // ends, where the palindromes have odd length; there, the length of a
+    // palindrome in x is its radius.
+    let n = 2 * x.len() + 1;
+    let mut radii = ::alloc::vec![0; n];
+    // The palindrome that reaches furthest to the right so far
+    let (mut center, mut right) = (0, 0);
+    for c in 0..n {
+        // The mirror image of c is in the palindrome at center
+        let mut r = if c < right { min(radii[2 * center - c], right - c) } else { 0 };
+        while r < c && c + r + 1 < n {
+            // Separators are at even positions, and element i at 2 i + 1
+            if (c + r) % 2 == 0 && x[(c - r) / 2 - 1] != x[(c + r) / 2] {
+                break;
+            }
+            r += 1;
+        }
+        radii[c] = r;
+        if c + r > right {
+            center = c;
+            right = c + r;
+        }
+    }
+    radii
+}
+
+#[test]
+fn test_palindromes() {
+    fn is_palindrome(x: &[u8]) -> bool {
+        x.iter().eq(x.iter().rev())
+    }
+
+    assert_eq!(longest_palindromic_prefix::<u8>(&[]), 0);
+    assert_eq!(longest_palindromic_suffix::<u8>(&[]), 0);
+    assert_eq!(longest_palindromic_prefix(b"racecar"), 7);
+
+    // every ternary word up to length 9
+    for len in 1..10 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| b'a' + (n / 3u32.pow(i) % 3) as u8).collect::<Vec<_>>();
+            let prefix = (1..x.len() + 1).rev().find(|&l| is_palindrome(&x[..l])).unwrap();
+            let suffix = (1..x.len() + 1).rev().find(|&l| is_palindrome(&x[x.len() - l..]))
+                                             .unwrap();
+            assert_eq!(longest_palindromic_prefix(&x), prefix, "{}", ::Bytestring(&x));
+            assert_eq!(longest_palindromic_suffix(&x), suffix, "{}", ::Bytestring(&x));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_palindromic_radii() {
+    assert_eq!(palindromic_radii::<u8>(&[]), [0]);
+    assert_eq!(palindromic_radii(b"a"), [0, 1, 0]);
+
+    // every ternary word up to length 8
+    for len in 1..9 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| b'a' + (n / 3u32.pow(i) % 3) as u8).collect::<Vec<_>>();
+            let radii = palindromic_radii(&x);
+            assert_eq!(radii.len(), 2 * x.len() + 1);
+            for (c, &r) in radii.iter().enumerate() {
+                // r has the parity of c, and is maximal
+                let grow = |r: usize| {
+                    r <= c && c + r <= 2 * x.len() &&
+                        x[(c - r) / 2..(c + r) / 2].iter().eq(x[(c - r) / 2..(c + r) / 2].iter().rev())
+                };
+                assert_eq!(r % 2, c % 2);
+                assert!(grow(r) && !grow(r + 2), "{} {}", ::Bytestring(&x), c);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::cmp::min;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use finder::Finder;
+use SearchState;
+
+/// The number of text positions in each chunk that a thread searches
+const CHUNK: usize = 1 << 20;
+
+/// The number of text positions that are searched between the checks for a
+/// match in an earlier chunk
+const BLOCK: usize = 1 << 16;
+
+/// Find the first match of `pattern` in `text` like `gs_find`, searching
+/// chunks of the text in parallel, using the rayon thread pool.
+///
+/// The chunks overlap by m − 1 elements, so that the matches that span two
+/// chunks are found. When a match is found, the search of each later chunk
+/// stops at its next check, and the search of the earlier chunks continues
+/// only to prove that the match is the first one; so the time to the first
+/// match is short even when the text is huge.
+///
+/// ```
+/// use galil_seiferas::par_find;
+///
+/// let mut text = vec![0u8; 1 << 24];
+/// text[(1 << 23) + 5..][..3].copy_from_slice(&[1, 2, 3]);
+/// text[(1 << 24) - 3..].copy_from_slice(&[1, 2, 3]);
+/// assert_eq!(par_find(&text, &[1, 2, 3]), Some((1 << 23) + 5));
+/// ```
+pub fn par_find<T: Eq + Sync>(text: &[T], pattern: &[T]) -> Option<usize> {
+    let finder = Finder::new(pattern);
+    let info = finder.info();
+    let m = info.len();
+    if text.len() < m {
+        return None;
+    }
+    // The earliest match that is found so far
+    let first = AtomicUsize::new(usize::MAX);
+    let chunks = (text.len() - m) / CHUNK + 1;
+    (0..chunks).into_par_iter().for_each(|chunk| {
+        let start = chunk * CHUNK;
+        // The matches that start in this chunk
+        let chunk_text = &text[start..min(start + CHUNK + m - 1, text.len())];
+        let pattern = finder.as_pattern(&info);
+        let mut state = SearchState::default();
+        let mut searched = 0;
+        loop {
+            if first.load(Ordering::Relaxed) <= start {
+                return;
+            }
+            // Search the next block; the search of the prefix of the chunk is
+            // resumed when the prefix grows.
+            searched = min(searched.max(m) + BLOCK, chunk_text.len());
+            if let Some(i) = pattern.search_from(&chunk_text[..searched], &mut state) {
+                first.fetch_min(start + i, Ordering::Relaxed);
+                return;
+            }
+            if searched == chunk_text.len() {
+                return;
+            }
+        }
+    });
+    match first.into_inner() {
+        usize::MAX => None,
+        i => Some(i),
+    }
+}
+
+#[test]
+fn test_par_find() {
+    let n = 3 * CHUNK + 17;
+    let mut text = "abaababaab".repeat(n / 10).into_bytes();
+    text.resize(n, b'b');
+    // the last pattern has a nonempty u
+    let patterns = [&b"abaabaab"[..], b"ab", b"", b"abc", b"aaabaaabaaabaabbbb"];
+    for &pos in &[0, CHUNK - 3, CHUNK, 2 * CHUNK + BLOCK - 2, n - 18] {
+        for pattern in &patterns {
+            let mut text = text.clone();
+            text[pos..pos + pattern.len()].copy_from_slice(pattern);
+            assert_eq!(par_find(&text, pattern), ::gs_find(&text, *pattern),
+                       "{} {}", pos, ::Bytestring(pattern));
+        }
+    }
+    assert_eq!(par_find::<u8>(b"", b""), Some(0));
+    assert_eq!(par_find(b"ab", b"abc"), None);
+    assert_eq!(par_find(&text, &text[..]), Some(0));
+}
@@ -0,0 +1,94 @@
+use finder::Finder;
+use {Needle, SearchState};
+
+/// Find the first match of `pattern` in `text` like `gs_find`, and return
+/// the matched part of the text for modifying it in place.
+///
+/// ```
+/// use galil_seiferas::gs_find_mut;
+///
+/// let mut config = *b"debug = off";
+/// gs_find_mut(&mut config, b"off").unwrap().copy_from_slice(b"on!");
+/// assert_eq!(&config, b"debug = on!");
+/// ```
+pub fn gs_find_mut<T: Eq, N: Needle<T>>(text: &mut [T], pattern: N) -> Option<&mut [T]> {
+    let pattern = pattern.as_needle();
+    let start = ::gs_find(text, pattern)?;
+    Some(&mut text[start..start + pattern.len()])
+}
+
+/// Call `f` with the start and the matched part of the text, for each
+/// non-overlapping match of `pattern` in `text`, and return the number of
+/// matches.
+///
+/// The matches are the same as those of `Finder::find_iter`, and the search
+/// continues after each match, so `f` can modify the matched part without
+/// changing which matches follow. This computes in **O(n)** time and **O(1)**
+/// space, not counting `f`.
+///
+/// ```
+/// use galil_seiferas::gs_for_each_match_mut;
+///
+/// let mut log = *b"user=alice pass=hunter2 pass=tiger";
+/// let count = gs_for_each_match_mut(&mut log, b"pass=", |start, _| {
+///     assert!(start == 11 || start == 24);
+/// });
+/// assert_eq!(count, 2);
+///
+/// let mut text = *b"a-b-c";
+/// gs_for_each_match_mut(&mut text, b"-", |_, dash| dash[0] = b'+');
+/// assert_eq!(&text, b"a+b+c");
+/// ```
+pub fn gs_for_each_match_mut<T, N, F>(text: &mut [T], pattern: N, mut f: F) -> usize
+    where T: Eq,
+          N: Needle<T>,
+          F: FnMut(usize, &mut [T]),
+{
+    let finder = Finder::new(pattern.as_needle());
+    let info = finder.info();
+    let pattern = finder.as_pattern(&info);
+    let mut state = SearchState::default();
+    let mut count = 0;
+    while let Some(start) = pattern.find_from(text, &mut state) {
+        let end = start + info.len();
+        // The search continues after the match, so that it does not read
+        // the part that f modifies.
+        if state.pos < end {
+            state.restart(end);
+        }
+        f(start, &mut text[start..end]);
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn test_find_mut() {
+    let mut text = *b"abcabc";
+    assert_eq!(gs_find_mut(&mut text, b"ca").map(|part| &*part), Some(&b"ca"[..]));
+    gs_find_mut(&mut text, b"bc").unwrap()[0] = b'x';
+    assert_eq!(&text, b"axcabc");
+    assert_eq!(gs_find_mut(&mut text, b"abcd"), None);
+    assert_eq!(gs_find_mut(&mut text, b"").map(|part| part.len()), Some(0));
+}
+
+#[test]
+fn test_for_each_match_mut() {
+    let text = b"abaababaabaababaababaabaababaabaab";
+    for pattern in &[&b"aba"[..], b"abaab", b"baabaabaab", b"", b"x"] {
+        let expected = Finder::new(pattern).find_iter(text).map(|m| m.start())
+                                           .collect::<Vec<_>>();
+        let mut patched = text.to_vec();
+        let mut found = Vec::new();
+        let count = gs_for_each_match_mut(&mut patched, *pattern, |start, part| {
+            assert_eq!(part, *pattern);
+            found.push(start);
+            // Replacing a match must not change the next matches
+            for elt in part {
+                *elt = b'.';
+            }
+        });
+        assert_eq!(count, found.len());
+        assert_eq!(found, expected, "{}", ::Bytestring(pattern));
+    }
+}
@@ -0,0 +1,152 @@
+
+use std::mem::{align_of, size_of};
+use std::slice;
+
+use gs_find_bytes;
+use swar::find_fast;
+
+/// An element type that is plain data: a `u8`, `u16` or `u32` (its `Repr`),
+/// or a newtype of one of those.
+///
+/// `gs_find_plain` searches all the plain types with the same `Repr` with
+/// one copy of the search, instead of one instantiation per element type.
+///
+/// ```
+/// use galil_seiferas::{gs_find_plain, Plain};
+///
+/// #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// #[repr(transparent)]
+/// struct Token(u16);
+///
+/// unsafe impl Plain for Token {
+///     type Repr = u16;
+/// }
+///
+/// let text = [Token(1), Token(7), Token(1), Token(2)];
+/// assert_eq!(gs_find_plain(&text, &[Token(1), Token(2)]), Some(2));
+/// ```
+///
+/// # Safety
+///
+/// `Self` must have the same size and alignment as `Self::Repr`, each of
+/// its values must be a valid `Self::Repr` (for example with
+/// `#[repr(transparent)]`), and two values must be equal if and only if
+/// their `Self::Repr` values are equal, like with `#[derive(PartialEq)]`.
+pub unsafe trait Plain: Eq {
+    /// The primitive type with the same representation
+    type Repr: Primitive;
+}
+
+/// A primitive type that plain element types are searched as: `u8`, `u16`
+/// or `u32`.
+///
+/// This trait is sealed.
+pub trait Primitive: Copy + Eq + private::Sealed {
+    /// Search with the (single) implementation for this type.
+    #[doc(hidden)]
+    fn find_primitive(text: &[Self], pattern: &[Self]) -> Option<usize>;
+}
+
+mod private {
+    pub trait Sealed { }
+}
+
+impl private::Sealed for u8 { }
+impl private::Sealed for u16 { }
+impl private::Sealed for u32 { }
+
+impl Primitive for u8 {
+    fn find_primitive(text: &[u8], pattern: &[u8]) -> Option<usize> {
+        gs_find_bytes(text, pattern)
+    }
+}
+
+impl Primitive for u16 {
+    fn find_primitive(text: &[u16], pattern: &[u16]) -> Option<usize> {
+        find_u16(text, pattern)
+    }
+}
+
+impl Primitive for u32 {
+    fn find_primitive(text: &[u32], pattern: &[u32]) -> Option<usize> {
+        find_u32(text, pattern)
+    }
+}
+
+#[inline(never)]
+fn find_u16(text: &[u16], pattern: &[u16]) -> Option<usize> {
+    find_fast(text, pattern)
+}
+
+#[inline(never)]
+fn find_u32(text: &[u32], pattern: &[u32]) -> Option<usize> {
+    find_fast(text, pattern)
+}
+
+macro_rules! plain {
+    ($($t:ty => $repr:ty),*) => {
+        $(
+            unsafe impl Plain for $t {
+                type Repr = $repr;
+            }
+        )*
+    }
+}
+
+plain!(u8 => u8, i8 => u8, u16 => u16, i16 => u16, u32 => u32, i32 => u32, char => u32);
+
+/// View a slice of plain elements as a slice of their representation.
+fn as_repr<T: Plain>(elements: &[T]) -> &[T::Repr] {
+    // The sizes are constant, so this is checked at compile time in effect
+    assert!(size_of::<T>() == size_of::<T::Repr>() && align_of::<T>() == align_of::<T::Repr>(),
+            "Plain: the element type must have the layout of its Repr");
+    // Safety: the layouts match, and each T is a valid T::Repr by the
+    // contract of Plain.
+    unsafe { slice::from_raw_parts(elements.as_ptr() as *const T::Repr, elements.len()) }
+}
+
+/// This is the Galil-Seiferas string matching algorithm, for plain element
+/// types.
+///
+/// This is like `gs_find`, but it is a thin wrapper: all the element types
+/// with the same `Plain::Repr` share one copy of the search.
+///
+/// The search is specialized for each width: like `gs_find_bytes` for
+/// bytes, and for 16- and 32-bit elements (for example UTF-16 text or token
+/// ids), partial matches are compared 16 bytes at a time with SSE2 on
+/// x86-64, and a word at a time elsewhere.
+///
+/// If a match exists where `pattern` is a substring of `text`, return the
+/// offset to the start of the match inside `Some(_)`. If not, return `None`.
+#[inline]
+pub fn gs_find_plain<T: Plain>(text: &[T], pattern: &[T]) -> Option<usize> {
+    T::Repr::find_primitive(as_repr(text), as_repr(pattern))
+}
+
+#[test]
+fn test_gs_find_plain() {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[repr(transparent)]
+    struct Token(u32);
+
+    unsafe impl Plain for Token {
+        type Repr = u32;
+    }
+
+    let text = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 9, 1, 4, 1, 5];
+    let tokens = text.iter().map(|&x| Token(x)).collect::<Vec<_>>();
+    for start in 0..text.len() {
+        for end in start..text.len() + 1 {
+            let pattern = &text[start..end];
+            let expected = ::gs_find(&text, pattern);
+            assert_eq!(gs_find_plain(&tokens, &tokens[start..end]), expected);
+            let text16 = text.iter().map(|&x| x as i16).collect::<Vec<_>>();
+            let pattern16 = pattern.iter().map(|&x| x as i16).collect::<Vec<_>>();
+            assert_eq!(gs_find_plain(&text16, &pattern16), expected);
+        }
+    }
+    assert_eq!(gs_find_plain(&[-1i8, -2, -3], &[-2, -3]), Some(1));
+    let chars = "galil-seiferas".chars().collect::<Vec<_>>();
+    assert_eq!(gs_find_plain(&chars, &['s', 'e']), Some(6));
+    assert_eq!(gs_find_plain::<u8>(&[], &[]), Some(0));
+}
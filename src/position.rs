@@ -0,0 +1,150 @@
+
+use gs_find;
+
+/// A line and column position in a string.
+///
+/// Both are counted from 1. Lines are terminated by `"\n"` or `"\r\n"`, like
+/// in `str::lines`, and the column counts `char`s, not bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    /// The line number, from 1
+    pub line: usize,
+    /// The column in `char`s, from 1
+    pub column: usize,
+}
+
+/// Return the line and column of the byte offset `offset` in `text`.
+///
+/// **Panics** if `offset` is not on a `char` boundary in `text`.
+///
+/// ```
+/// use galil_seiferas::{line_column, LineColumn};
+///
+/// let text = "fn main() {\r\n    «x»\r\n}";
+/// assert_eq!(line_column(text, 17), LineColumn { line: 2, column: 5 });
+/// assert_eq!(line_column(text, 19), LineColumn { line: 2, column: 6 });
+/// ```
+pub fn line_column(text: &str, offset: usize) -> LineColumn {
+    LineColumns::new(text, Some(offset)).next().unwrap()
+}
+
+/// Find the first match of `pattern` in `text` and return its byte offset
+/// and its line and column.
+///
+/// ```
+/// use galil_seiferas::{find_with_position, LineColumn};
+///
+/// let text = "first\nsecond line\n";
+/// assert_eq!(find_with_position(text, "line"),
+///            Some((13, LineColumn { line: 2, column: 8 })));
+/// ```
+pub fn find_with_position(text: &str, pattern: &str) -> Option<(usize, LineColumn)> {
+    let offset = gs_find(text.as_bytes(), pattern.as_bytes())?;
+    Some((offset, line_column(text, offset)))
+}
+
+/// Return an iterator that converts each byte offset in `offsets` to a line
+/// and column in `text`.
+///
+/// When the offsets are in nondecreasing order, like the starts of matches,
+/// all of them are converted in a single pass over the text.
+///
+/// **Panics** if an offset is not on a `char` boundary in `text`.
+///
+/// ```
+/// use galil_seiferas::{line_columns, Finder};
+///
+/// let text = "ab\nab ab\n";
+/// let finder = Finder::new(b"ab");
+/// let starts = finder.find_iter(text.as_bytes()).map(|m| m.start());
+/// let positions = line_columns(text, starts)
+///     .map(|pos| (pos.line, pos.column))
+///     .collect::<Vec<_>>();
+/// assert_eq!(positions, [(1, 1), (2, 1), (2, 4)]);
+/// ```
+pub fn line_columns<I>(text: &str, offsets: I) -> LineColumns<'_, I::IntoIter>
+    where I: IntoIterator<Item = usize>,
+{
+    LineColumns::new(text, offsets)
+}
+
+/// An iterator of line and column positions, created by `line_columns`.
+#[derive(Clone, Debug)]
+pub struct LineColumns<'a, I> {
+    text: &'a str,
+    offsets: I,
+    /// The byte offset that `line` and `column` are computed for
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a, I: Iterator<Item = usize>> LineColumns<'a, I> {
+    fn new<J>(text: &'a str, offsets: J) -> Self
+        where J: IntoIterator<IntoIter = I>,
+    {
+        LineColumns {
+            text,
+            offsets: offsets.into_iter(),
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = usize>> Iterator for LineColumns<'a, I> {
+    type Item = LineColumn;
+
+    fn next(&mut self) -> Option<LineColumn> {
+        let offset = self.offsets.next()?;
+        assert!(self.text.is_char_boundary(offset),
+                "line_columns: offset {} is not a char boundary", offset);
+        if offset < self.pos {
+            self.pos = 0;
+            self.line = 1;
+            self.column = 1;
+        }
+        for &byte in &self.text.as_bytes()[self.pos..offset] {
+            if byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                // Count the first byte of each UTF-8 sequence
+                self.column += 1;
+            }
+        }
+        self.pos = offset;
+        Some(LineColumn { line: self.line, column: self.column })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+#[test]
+fn test_line_column() {
+    let text = "å\r\nb\n\nλx\r\n";
+    let pos = |line, column| LineColumn { line, column };
+    assert_eq!(line_column(text, 0), pos(1, 1));
+    assert_eq!(line_column(text, 2), pos(1, 2));
+    assert_eq!(line_column(text, 3), pos(1, 3));
+    assert_eq!(line_column(text, 4), pos(2, 1));
+    assert_eq!(line_column(text, 6), pos(3, 1));
+    assert_eq!(line_column(text, 9), pos(4, 2));
+    assert_eq!(line_column(text, text.len()), pos(5, 1));
+
+    let offsets = vec![0, 9, 9, 4, text.len()];
+    let positions = line_columns(text, offsets).collect::<Vec<_>>();
+    assert_eq!(positions, vec![pos(1, 1), pos(4, 2), pos(4, 2), pos(2, 1), pos(5, 1)]);
+
+    assert_eq!(find_with_position(text, "x"), Some((9, pos(4, 2))));
+    assert_eq!(find_with_position(text, "y"), None);
+}
+
+#[test]
+#[should_panic]
+fn test_line_column_not_char_boundary() {
+    line_column("å", 1);
+}
@@ -0,0 +1,282 @@
+
+use std::cmp::{max, min};
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use analysis::reach_array;
+use kmp::borders;
+use {longest_common_prefix_from, Pattern, SearchState};
+
+/// An iterator of the occurrences of prefixes of a pattern, created by
+/// `gs_find_prefixes`.
+///
+/// The iterator element is `(position, length)`, the position in the text
+/// and the length of the longest prefix of the pattern that occurs there.
+pub struct PrefixMatches<'p, 't, T: 'p + 't> {
+    min_prefix: Pattern<'p, T, fn(&T, &T) -> bool>,
+    pattern: &'p [T],
+    text: &'t [T],
+    state: SearchState,
+    /// The reaches of the pattern, see `reach_array`
+    reach: Vec<usize>,
+    /// `text[l..r]` is a prefix of the pattern, with the greatest r so far
+    l: usize,
+    r: usize,
+}
+
+/// Find every position in `text` where a prefix of `pattern` of at least
+/// `min_len` elements occurs; yield the position and the length of the
+/// longest prefix that occurs there.
+///
+/// A prefix that runs into the end of the text is reported too, so that a
+/// pattern that is only partially present at the end of the text is found.
+/// `min_len` is clamped to `1 ..= pattern.len()`.
+///
+/// The text is scanned once for the shortest prefix, and each occurrence is
+/// extended to the longest prefix with the Z algorithm, like
+/// `matching_statistics`, so that no element of the text is compared twice
+/// past the furthest match; this computes in **O(n + m)** time and **O(m)**
+/// space.
+///
+/// ```
+/// use galil_seiferas::gs_find_prefixes;
+///
+/// let text = b"..syn..sync..sy";
+/// let found = gs_find_prefixes(text, b"sync", 2).collect::<Vec<_>>();
+/// assert_eq!(found, [(2, 3), (7, 4), (13, 2)]);
+/// ```
+pub fn gs_find_prefixes<'p, 't, T: Eq>(text: &'t [T], pattern: &'p [T], min_len: usize)
+    -> PrefixMatches<'p, 't, T>
+{
+    let min_len = min(max(min_len, 1), pattern.len());
+    let min_prefix = Pattern::preprocess_using(&pattern[..min_len],
+                                               T::eq as fn(&T, &T) -> bool);
+    let mut reach = vec![0; pattern.len()];
+    reach_array(pattern, &mut reach);
+    PrefixMatches {
+        min_prefix,
+        pattern,
+        text,
+        state: SearchState::default(),
+        reach,
+        l: 0,
+        r: 0,
+    }
+}
+
+impl<'p, 't, T: Eq> Iterator for PrefixMatches<'p, 't, T> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+        let pos = self.min_prefix.find_from(self.text, &mut self.state)?;
+        let len = min(self.pattern.len(), self.text.len() - pos);
+        let mut from = self.min_prefix.len();
+        if pos < self.r {
+            // text[pos..r] is pattern[pos - l..r - l], which matches the
+            // pattern for reach[pos - l] - (pos - l) elements
+            let known = self.reach[pos - self.l] - (pos - self.l);
+            if known < self.r - pos {
+                return Some((pos, known));
+            }
+            from = max(from, self.r - pos);
+        }
+        let matched = longest_common_prefix_from(from, &self.text[pos..],
+                                                 &self.pattern[..len], &T::eq);
+        if pos + matched > self.r {
+            self.l = pos;
+            self.r = pos + matched;
+        }
+        Some((pos, matched))
+    }
+}
+
+/// Return the first occurrence of the longest prefix of `pattern` that occurs
+/// in `text`, as its position and length; `None` if no nonempty prefix
+/// occurs.
+///
+/// When the whole pattern occurs, this is its first match. The text is
+/// scanned once, tracking the longest prefix of the pattern that ends at
+/// each position, with a Knuth-Morris-Pratt failure table; this computes
+/// in **O(n + m)** time and **O(m)** space.
+///
+/// ```
+/// use galil_seiferas::longest_prefix_match;
+///
+/// let text = b"the ring, the rin, the rings";
+/// assert_eq!(longest_prefix_match(text, b"the rings"), Some((19, 9)));
+/// assert_eq!(longest_prefix_match(text, b"the rink"), Some((0, 7)));
+/// assert_eq!(longest_prefix_match(text, b"lord"), None);
+/// ```
+pub fn longest_prefix_match<T: Eq>(text: &[T], pattern: &[T]) -> Option<(usize, usize)> {
+    let m = pattern.len();
+    let mut table = vec![0; m];
+    borders(pattern, &mut table);
+    let mut best = None;
+    let mut best_len = 0;
+    // The length of the longest prefix of the pattern that ends at i
+    let mut j = 0;
+    for (i, elt) in text.iter().enumerate() {
+        if j == m {
+            break;
+        }
+        while j > 0 && pattern[j] != *elt {
+            j = table[j - 1];
+        }
+        if pattern[j] == *elt {
+            j += 1;
+            if j > best_len {
+                best_len = j;
+                best = Some((i + 1 - j, j));
+            }
+        }
+    }
+    best
+}
+
+/// Fill `out[i]` with the length of the longest prefix of `pattern` that
+/// occurs at `text[i..]`, for each position of the text: the matching
+/// statistics of the text.
+///
+/// The lengths are computed with the Z algorithm, from the reaches of the
+/// pattern (see `reach_array`); this computes in **O(n + m)** time and
+/// **O(m)** space, in the first n elements of `out`; the rest of `out` is not
+/// modified.
+///
+/// **Panics** if `out` is shorter than `text`.
+///
+/// ```
+/// use galil_seiferas::matching_statistics;
+///
+/// let mut lengths = [0; 8];
+/// matching_statistics(b"abcabdab", b"abcd", &mut lengths);
+/// assert_eq!(lengths, [3, 0, 0, 2, 0, 0, 2, 0]);
+/// ```
+pub fn matching_statistics<T: Eq>(text: &[T], pattern: &[T], out: &mut [usize]) {
+    let n = text.len();
+    let m = pattern.len();
+    assert!(out.len() >= n, "matching_statistics: out must be at least as long as text");
+    let mut reach = vec![0; m];
+    reach_array(pattern, &mut reach);
+    // text[l..r] is a prefix of the pattern, with the greatest r so far
+    let (mut l, mut r) = (0, 0);
+    for i in 0..n {
+        let mut len = 0;
+        if i < r {
+            // text[i..r] is pattern[i - l..r - l], which matches the pattern
+            // for reach[i - l] - (i - l) elements
+            let known = reach[i - l] - (i - l);
+            if known < r - i {
+                out[i] = known;
+                continue;
+            }
+            len = r - i;
+        }
+        while len < m && i + len < n && text[i + len] == pattern[len] {
+            len += 1;
+        }
+        out[i] = len;
+        if i + len > r {
+            l = i;
+            r = i + len;
+        }
+    }
+}
+
+#[test]
+fn test_matching_statistics() {
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbb";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 20) {
+            let pattern = &text[start..end];
+            let mut lengths = vec![!0; text.len() + 1];
+            matching_statistics(text, pattern, &mut lengths);
+            for (i, &len) in lengths[..text.len()].iter().enumerate() {
+                let expected = text[i..].iter().zip(pattern).take_while(|&(a, b)| a == b).count();
+                assert_eq!(len, expected, "{} {}", ::Bytestring(pattern), i);
+            }
+            assert_eq!(lengths[text.len()], !0);
+        }
+    }
+}
+
+#[test]
+fn test_longest_prefix_match() {
+    fn brute_force(text: &[u8], pattern: &[u8]) -> Option<(usize, usize)> {
+        (1..pattern.len() + 1).rev().filter_map(|len| {
+            text.windows(len).position(|w| w == &pattern[..len]).map(|i| (i, len))
+        }).next()
+    }
+
+    let text = b"abaababaabaababaababaabaababaabaab";
+    for start in 0..text.len() {
+        for end in start..text.len() {
+            let mut pattern = text[start..end].to_vec();
+            assert_eq!(longest_prefix_match(text, &pattern), brute_force(text, &pattern));
+            pattern.push(b'c');
+            assert_eq!(longest_prefix_match(text, &pattern), brute_force(text, &pattern));
+        }
+    }
+    assert_eq!(longest_prefix_match(b"", b"a"), None);
+    assert_eq!(longest_prefix_match(b"abc", b""), None);
+}
+
+#[test]
+fn test_find_prefixes() {
+    let text = b"abcab-abcabcab-abca";
+    let found = gs_find_prefixes(text, b"abcabcab", 3).collect::<Vec<_>>();
+    assert_eq!(found, vec![(0, 5), (6, 8), (9, 5), (15, 4)]);
+
+    let found = gs_find_prefixes(b"aaaa", b"aa", 0).collect::<Vec<_>>();
+    assert_eq!(found, vec![(0, 2), (1, 2), (2, 2), (3, 1)]);
+
+    assert_eq!(gs_find_prefixes(b"abc", b"", 1).count(), 0);
+    assert_eq!(gs_find_prefixes(b"abc", b"abx", 5).count(), 0);
+}
+
+#[test]
+fn test_find_prefixes_brute_force() {
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbb";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 20) {
+            let pattern = &text[start..end];
+            for min_len in 1..4 {
+                let expected = (0..text.len()).filter_map(|i| {
+                    let len = text[i..].iter().zip(pattern).take_while(|&(a, b)| a == b).count();
+                    if len >= min_len.min(pattern.len()) && len > 0 { Some((i, len)) } else { None }
+                }).collect::<Vec<_>>();
+                let found = gs_find_prefixes(text, pattern, min_len).collect::<Vec<_>>();
+                assert_eq!(found, expected, "{} {}", ::Bytestring(pattern), min_len);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_find_prefixes_linear() {
+    use std::cell::Cell;
+
+    // An element that counts its comparisons
+    struct Counted<'a>(u8, &'a Cell<usize>);
+
+    impl<'a> PartialEq for Counted<'a> {
+        fn eq(&self, other: &Self) -> bool {
+            self.1.set(self.1.get() + 1);
+            self.0 == other.0
+        }
+    }
+    impl<'a> Eq for Counted<'a> { }
+
+    // Each position matches a long prefix, which the search without the
+    // reaches compares again at each position
+    let count = Cell::new(0);
+    let n = 2000;
+    let text = (0..n).map(|_| Counted(b'a', &count)).collect::<Vec<_>>();
+    let pattern = (0..n / 2).map(|_| Counted(b'a', &count)).collect::<Vec<_>>();
+    assert_eq!(gs_find_prefixes(&text, &pattern, 1).count(), n);
+    // The search for the prefix, the reaches and the extensions are each linear
+    assert!(count.get() <= 10 * n, "{} comparisons", count.get());
+}
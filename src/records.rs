@@ -0,0 +1,149 @@
+
+use std::cmp::max;
+use std::io::{self, Read};
+
+use alloc::vec::Vec;
+
+use finder::Finder;
+
+/// The number of bytes to read at a time
+const READ_SIZE: usize = 8 * 1024;
+
+/// An iterator of the records in a stream, where each record is terminated
+/// by a (multi-byte) delimiter.
+///
+/// The records are yielded without the delimiter. The last record does not
+/// need to be terminated, but an empty last record is not yielded. Delimiters
+/// may span separate reads.
+///
+/// The input is searched in a single pass, except that the last m − 1 bytes
+/// are searched again after each read. The buffer grows to hold the longest
+/// record.
+///
+/// ```
+/// use galil_seiferas::RecordReader;
+///
+/// let input = &b"first\r\n\r\nsecond\r\n\r\nthird"[..];
+/// let records = RecordReader::new(input, b"\r\n\r\n")
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(records, [&b"first"[..], b"second", b"third"]);
+/// ```
+pub struct RecordReader<R> {
+    inner: R,
+    finder: Finder<'static, u8>,
+    buffer: Vec<u8>,
+    /// The start of the current record in the buffer
+    start: usize,
+    /// The end of the part of the buffer that is searched without finding
+    /// a delimiter
+    searched: usize,
+    eof: bool,
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Create a record reader that reads from `inner` and splits on
+    /// `delimiter`.
+    ///
+    /// **Panics** if `delimiter` is empty.
+    pub fn new(inner: R, delimiter: &[u8]) -> Self {
+        assert!(!delimiter.is_empty(), "RecordReader: the delimiter must not be empty");
+        RecordReader {
+            inner,
+            finder: Finder::from_vec(delimiter.to_vec()),
+            buffer: Vec::new(),
+            start: 0,
+            searched: 0,
+            eof: false,
+        }
+    }
+
+    /// Return the inner reader; input that is read but not yet returned as a
+    /// record is discarded.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read more input into the buffer; return the number of bytes read.
+    fn fill_buffer(&mut self) -> io::Result<usize> {
+        self.buffer.drain(..self.start);
+        self.searched -= self.start;
+        self.start = 0;
+        let len = self.buffer.len();
+        self.buffer.resize(len + READ_SIZE, 0);
+        let result = loop {
+            match self.inner.read(&mut self.buffer[len..]) {
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+        self.buffer.truncate(len + *result.as_ref().unwrap_or(&0));
+        result
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = self.finder.pattern().len();
+        loop {
+            // A delimiter can start in the last m - 1 bytes that were
+            // searched, and end in the input that was read after them.
+            let from = max(self.start, self.searched.saturating_sub(m - 1));
+            if let Some(found) = self.finder.find(&self.buffer[from..]) {
+                let record = self.buffer[self.start..from + found.start()].to_vec();
+                self.start = from + found.end();
+                self.searched = self.start;
+                return Some(Ok(record));
+            }
+            self.searched = self.buffer.len();
+            if self.eof {
+                if self.start == self.buffer.len() {
+                    return None;
+                }
+                let record = self.buffer[self.start..].to_vec();
+                self.start = self.buffer.len();
+                return Some(Ok(record));
+            }
+            match self.fill_buffer() {
+                Ok(0) => self.eof = true,
+                Ok(_) => { }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_record_reader() {
+    /// A reader that returns at most one byte at a time
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(1);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    let input = b"a--b---c----d--";
+    let expected = vec![&b"a"[..], b"b", b"-c", b"", b"d"];
+    let records = RecordReader::new(&input[..], b"--").map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(records, expected);
+    let records = RecordReader::new(Trickle(input), b"--").map(Result::unwrap)
+                                                          .collect::<Vec<_>>();
+    assert_eq!(records, expected);
+
+    let long = "ab".repeat(READ_SIZE) + "aba<>a<ab<><>";
+    let records = RecordReader::new(long.as_bytes(), b"<>").map(Result::unwrap)
+                                                           .collect::<Vec<_>>();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].len(), 2 * READ_SIZE + 3);
+    assert_eq!(records[1], b"a<ab");
+    assert_eq!(records[2], b"");
+
+    assert_eq!(RecordReader::new(&b""[..], b"x").count(), 0);
+}
@@ -0,0 +1,248 @@
+
+use std::cmp::min;
+use std::io::{self, Write};
+
+use alloc::vec::Vec;
+
+use finder::Finder;
+
+/// A writer that replaces each occurrence of a pattern in the bytes written
+/// through it, and writes the result to the inner writer.
+///
+/// Occurrences are replaced from left to right without overlap, like
+/// `str::replace`, even when they span separate writes. At most **2 m**
+/// bytes are buffered (and a replacement, if the inner writer fails while it
+/// is written), and the search uses **O(n)** time in total, plus **O(m)**
+/// for each `flush`.
+///
+/// If the inner writer fails, the bytes that it did not accept stay
+/// buffered and are written again on the next write or flush; no byte is
+/// written twice.
+///
+/// Bytes that could be the start of an occurrence are held back until the
+/// next write, so call `finish` when done; dropping the writer also writes
+/// the remaining bytes, but ignores any errors.
+///
+/// ```
+/// use std::io::Write;
+/// use galil_seiferas::ReplacingWriter;
+///
+/// let mut writer = ReplacingWriter::new(Vec::new(), b"secret", b"******");
+/// writer.write_all(b"user=joe password=sec").unwrap();
+/// writer.write_all(b"ret\n").unwrap();
+/// let output = writer.finish().unwrap();
+/// assert_eq!(output, b"user=joe password=******\n");
+/// ```
+pub struct ReplacingWriter<W: Write> {
+    inner: Option<W>,
+    finder: Finder<'static, u8>,
+    replacement: Vec<u8>,
+    /// The bytes that are not yet searched or could be part of a match,
+    /// after the replaced bytes that are not yet written
+    buffer: Vec<u8>,
+    /// The length of the replaced bytes at the start of the buffer
+    replaced: usize,
+}
+
+impl<W: Write> ReplacingWriter<W> {
+    /// Create a writer that replaces `pattern` with `replacement` and writes
+    /// to `inner`.
+    ///
+    /// **Panics** if `pattern` is empty.
+    pub fn new(inner: W, pattern: &[u8], replacement: &[u8]) -> Self {
+        assert!(!pattern.is_empty(), "ReplacingWriter: the pattern must not be empty");
+        ReplacingWriter {
+            inner: Some(inner),
+            finder: Finder::from_vec(pattern.to_vec()),
+            replacement: replacement.to_vec(),
+            buffer: Vec::with_capacity(2 * pattern.len()),
+            replaced: 0,
+        }
+    }
+
+    /// The inner writer
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Write the remaining buffered bytes, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_buffer(true)?;
+        let mut inner = self.inner.take().unwrap();
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    /// Replace the matches in the buffer and write it to the inner writer,
+    /// except for a tail that could be the start of a match. If `is_end` is
+    /// true, there is no more input and everything is written.
+    fn write_buffer(&mut self, is_end: bool) -> io::Result<()> {
+        let m = self.finder.pattern().len();
+        loop {
+            let replaced = self.replaced;
+            self.write_prefix(replaced)?;
+            let start = match self.finder.find(&self.buffer) {
+                Some(found) => found.start(),
+                None => break,
+            };
+            self.write_prefix(start)?;
+            self.buffer.splice(..m, self.replacement.iter().cloned());
+            self.replaced = self.replacement.len();
+        }
+        // A match that starts before the last m - 1 bytes would be complete
+        // in the buffer, so it would have been found.
+        let keep = if is_end { 0 } else { m - 1 };
+        let tail = self.buffer.len().saturating_sub(keep);
+        self.write_prefix(tail)
+    }
+
+    /// Write the first `len` bytes of the buffer to the inner writer, and
+    /// remove the bytes from the buffer as soon as they are written.
+    fn write_prefix(&mut self, mut len: usize) -> io::Result<()> {
+        let inner = self.inner.as_mut().unwrap();
+        while len > 0 {
+            match inner.write(&self.buffer[..len]) {
+                Ok(0) => {
+                    return Err(io::Error::new(io::ErrorKind::WriteZero,
+                                              "failed to write the buffered data"));
+                }
+                Ok(n) => {
+                    self.buffer.drain(..n);
+                    self.replaced -= min(self.replaced, n);
+                    len -= n;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => { }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for ReplacingWriter<W> {
+    /// Buffer the bytes of `buf`, and write out the full buffer.
+    ///
+    /// If the inner writer fails, return the number of bytes that are taken
+    /// before the error, and the error only if no byte is taken.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let capacity = 2 * self.finder.pattern().len();
+        let mut consumed = 0;
+        while consumed < buf.len() {
+            // The buffer keeps at most m - 1 bytes after it is written out,
+            // so at least m + 1 new bytes are searched each time.
+            if self.buffer.len() >= capacity {
+                if let Err(error) = self.write_buffer(false) {
+                    return if consumed == 0 { Err(error) } else { Ok(consumed) };
+                }
+            }
+            let n = (capacity - self.buffer.len()).min(buf.len() - consumed);
+            self.buffer.extend_from_slice(&buf[consumed..consumed + n]);
+            consumed += n;
+        }
+        Ok(consumed)
+    }
+
+    /// Flush the inner writer. Bytes that could be the start of a match are
+    /// still held back.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer(false)?;
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write> Drop for ReplacingWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.write_buffer(true);
+        }
+    }
+}
+
+#[test]
+fn test_replacing_writer() {
+    let replace = |chunks: &[&[u8]], pattern: &[u8], replacement: &[u8]| {
+        let mut writer = ReplacingWriter::new(Vec::new(), pattern, replacement);
+        for chunk in chunks {
+            writer.write_all(chunk).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    };
+    assert_eq!(replace(&[b"abcabc"], b"bc", b"X"), "aXaX");
+    assert_eq!(replace(&[b"ab", b"", b"ca", b"bc"], b"bc", b"X"), "aXaX");
+    assert_eq!(replace(&[b"aaaaa"], b"aa", b"b"), "bba");
+    assert_eq!(replace(&[b"a", b"a", b"a", b"a", b"a"], b"aa", b"b"), "bba");
+    assert_eq!(replace(&[b"xyz"], b"xyzw", b"-"), "xyz");
+    assert_eq!(replace(&[b"x"], b"x", b""), "");
+
+    // Compare with str::replace for every way to split the input in two
+    let text = "abaababaabaababaababaabaababaab";
+    let expected = text.replace("abaab", "<>");
+    for i in 0..text.len() + 1 {
+        let (a, b) = text.split_at(i);
+        assert_eq!(replace(&[a.as_bytes(), b.as_bytes()], b"abaab", b"<>"), expected);
+    }
+
+    let mut output = Vec::new();
+    {
+        let mut writer = ReplacingWriter::new(&mut output, b"ab", b"AB");
+        writer.write_all(b"xaba").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref().len(), 3);
+    }
+    assert_eq!(output, b"xABa");
+}
+
+#[test]
+fn test_replacing_writer_errors() {
+    use std::cell::Cell;
+
+    // A writer that takes at most two bytes at a time, and fails every
+    // third call while `failing` is set
+    struct Flaky<'a> {
+        output: Vec<u8>,
+        calls: usize,
+        failing: &'a Cell<bool>,
+    }
+
+    impl<'a> Write for Flaky<'a> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.failing.get() && self.calls == 3 {
+                self.calls = 0;
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "flaky"));
+            }
+            let n = buf.len().min(2);
+            self.output.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let text = "abaababaabaababaababaabaababaab";
+    for &(pattern, replacement) in &[("abaab", "<>"), ("ab", "<long>"), ("a", "")] {
+        let expected = text.replace(pattern, replacement);
+        for chunk_len in 1..8 {
+            let failing = Cell::new(true);
+            let inner = Flaky { output: Vec::new(), calls: 0, failing: &failing };
+            let mut writer = ReplacingWriter::new(inner, pattern.as_bytes(),
+                                                  replacement.as_bytes());
+            for chunk in text.as_bytes().chunks(chunk_len) {
+                let mut rest = chunk;
+                while !rest.is_empty() {
+                    if let Ok(n) = writer.write(rest) {
+                        assert!(n > 0);
+                        rest = &rest[n..];
+                    }
+                }
+            }
+            while writer.flush().is_err() { }
+            failing.set(false);
+            let output = writer.finish().unwrap().output;
+            assert_eq!(String::from_utf8(output).unwrap(), expected,
+                       "{:?} {}", pattern, chunk_len);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+
+use std::cmp::{max, min};
+
+use haystack::{skip, sub, Haystack};
+use {decomposition_by, search_from_by, SearchState};
+
+/// The strand of a match found by `find_with_revcomp`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Strand {
+    /// The pattern itself was found
+    Forward,
+    /// The reverse complement of the pattern was found
+    Reverse,
+}
+
+/// The number of text positions that both searches scan in turn
+const BLOCK: usize = 4096;
+
+/// A view of the reverse complement of a pattern, which computes each
+/// element when it is accessed.
+struct ReverseComplement<'a, T: 'a, F: 'a> {
+    pattern: &'a [T],
+    complement: &'a F,
+}
+
+impl<'a, T, F> Clone for ReverseComplement<'a, T, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, F> Copy for ReverseComplement<'a, T, F> { }
+
+impl<'a, T, F: Fn(&T) -> T> Haystack for ReverseComplement<'a, T, F> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        self.pattern.len()
+    }
+
+    fn get(&self, index: usize) -> T {
+        (self.complement)(&self.pattern[self.pattern.len() - 1 - index])
+    }
+}
+
+/// Find the first occurrence of either `pattern` or its reverse complement
+/// in `text`, and return its offset and strand.
+///
+/// The reverse complement is the pattern reversed, with `complement` applied
+/// to each element. If both strands match at the same offset (the pattern is
+/// its own reverse complement), `Strand::Forward` is reported.
+///
+/// Both strands are searched in one scan of the text, a block at a time,
+/// where the two searches take turns at each block and each resumes where it
+/// left off; the scan stops at the first block with a match. This computes
+/// in **O(n)** time and **O(1)** space; the reverse complement is a view of
+/// the pattern, so `complement` is called for each comparison with it.
+///
+/// ```
+/// use galil_seiferas::{find_with_revcomp, Strand};
+///
+/// fn complement(base: &u8) -> u8 {
+///     match *base {
+///         b'A' => b'T',
+///         b'T' => b'A',
+///         b'C' => b'G',
+///         b'G' => b'C',
+///         other => other,
+///     }
+/// }
+///
+/// let text = b"GGTTACCAGTAACG";
+/// assert_eq!(find_with_revcomp(text, b"GTAAC", complement), Some((1, Strand::Reverse)));
+/// assert_eq!(find_with_revcomp(text, b"AGTA", complement), Some((7, Strand::Forward)));
+/// ```
+pub fn find_with_revcomp<T, F>(text: &[T], pattern: &[T], complement: F)
+    -> Option<(usize, Strand)>
+    where T: Eq,
+          F: Fn(&T) -> T,
+{
+    let m = pattern.len();
+    let revcomp = ReverseComplement { pattern, complement: &complement };
+    let (u_len, hrp1) = decomposition_by(pattern, &T::eq);
+    let (rev_u_len, rev_hrp1) = decomposition_by(revcomp, &T::eq);
+    let mut forward_state = SearchState::default();
+    let mut reverse_state = SearchState::default();
+    let mut end = 0;
+    loop {
+        // Find the matches that end in the next block
+        end = min(max(end, m) + BLOCK, text.len());
+        let forward = search_from_by(&text[..end], sub(pattern, 0..u_len), skip(pattern, u_len),
+                                     hrp1, &mut forward_state, &T::eq);
+        // A reverse match must start before the forward match to be
+        // reported, so only search the text up to the end of the forward
+        // match.
+        let reverse_end = forward.map_or(end, |i| i + m);
+        let reverse = search_from_by(&text[..reverse_end],
+                                     sub(revcomp, 0..rev_u_len), skip(revcomp, rev_u_len),
+                                     rev_hrp1, &mut reverse_state, &T::eq);
+        match (forward, reverse) {
+            (Some(i), Some(j)) if j < i => return Some((j, Strand::Reverse)),
+            (Some(i), _) => return Some((i, Strand::Forward)),
+            (None, Some(j)) => return Some((j, Strand::Reverse)),
+            (None, None) if end == text.len() => return None,
+            (None, None) => { }
+        }
+    }
+}
+
+#[test]
+fn test_find_with_revcomp() {
+    fn complement(base: &u8) -> u8 {
+        match *base {
+            b'A' => b'T',
+            b'T' => b'A',
+            b'C' => b'G',
+            b'G' => b'C',
+            other => other,
+        }
+    }
+    let find = |text: &[u8], pattern: &[u8]| find_with_revcomp(text, pattern, complement);
+    assert_eq!(find(b"AAGCAATTGC", b"TTGC"), Some((2, Strand::Reverse)));
+    assert_eq!(find(b"AATTGCAGCAA", b"TTGC"), Some((2, Strand::Forward)));
+    assert_eq!(find(b"AAACCC", b"GGG"), Some((3, Strand::Reverse)));
+    assert_eq!(find(b"GGGAAACCC", b"GGG"), Some((0, Strand::Forward)));
+    assert_eq!(find(b"AAAA", b"GG"), None);
+    assert_eq!(find(b"AAAA", b""), Some((0, Strand::Forward)));
+    // ACGT is its own reverse complement
+    assert_eq!(find(b"TTACGT", b"ACGT"), Some((2, Strand::Forward)));
+    // Overlapping matches of both strands
+    assert_eq!(find(b"CCCAAAGGTT", b"CCTTT"), Some((3, Strand::Reverse)));
+    assert_eq!(find(b"CCTTTAAGG", b"CCTTT"), Some((0, Strand::Forward)));
+    assert_eq!(find(b"CAAAGGTTT", b"CCTTT"), Some((1, Strand::Reverse)));
+    // Matches in later blocks
+    let mut text = b"ACGT".repeat(3000);
+    text.extend_from_slice(b"AAAGGG");
+    assert_eq!(find(&text, b"AAAGG"), Some((12000, Strand::Forward)));
+    assert_eq!(find(&text, b"CCTTT"), Some((12000, Strand::Reverse)));
+    assert_eq!(find(&text, b"CCCTTT"), Some((12000, Strand::Reverse)));
+    assert_eq!(find(&text, b"TTTT"), None);
+}
@@ -0,0 +1,181 @@
+
+use std::cmp::{max, min};
+
+use haystack::{skip, sub, Reversed};
+use {decomposition_by, find_from_by, SearchState};
+
+/// The orientation of a match found by `find_pattern_or_reverse`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// The pattern itself was found
+    Forward,
+    /// The reversal of the pattern was found
+    Reversed,
+}
+
+/// The number of text positions that both searches scan in turn
+const BLOCK: usize = 4096;
+
+/// Find the first occurrence of either `pattern` or its reversal in `text`,
+/// and return its offset and orientation.
+///
+/// If both orientations match at the same offset (the pattern is a
+/// palindrome), `Orientation::Forward` is reported.
+///
+/// The text is scanned once, a block at a time, where the searches for the
+/// pattern and for its reversal take turns at each block; the scan stops at
+/// the first block with a match. This computes in **O(n)** time and
+/// **O(1)** space; the reversal is a view of the pattern.
+///
+/// ```
+/// use galil_seiferas::{find_pattern_or_reverse, Orientation};
+///
+/// let signal = [0, 1, 5, 3, 2, 0, 2, 3, 5, 0];
+/// assert_eq!(find_pattern_or_reverse(&signal, &[2, 3, 5]), Some((2, Orientation::Reversed)));
+/// assert_eq!(find_pattern_or_reverse(&signal, &[2, 3, 5, 0]), Some((6, Orientation::Forward)));
+/// ```
+pub fn find_pattern_or_reverse<T: Eq>(text: &[T], pattern: &[T])
+    -> Option<(usize, Orientation)>
+{
+    let m = pattern.len();
+    let (u_len, hrp1) = decomposition_by(pattern, &T::eq);
+    let reversal = Reversed::new(pattern);
+    let (rev_u_len, rev_hrp1) = decomposition_by(reversal, &T::eq);
+    let block = max(m, BLOCK);
+    let mut start = 0;
+    while start + m <= text.len() {
+        // Find the matches that start in this block
+        let end = min(start + block + m - 1, text.len());
+        let forward = find_from_by(&text[..end], sub(pattern, 0..u_len), skip(pattern, u_len),
+                                   hrp1, &mut SearchState::at(start), &T::eq);
+        // A reversed match must start before the forward match to be
+        // reported, so only search the text up to the end of the forward
+        // match.
+        let reverse_end = forward.map_or(end, |i| i + m);
+        let reverse = find_from_by(&text[..reverse_end],
+                                   sub(reversal, 0..rev_u_len), skip(reversal, rev_u_len),
+                                   rev_hrp1, &mut SearchState::at(start), &T::eq);
+        match (forward, reverse) {
+            (Some(i), Some(j)) if j < i => return Some((j, Orientation::Reversed)),
+            (Some(i), _) => return Some((i, Orientation::Forward)),
+            (None, Some(j)) => return Some((j, Orientation::Reversed)),
+            (None, None) => start += block,
+        }
+    }
+    None
+}
+
+/// Return the start of the last match of `pattern` in `text`, if any.
+///
+/// The `Reversed` view of the text is searched for the reversal of the
+/// pattern, so this is the same search as `gs_find`, from the end of the
+/// text, in **O(n)** time and **O(1)** space.
+///
+/// ```
+/// use galil_seiferas::gs_rfind;
+///
+/// assert_eq!(gs_rfind(b"one two one two", b"one"), Some(8));
+/// assert_eq!(gs_rfind(b"one two", b"three"), None);
+/// ```
+pub fn gs_rfind<T: Eq>(text: &[T], pattern: &[T]) -> Option<usize> {
+    let text = Reversed::new(text);
+    let reversal = Reversed::new(pattern);
+    let (u_len, hrp1) = decomposition_by(reversal, &T::eq);
+    let found = find_from_by(text, sub(reversal, 0..u_len), skip(reversal, u_len),
+                             hrp1, &mut SearchState::default(), &T::eq)?;
+    Some(text.original_range(found, pattern.len()).start)
+}
+
+/// Return the start of the last match of `pattern` in `text` that ends at or
+/// before `end`, for example to find the previous match from a cursor.
+///
+/// This is `gs_rfind` in `text[..end]`, so the time of the search is linear
+/// in the distance from `end` back to the match, and not in the length of
+/// the text before it. `end` is clamped to the length of the text.
+///
+/// ```
+/// use galil_seiferas::gs_rfind_before;
+///
+/// let text = b"one two one two";
+/// assert_eq!(gs_rfind_before(text, b"one", text.len()), Some(8));
+/// assert_eq!(gs_rfind_before(text, b"one", 10), Some(0));
+/// assert_eq!(gs_rfind_before(text, b"two", 6), None);
+/// ```
+pub fn gs_rfind_before<T: Eq>(text: &[T], pattern: &[T], end: usize) -> Option<usize> {
+    gs_rfind(&text[..min(end, text.len())], pattern)
+}
+
+#[test]
+fn test_gs_rfind() {
+    let text = b"abaababaabaab";
+    for pattern in &[&b""[..], b"a", b"ab", b"aba", b"abaab", b"baabaab", b"bb", text] {
+        let expected = (0..text.len() + 1).rev().find(|&i| text[i..].starts_with(pattern));
+        assert_eq!(gs_rfind(text, pattern), expected, "{}", ::Bytestring(pattern));
+    }
+    assert_eq!(gs_rfind::<u8>(b"", b""), Some(0));
+    assert_eq!(gs_rfind(b"ab", b"abc"), None);
+}
+
+#[test]
+fn test_gs_rfind_before() {
+    let text = b"abaababaabaab";
+    for end in 0..text.len() + 2 {
+        for pattern in &[&b""[..], b"a", b"ab", b"aba", b"abaab", b"baabaab", b"bb"] {
+            let expected = (0..text.len() + 1).rev()
+                .find(|&i| i + pattern.len() <= end && text[i..].starts_with(pattern));
+            assert_eq!(gs_rfind_before(text, pattern, end), expected,
+                       "{} {}", end, ::Bytestring(pattern));
+        }
+    }
+    assert_eq!(gs_rfind_before::<u8>(b"", b"", 0), Some(0));
+    assert_eq!(gs_rfind_before(b"ab", b"abc", 2), None);
+}
+
+#[test]
+fn test_find_pattern_or_reverse() {
+    fn brute_force(text: &[u8], pattern: &[u8]) -> Option<(usize, Orientation)> {
+        let reversal = pattern.iter().rev().cloned().collect::<Vec<_>>();
+        (0..text.len() + 1).filter_map(|i| {
+            if text[i..].starts_with(pattern) {
+                Some((i, Orientation::Forward))
+            } else if text[i..].starts_with(&reversal) {
+                Some((i, Orientation::Reversed))
+            } else {
+                None
+            }
+        }).next()
+    }
+
+    assert_eq!(find_pattern_or_reverse(b"abc", b""), Some((0, Orientation::Forward)));
+    assert_eq!(find_pattern_or_reverse(b"", b""), Some((0, Orientation::Forward)));
+    assert_eq!(find_pattern_or_reverse(b"ab", b"abc"), None);
+    assert_eq!(find_pattern_or_reverse(b"xxabaxx", b"aba"), Some((2, Orientation::Forward)));
+
+    // Every ternary text of length < 8 and pattern of length < 4
+    for len in 0..8 {
+        for n in 0..3u32.pow(len) {
+            let text = (0..len).map(|i| b'a' + (n / 3u32.pow(i) % 3) as u8).collect::<Vec<_>>();
+            for pattern_len in 0..4 {
+                for p in 0..3u32.pow(pattern_len) {
+                    let pattern = (0..pattern_len).map(|i| b'a' + (p / 3u32.pow(i) % 3) as u8)
+                                                  .collect::<Vec<_>>();
+                    assert_eq!(find_pattern_or_reverse(&text, &pattern),
+                               brute_force(&text, &pattern),
+                               "{} {}", ::Bytestring(&text), ::Bytestring(&pattern));
+                }
+            }
+        }
+    }
+
+    // Matches near and across the block boundaries
+    for &pos in &[BLOCK - 3, BLOCK - 1, BLOCK, 2 * BLOCK - 2, 3 * BLOCK] {
+        for pattern in &[&b"abc"[..], b"aabaab", b"abcdefg"] {
+            let mut text = vec![b'x'; 4 * BLOCK];
+            let reversal = pattern.iter().rev().cloned().collect::<Vec<_>>();
+            text[pos..pos + pattern.len()].copy_from_slice(&reversal);
+            assert_eq!(find_pattern_or_reverse(&text, pattern), brute_force(&text, pattern));
+            text[pos + 1..pos + 1 + pattern.len()].copy_from_slice(pattern);
+            assert_eq!(find_pattern_or_reverse(&text, pattern), brute_force(&text, pattern));
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use haystack::{gs_find_in, Haystack};
+
+/// A view of the contents of a circular buffer, as a haystack.
+///
+/// The contents are the `len` elements of `buffer` that start at `head`
+/// and wrap around its end. Search the view with `gs_find_in` or
+/// `gs_find_ring`; the match positions are logical offsets, from `head`,
+/// and matches that wrap across the end of the buffer are found, without
+/// copying the contents out.
+#[derive(Debug)]
+pub struct Ring<'a, T: 'a> {
+    buffer: &'a [T],
+    head: usize,
+    len: usize,
+}
+
+impl<'a, T> Copy for Ring<'a, T> { }
+impl<'a, T> Clone for Ring<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Ring<'a, T> {
+    /// Create a view of the `len` elements of `buffer` that start at `head`.
+    ///
+    /// **Panics** if `head` is not inside `buffer` (it can be zero for an
+    /// empty buffer) or if `len` is greater than the length of `buffer`.
+    pub fn new(buffer: &'a [T], head: usize, len: usize) -> Self {
+        assert!(head < buffer.len() || head == 0,
+                "Ring: head {} out of bounds for a buffer of length {}", head, buffer.len());
+        assert!(len <= buffer.len(),
+                "Ring: len {} is greater than the buffer length {}", len, buffer.len());
+        Ring { buffer, head, len }
+    }
+
+    /// Return the contents as two slices, in order; the second one is
+    /// nonempty if the contents wrap around the end of the buffer.
+    pub fn as_slices(&self) -> (&'a [T], &'a [T]) {
+        let first_len = self.len.min(self.buffer.len() - self.head);
+        (&self.buffer[self.head..self.head + first_len], &self.buffer[..self.len - first_len])
+    }
+
+    /// Return the index in the buffer of the element at logical offset
+    /// `index`.
+    ///
+    /// **Panics** if `index` is out of bounds.
+    pub fn physical_index(&self, index: usize) -> usize {
+        assert!(index < self.len, "Ring: index {} out of bounds for length {}", index, self.len);
+        let to_end = self.buffer.len() - self.head;
+        if index < to_end { self.head + index } else { index - to_end }
+    }
+}
+
+impl<'a, T> Haystack for Ring<'a, T> {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        &self.buffer[self.physical_index(index)]
+    }
+}
+
+/// Find the first match of `pattern` in the contents of a circular buffer:
+/// the `len` elements of `buffer` that start at `head`, see `Ring`. Return
+/// the logical offset of the match, from `head`.
+///
+/// **Panics** if `head` or `len` is out of bounds, like `Ring::new`.
+///
+/// ```
+/// use galil_seiferas::gs_find_ring;
+///
+/// // The log wrapped around: it starts at index 6
+/// let buffer = b"or\nxxxok\nerr";
+/// assert_eq!(gs_find_ring(buffer, 6, 9, b"error"), Some(3));
+/// assert_eq!(gs_find_ring(buffer, 6, 7, b"error"), None);
+/// ```
+pub fn gs_find_ring<T: Eq>(buffer: &[T], head: usize, len: usize, pattern: &[T])
+    -> Option<usize>
+{
+    gs_find_in(Ring::new(buffer, head, len), pattern)
+}
+
+#[test]
+fn test_ring() {
+    let text = b"abaababaabaababaababaabaab";
+    for head in 0..text.len() {
+        // Rotate the text into a buffer, so that the contents start at head
+        let mut buffer = text.to_vec();
+        buffer.rotate_right(head);
+        for len in 0..text.len() + 1 {
+            let ring = Ring::new(&buffer, head, len);
+            let (first, second) = ring.as_slices();
+            assert_eq!([first, second].concat(), &text[..len]);
+            for pattern in &[&b"aab"[..], b"baababaab", b"", b"abb", &text[..len]] {
+                assert_eq!(gs_find_ring(&buffer, head, len, pattern),
+                           ::gs_find(&text[..len], *pattern));
+            }
+        }
+    }
+    assert_eq!(gs_find_ring::<u8>(b"", 0, 0, b""), Some(0));
+    assert_eq!(Ring::new(b"abc", 2, 3).physical_index(1), 0);
+}
+
+#[test]
+#[should_panic(expected = "greater than the buffer length")]
+fn test_ring_len_oob() {
+    Ring::new(b"abc", 0, 4);
+}
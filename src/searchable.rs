@@ -0,0 +1,159 @@
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use needle::Needle;
+use strs::{CharMatchIndices, StrMatches};
+use gs_find;
+
+/// A text that can be searched.
+///
+/// This is implemented for slices, arrays, and (for bytes) strings, and with
+/// crate feature `alloc`, also for vectors, boxed slices and `String`, so
+/// that generic code can search any of them. The strings also have the
+/// searches that return string slices: `gs_matches` and
+/// `gs_char_match_indices`.
+///
+/// ```
+/// use galil_seiferas::GsSearchable;
+///
+/// fn has_header<H: GsSearchable<u8> + ?Sized>(text: &H) -> bool {
+///     text.gs_contains(b"HTTP/1.1")
+/// }
+///
+/// assert!(has_header("GET / HTTP/1.1"));
+/// assert!(has_header(b"HTTP/1.1 200 OK"));
+/// assert!(!has_header(&b"HTTP/2"[..]));
+/// ```
+pub trait GsSearchable<T> {
+    /// Return the text as a slice
+    fn as_text(&self) -> &[T];
+
+    /// Return the start of the first match of `pattern`, if any; see
+    /// `gs_find`.
+    fn gs_find<N: Needle<T>>(&self, pattern: N) -> Option<usize>
+        where T: Eq,
+    {
+        gs_find(self.as_text(), pattern)
+    }
+
+    /// Return `true` if `pattern` occurs in the text.
+    fn gs_contains<N: Needle<T>>(&self, pattern: N) -> bool
+        where T: Eq,
+    {
+        self.gs_find(pattern).is_some()
+    }
+
+    /// Return an iterator of the non-overlapping matches of `needle` in a
+    /// string, as slices of the string, like `str::matches`.
+    ///
+    /// An empty needle matches at each char boundary.
+    ///
+    /// ```
+    /// use galil_seiferas::GsSearchable;
+    ///
+    /// let text = "naïve café, naïve thé";
+    /// assert_eq!(text.gs_matches("naïve").collect::<Vec<_>>(), ["naïve", "naïve"]);
+    /// ```
+    fn gs_matches<'p, 't>(&'t self, needle: &'p str) -> StrMatches<'p, 't>
+        where Self: AsRef<str>,
+    {
+        StrMatches::new(self.as_ref(), needle)
+    }
+
+    /// Return an iterator of the non-overlapping matches of `needle` in a
+    /// string, with the byte index and the char index of the start of each
+    /// match.
+    ///
+    /// The char indices are counted during the same pass over the string,
+    /// so all of them are computed in **O(n)** time.
+    ///
+    /// ```
+    /// use galil_seiferas::GsSearchable;
+    ///
+    /// let text = "ça va, ça va";
+    /// let found = text.gs_char_match_indices("va").collect::<Vec<_>>();
+    /// assert_eq!(found, [(4, 3, "va"), (12, 10, "va")]);
+    /// ```
+    fn gs_char_match_indices<'p, 't>(&'t self, needle: &'p str) -> CharMatchIndices<'p, 't>
+        where Self: AsRef<str>,
+    {
+        CharMatchIndices::new(self.as_ref(), needle)
+    }
+}
+
+impl<T> GsSearchable<T> for [T] {
+    fn as_text(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> GsSearchable<T> for &[T] {
+    fn as_text(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> GsSearchable<T> for [T; N] {
+    fn as_text(&self) -> &[T] {
+        self
+    }
+}
+
+impl GsSearchable<u8> for str {
+    fn as_text(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl GsSearchable<u8> for &str {
+    fn as_text(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> GsSearchable<T> for Vec<T> {
+    fn as_text(&self) -> &[T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> GsSearchable<T> for Box<[T]> {
+    fn as_text(&self) -> &[T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl GsSearchable<u8> for String {
+    fn as_text(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[test]
+fn test_searchable() {
+    fn find<H: GsSearchable<u8> + ?Sized>(text: &H) -> Option<usize> {
+        text.gs_find(b"ca")
+    }
+
+    assert_eq!(find(&b"abcabc"[..]), Some(2));
+    assert_eq!(find(&&b"abcabc"[..]), Some(2));
+    assert_eq!(find(b"abcabc"), Some(2));
+    assert_eq!(find("abcabc"), Some(2));
+    assert_eq!(find(&"abcabc"), Some(2));
+    assert!([1, 2, 3].gs_contains(&3));
+    #[cfg(feature = "alloc")]
+    {
+        assert_eq!(find(&b"abcabc".to_vec()), Some(2));
+        assert_eq!(find(&b"abcabc".to_vec().into_boxed_slice()), Some(2));
+        assert_eq!(find(&String::from("abc")), None);
+        assert!(!vec![1, 2, 3].gs_contains([3, 2]));
+    }
+}
@@ -0,0 +1,305 @@
+
+use finder::{Finder, Match};
+use iter::{BoundaryFn, Matches, Options};
+use two_way::two_way_info;
+
+/// The string matching algorithm used by a searcher.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Engine {
+    /// The Galil-Seiferas algorithm
+    #[default]
+    GalilSeiferas,
+    /// The Two-Way algorithm, see `TwoWay`; it needs `T: Ord`, and
+    /// `GsSearcherBuilder::build_ord`.
+    TwoWay,
+    /// Let the builder choose: Two-Way with `build_ord`, and Galil-Seiferas
+    /// with `build`.
+    ///
+    /// Use `GsSearcher::engine` to see which engine was chosen.
+    Auto,
+}
+
+/// A builder for a configured searcher, `GsSearcher`.
+///
+/// ```
+/// use galil_seiferas::GsSearcherBuilder;
+///
+/// let searcher = GsSearcherBuilder::new()
+///     .range(2..12)
+///     .overlapping(true)
+///     .build(b"aa");
+///
+/// let text = b"aaaaaaaa";
+/// let starts = searcher.find_iter(text).map(|m| m.start()).collect::<Vec<_>>();
+/// assert_eq!(starts, [2, 3, 4, 5, 6]);
+/// ```
+pub struct GsSearcherBuilder<T> {
+    options: Options<T>,
+    engine: Engine,
+}
+
+impl<T> Default for GsSearcherBuilder<T> {
+    fn default() -> Self {
+        GsSearcherBuilder {
+            options: Options::default(),
+            engine: Engine::default(),
+        }
+    }
+}
+
+impl<T: Eq> GsSearcherBuilder<T> {
+    /// Create a new builder with the default options: search the whole
+    /// text for non-overlapping matches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start searching at `offset` in the text.
+    ///
+    /// Match offsets are still reported relative to the start of the text.
+    pub fn start(&mut self, offset: usize) -> &mut Self {
+        self.options.start = offset;
+        self
+    }
+
+    /// Only search the part `range` of the text; matches must be completely
+    /// inside the range. The end of the range is clamped to the text length.
+    ///
+    /// Match offsets are still reported relative to the start of the text.
+    pub fn range(&mut self, range: ::std::ops::Range<usize>) -> &mut Self {
+        self.options.start = range.start;
+        self.options.end = Some(range.end);
+        self
+    }
+
+    /// If `anchored` is true, a match must start exactly at the start offset.
+    pub fn anchored(&mut self, anchored: bool) -> &mut Self {
+        self.options.anchored = anchored;
+        self
+    }
+
+    /// If `overlapping` is true, iterate all matches, including those that
+    /// overlap previous matches. The default is false.
+    pub fn overlapping(&mut self, overlapping: bool) -> &mut Self {
+        self.options.overlapping = overlapping;
+        self
+    }
+
+    /// Prefetch the text `distance` elements ahead of the scan position, if
+    /// it is nonzero; the default is zero.
+    ///
+    /// Scanning large texts that are not in the cache, for example memory
+    /// mapped files, is bound by memory latency; prefetching helps with that.
+    /// It only has an effect on x86-64, and with the Galil-Seiferas engine.
+    #[cfg(feature = "prefetch")]
+    pub fn prefetch(&mut self, distance: usize) -> &mut Self {
+        self.options.prefetch = distance;
+        self
+    }
+
+    /// Only accept matches where `boundary(text, start, end)` returns true.
+    ///
+    /// Rejected matches are skipped as if they did not exist, so they do not
+    /// hide overlapping matches.
+    pub fn boundary(&mut self, boundary: BoundaryFn<T>) -> &mut Self {
+        self.options.boundary = Some(boundary);
+        self
+    }
+
+    /// Preprocess `pattern` and create the searcher, with the Galil-Seiferas
+    /// engine.
+    ///
+    /// Two-Way needs `T: Ord`; if it is selected, this falls back to
+    /// Galil-Seiferas, and `GsSearcher::engine` reports that. Use `build_ord`
+    /// for the selected engine.
+    pub fn build<'a>(&self, pattern: &'a [T]) -> GsSearcher<'a, T> {
+        GsSearcher {
+            finder: Finder::new(pattern),
+            options: self.options,
+            engine: Engine::GalilSeiferas,
+        }
+    }
+}
+
+impl<T: Ord> GsSearcherBuilder<T> {
+    /// Select the string matching algorithm; the default is Galil-Seiferas.
+    ///
+    /// The engine can only be selected for elements that are `Ord`, since
+    /// Two-Way needs an ordering; create the searcher with `build_ord`.
+    pub fn engine(&mut self, engine: Engine) -> &mut Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Preprocess `pattern` and create the searcher, with any engine.
+    ///
+    /// `Engine::Auto` chooses Two-Way.
+    ///
+    /// ```
+    /// use galil_seiferas::{Engine, GsSearcherBuilder};
+    ///
+    /// let searcher = GsSearcherBuilder::new().engine(Engine::Auto).build_ord(b"ab");
+    /// assert_eq!(searcher.engine(), Engine::TwoWay);
+    /// assert_eq!(searcher.find_iter(b"abcab").count(), 2);
+    /// ```
+    pub fn build_ord<'a>(&self, pattern: &'a [T]) -> GsSearcher<'a, T> {
+        let mut options = self.options;
+        let engine = match self.engine {
+            Engine::GalilSeiferas => Engine::GalilSeiferas,
+            Engine::TwoWay | Engine::Auto => {
+                options.two_way = Some(two_way_info(pattern));
+                Engine::TwoWay
+            }
+        };
+        GsSearcher {
+            finder: Finder::new(pattern),
+            options,
+            engine,
+        }
+    }
+}
+
+/// A searcher for a preprocessed pattern with search options, created by
+/// `GsSearcherBuilder`.
+pub struct GsSearcher<'a, T: 'a> {
+    finder: Finder<'a, T>,
+    options: Options<T>,
+    engine: Engine,
+}
+
+impl<'a, T: Eq> GsSearcher<'a, T> {
+    /// The finder for the pattern
+    pub fn finder(&self) -> &Finder<'a, T> {
+        &self.finder
+    }
+
+    /// The string matching algorithm that is used; never `Engine::Auto`.
+    pub fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    /// Return the first match in `text`, if any.
+    pub fn find(&self, text: &[T]) -> Option<Match> {
+        self.find_iter(text).next()
+    }
+
+    /// Return an iterator of the matches in `text`.
+    pub fn find_iter<'s, 't>(&'s self, text: &'t [T]) -> Matches<'s, 't, T> {
+        Matches::new(&self.finder, text, self.options)
+    }
+}
+
+#[test]
+fn test_searcher_options() {
+    let text = b"ab ab abab ab";
+    let starts = |searcher: &GsSearcher<u8>| {
+        searcher.find_iter(text).map(|m| m.start()).collect::<Vec<_>>()
+    };
+    let searcher = GsSearcherBuilder::new().build(b"ab");
+    assert_eq!(starts(&searcher), vec![0, 3, 6, 8, 11]);
+    assert_eq!(searcher.engine(), Engine::GalilSeiferas);
+
+    let searcher = GsSearcherBuilder::new().start(1).build(b"ab");
+    assert_eq!(starts(&searcher), vec![3, 6, 8, 11]);
+
+    let searcher = GsSearcherBuilder::new().range(1..10).build(b"ab");
+    assert_eq!(starts(&searcher), vec![3, 6, 8]);
+
+    let searcher = GsSearcherBuilder::new().start(3).anchored(true).build(b"ab");
+    assert_eq!(starts(&searcher), vec![3]);
+    let searcher = GsSearcherBuilder::new().start(4).anchored(true).build(b"ab");
+    assert_eq!(starts(&searcher), vec![]);
+
+    fn word_boundary(text: &[u8], start: usize, end: usize) -> bool {
+        (start == 0 || text[start - 1] == b' ') && (end == text.len() || text[end] == b' ')
+    }
+    let searcher = GsSearcherBuilder::new().boundary(word_boundary).build(b"ab");
+    assert_eq!(starts(&searcher), vec![0, 3, 11]);
+
+    let searcher = GsSearcherBuilder::new().overlapping(true).build(b"aba");
+    assert_eq!(searcher.find_iter(b"ababa").map(|m| m.start()).collect::<Vec<_>>(),
+               vec![0, 2]);
+}
+
+#[test]
+fn test_searcher_engine() {
+    let text = b"ab ab abab ab";
+    for &engine in &[Engine::GalilSeiferas, Engine::TwoWay, Engine::Auto] {
+        let mut builder = GsSearcherBuilder::new();
+        builder.engine(engine);
+        let searcher = builder.build_ord(b"ab");
+        let expected = if engine == Engine::GalilSeiferas { engine } else { Engine::TwoWay };
+        assert_eq!(searcher.engine(), expected);
+        let starts = searcher.find_iter(text).map(|m| m.start()).collect::<Vec<_>>();
+        assert_eq!(starts, vec![0, 3, 6, 8, 11]);
+
+        let searcher = builder.range(1..10).overlapping(true).build_ord(b"aa");
+        let starts = searcher.find_iter(b"aaaaaaaaaaaa").map(|m| m.start()).collect::<Vec<_>>();
+        assert_eq!(starts, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+    let searcher = GsSearcherBuilder::new().engine(Engine::Auto).build(b"ab");
+    assert_eq!(searcher.engine(), Engine::GalilSeiferas);
+
+    // The overlapping matches, with the Two-Way memory kept between them
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbb";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 12) {
+            let pattern = &text[start..end];
+            let mut builder = GsSearcherBuilder::new();
+            builder.overlapping(true);
+            let expected = builder.build(pattern).find_iter(text).collect::<Vec<_>>();
+            let two_way = builder.engine(Engine::TwoWay).build_ord(pattern);
+            assert_eq!(two_way.find_iter(text).collect::<Vec<_>>(), expected,
+                       "{}", ::Bytestring(pattern));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "prefetch")]
+fn test_searcher_prefetch() {
+    let text = "ab".repeat(1000) + "abc";
+    for &distance in &[0, 1, 64, 4096, usize::MAX] {
+        let searcher = GsSearcherBuilder::new().prefetch(distance).overlapping(true).build(b"abab");
+        assert_eq!(searcher.find_iter(text.as_bytes()).count(), 1000);
+        let searcher = GsSearcherBuilder::new().prefetch(distance).build(b"abc");
+        assert_eq!(searcher.find(text.as_bytes()).map(|m| m.start()), Some(2000));
+    }
+}
+
+#[test]
+fn test_searcher_two_way_fallback() {
+    let searcher = GsSearcherBuilder::new().engine(Engine::TwoWay).build(b"ab");
+    assert_eq!(searcher.engine(), Engine::GalilSeiferas);
+    assert_eq!(searcher.find_iter(b"abcab").count(), 2);
+}
+
+#[test]
+fn test_searcher_two_way_overlapping_linear() {
+    use std::cell::Cell;
+
+    // An element that counts its comparisons
+    #[derive(Eq, PartialOrd, Ord)]
+    struct Counted<'a>(u8, &'a Cell<usize>);
+
+    impl<'a> PartialEq for Counted<'a> {
+        fn eq(&self, other: &Self) -> bool {
+            self.1.set(self.1.get() + 1);
+            self.0 == other.0
+        }
+    }
+
+    let count = Cell::new(0);
+    let n = 3000;
+    let text = (0..n).map(|_| Counted(b'a', &count)).collect::<Vec<_>>();
+    for &m in &[1, 3, 100] {
+        let pattern = (0..m).map(|_| Counted(b'a', &count)).collect::<Vec<_>>();
+        let searcher = GsSearcherBuilder::new().engine(Engine::TwoWay).overlapping(true)
+                                               .build_ord(&pattern);
+        count.set(0);
+        assert_eq!(searcher.find_iter(&text).count(), n - m + 1);
+        // Each match is one comparison past the remembered prefix
+        assert!(count.get() <= 2 * n + 2 * m, "{} {} comparisons", m, count.get());
+    }
+}
@@ -0,0 +1,224 @@
+#[cfg(feature = "alloc")]
+use std::cmp::{max, min};
+#[cfg(feature = "alloc")]
+use std::ops::Range;
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+use analysis::longest_suffix_prefix_by;
+#[cfg(feature = "alloc")]
+use kmp::borders;
+#[cfg(feature = "alloc")]
+use prefix::matching_statistics;
+use searcher::GsSearcherBuilder;
+
+/// Return true if `seed` is a seed of `x`.
+///
+/// A seed of `x` is a factor of `x` that covers a superstring of `x`: each
+/// position of `x` is inside an occurrence of the seed, where occurrences
+/// may overhang the start and the end of `x`. Each cover of `x` is a seed,
+/// see `shortest_cover`.
+///
+/// This computes in **O(m)** time.
+///
+/// ```
+/// use galil_seiferas::is_seed;
+///
+/// // x is in abaababaaba, which is covered by aba
+/// assert!(is_seed(b"baababaa", b"aba"));
+/// assert!(!is_seed(b"baababaa", b"ab"));
+/// ```
+pub fn is_seed<T: Eq>(x: &[T], seed: &[T]) -> bool {
+    let (n, l) = (x.len(), seed.len());
+    if l == 0 || l > n {
+        return n == 0 && l == 0;
+    }
+    // The longest prefix of x that is covered by an occurrence of the seed
+    // that overhangs the start, and likewise the longest suffix at the end
+    let mut covered = longest_suffix_prefix_by(&seed[1..], x, &T::eq);
+    let end = n - longest_suffix_prefix_by(&x[n - l + 1..], seed, &T::eq);
+    let mut found = false;
+    let searcher = GsSearcherBuilder::new().overlapping(true).build(seed);
+    for m in searcher.find_iter(x) {
+        if m.start() > covered {
+            return false;
+        }
+        covered = m.end();
+        found = true;
+        if covered >= end {
+            break;
+        }
+    }
+    found && covered >= end
+}
+
+/// Return the range of the first occurrence of the shortest seed of `x`.
+///
+/// The shortest seed is at most as long as the shortest cover; see `is_seed`
+/// for the definition.
+///
+/// For each start i, the seeds that start there are checked for all lengths
+/// in one pass, from the lengths of the common prefixes of `x[i..]` and each
+/// suffix of `x` (see `matching_statistics`): the occurrences of `x[i..i + l]`
+/// are the positions where that length is at least l, so they are removed
+/// in order of length, and the overhangs at the start and at the end are
+/// tracked like in `is_seed`. This computes in **O(m²)** time and **O(m)**
+/// space.
+///
+/// ```
+/// use galil_seiferas::shortest_seed;
+///
+/// // The shortest cover is aaba
+/// let x = b"aabaabaaba";
+/// assert_eq!(&x[shortest_seed(x)], b"aab");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn shortest_seed<T: Eq>(x: &[T]) -> Range<usize> {
+    let n = x.len();
+    let mut border = vec![0; n];
+    borders(x, &mut border);
+    // lcp[p] is the length of the common prefix of x[p..] and x[i..]
+    let mut lcp = vec![0; n];
+    // The positions sorted by lcp, with the start of each length in order
+    let mut order = vec![0; n];
+    let mut start = vec![0; n + 2];
+    // The occurrences of x[i..i + l], as a doubly linked list of positions,
+    // which is circular through the sentinel n
+    let mut next = vec![0; n + 1];
+    let mut prev = vec![0; n + 1];
+    // x is a seed of itself
+    let mut best = 0..n;
+    // A seed of length l occurs at some i < l, see below
+    let mut i = 0;
+    while i + 1 < best.len() {
+        matching_statistics(x, &x[i..], &mut lcp);
+        for elt in &mut start[..n - i + 2] {
+            *elt = 0;
+        }
+        for &len in &lcp {
+            start[len + 1] += 1;
+        }
+        for len in 1..n - i + 2 {
+            start[len] += start[len - 1];
+        }
+        for (p, &len) in lcp.iter().enumerate() {
+            order[start[len]] = p;
+            start[len] += 1;
+        }
+        // Now start[len] is the end of the positions of lcp len in order
+        for p in 0..n + 1 {
+            next[p] = (p + 1) % (n + 1);
+            prev[(p + 1) % (n + 1)] = p;
+        }
+        let mut removed = 0;
+        let mut max_gap = 1;
+        // The longest prefix of x that is a suffix of x[i + 1..i + l], and
+        // the longest suffix of x shorter than l that is a prefix of x[i..]
+        let mut overhang_start = 0;
+        let mut overhang_end = 0;
+        for l in 1..min(best.len(), n - i + 1) {
+            while removed < start[l - 1] {
+                let p = order[removed];
+                let (a, b) = (prev[p], next[p]);
+                next[a] = b;
+                prev[b] = a;
+                if a != n && b != n {
+                    max_gap = max(max_gap, b - a);
+                }
+                removed += 1;
+            }
+            if l > 1 {
+                let elt = &x[i + l - 1];
+                while overhang_start > 0 && x[overhang_start] != *elt {
+                    overhang_start = border[overhang_start - 1];
+                }
+                if x[overhang_start] == *elt {
+                    overhang_start += 1;
+                }
+                if lcp[n - (l - 1)] == l - 1 {
+                    overhang_end = l - 1;
+                }
+            }
+            // Position l - 1 is inside an occurrence of a seed of length l
+            // that starts before l; if it overhangs the end, then n - l < l.
+            // So the seed occurs in x at some i < l, and the first
+            // occurrence is found with the least i.
+            if l <= i {
+                continue;
+            }
+            let (first, last) = (next[n], prev[n]);
+            if first <= overhang_start && max_gap <= l && last + l + overhang_end >= n {
+                best = i..i + l;
+                break;
+            }
+        }
+        i += 1;
+    }
+    best
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_seeds() {
+    fn brute_force_is_seed(x: &[u8], seed: &[u8]) -> bool {
+        let (n, l) = (x.len() as isize, seed.len() as isize);
+        // An occurrence at offset o agrees with x where they overlap
+        let fits = |o: isize| {
+            (o.max(0)..(o + l).min(n)).all(|p| x[p as usize] == seed[(p - o) as usize])
+        };
+        let is_factor = (0..n - l + 1).any(&fits);
+        is_factor && (0..n).all(|p| (p - l + 1..p + 1).any(&fits))
+    }
+
+    assert!(is_seed::<u8>(b"", b""));
+    assert!(!is_seed(b"ab", b""));
+    assert!(!is_seed(b"ab", b"abc"));
+    assert!(!is_seed(b"ab", b"ba"));
+    assert_eq!(shortest_seed::<u8>(b""), 0..0);
+    assert_eq!(shortest_seed(b"abcd"), 0..4);
+
+    // every ternary word up to length 8
+    for len in 1..9 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| b'a' + (n / 3u32.pow(i) % 3) as u8).collect::<Vec<_>>();
+            let n = x.len();
+            let mut expected = None;
+            for l in 1..n + 1 {
+                for i in 0..n - l + 1 {
+                    let seed = &x[i..i + l];
+                    assert_eq!(is_seed(&x, seed), brute_force_is_seed(&x, seed),
+                               "{} {}", ::Bytestring(&x), ::Bytestring(seed));
+                    if expected.is_none() && brute_force_is_seed(&x, seed) {
+                        expected = Some(i..i + l);
+                    }
+                }
+            }
+            assert_eq!(shortest_seed(&x), expected.unwrap(), "{}", ::Bytestring(&x));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_shortest_seed_long() {
+    // Check the candidates in order of length and start
+    fn brute_force(x: &[u8]) -> Range<usize> {
+        let n = x.len();
+        (1..n + 1).flat_map(|l| (0..n - l + 1).map(move |i| i..i + l))
+                  .find(|r| is_seed(x, &x[r.clone()]))
+                  .unwrap_or(0..0)
+    }
+
+    // every binary word of length 14, and the prefixes of the Fibonacci word
+    let len = 14;
+    for n in 0..1u32 << len {
+        let x = (0..len).map(|i| b'a' + (n >> i & 1) as u8).collect::<Vec<_>>();
+        assert_eq!(shortest_seed(&x), brute_force(&x), "{}", ::Bytestring(&x));
+    }
+    let fibonacci = b"abaababaabaababaababaabaababaabaababaababaabaababaababaabaab";
+    for len in 0..fibonacci.len() + 1 {
+        let x = &fibonacci[..len];
+        assert_eq!(shortest_seed(x), brute_force(x), "{}", ::Bytestring(x));
+    }
+}
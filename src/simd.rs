@@ -0,0 +1,121 @@
+
+use engine::SearchEngine;
+use two_way::{two_way_find_with, two_way_info, TwoWayInfo};
+
+/// The Two-Way algorithm for byte strings, with a SIMD prefilter.
+///
+/// Where the Two-Way search has no partial match to remember, it skips ahead
+/// to the next position where both the first and the last byte of the
+/// pattern match, testing 16 positions at a time with SSE2 on x86-64 (and
+/// one at a time elsewhere). The search stays linear time in the worst case,
+/// like `TwoWay`, and it is much faster on typical texts.
+///
+/// ```
+/// use galil_seiferas::{SearchEngine, SimdTwoWay};
+///
+/// let engine = SimdTwoWay::new(b"fox");
+/// assert_eq!(engine.find(b"the quick brown fox"), Some(16));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct SimdTwoWay<'a> {
+    pattern: &'a [u8],
+    info: TwoWayInfo,
+}
+
+impl<'a> SimdTwoWay<'a> {
+    /// Preprocess `pattern` for searching.
+    pub fn new(pattern: &'a [u8]) -> Self {
+        SimdTwoWay { pattern, info: two_way_info(pattern) }
+    }
+}
+
+impl<'a> SearchEngine<u8> for SimdTwoWay<'a> {
+    fn pattern(&self) -> &[u8] {
+        self.pattern
+    }
+
+    fn find_at(&self, text: &[u8], start: usize) -> Option<usize> {
+        let m = self.pattern.len();
+        if m == 0 {
+            return if start <= text.len() { Some(start) } else { None };
+        }
+        if text.len() < m {
+            return None;
+        }
+        let (first, last) = (self.pattern[0], self.pattern[m - 1]);
+        let last_start = text.len() - m;
+        two_way_find_with(&self.info, self.pattern, text, start, |pos| {
+            screen(text, pos, last_start, first, last, m - 1)
+        })
+    }
+}
+
+/// Return the first position `i` in `pos..=last_start` where
+/// `text[i] == first` and `text[i + offset] == last`, if any.
+///
+/// `last_start + offset` must be less than `text.len()`.
+#[cfg(target_arch = "x86_64")]
+fn screen(text: &[u8], pos: usize, last_start: usize, first: u8, last: u8, offset: usize)
+    -> Option<usize>
+{
+    use std::arch::x86_64::{_mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128,
+                            _mm_movemask_epi8, _mm_set1_epi8, __m128i};
+
+    debug_assert!(last_start + offset < text.len());
+    let mut i = pos;
+    // SSE2 is always available on x86-64.
+    unsafe {
+        let firsts = _mm_set1_epi8(first as i8);
+        let lasts = _mm_set1_epi8(last as i8);
+        while i + 16 <= last_start + 1 {
+            // The loads end at i + offset + 16 <= last_start + offset + 1
+            let a = _mm_loadu_si128(text.as_ptr().add(i) as *const __m128i);
+            let b = _mm_loadu_si128(text.as_ptr().add(i + offset) as *const __m128i);
+            let both = _mm_and_si128(_mm_cmpeq_epi8(a, firsts), _mm_cmpeq_epi8(b, lasts));
+            let mask = _mm_movemask_epi8(both);
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += 16;
+        }
+    }
+    screen_scalar(text, i, last_start, first, last, offset)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn screen(text: &[u8], pos: usize, last_start: usize, first: u8, last: u8, offset: usize)
+    -> Option<usize>
+{
+    screen_scalar(text, pos, last_start, first, last, offset)
+}
+
+fn screen_scalar(text: &[u8], pos: usize, last_start: usize, first: u8, last: u8,
+                 offset: usize) -> Option<usize>
+{
+    (pos..last_start + 1).find(|&i| text[i] == first && text[i + offset] == last)
+}
+
+#[test]
+fn test_simd_two_way() {
+    let text = b"abaababaabaababaababaabaababaabaab.abaababaabaababaababaabaababaabaab";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 20) {
+            let pattern = &text[start..end];
+            let engine = SimdTwoWay::new(pattern);
+            let starts = (0..text.len() + 1)
+                .filter(|&i| text[i..].starts_with(pattern))
+                .collect::<Vec<_>>();
+            assert_eq!(engine.find_iter(text).next(), starts.first().cloned());
+            let found = (0..text.len() + 1)
+                .filter_map(|i| engine.find_at(text, i))
+                .collect::<::std::collections::BTreeSet<_>>();
+            assert_eq!(found.into_iter().collect::<Vec<_>>(), starts);
+        }
+    }
+    let mut long = vec![b'x'; 100];
+    long.extend_from_slice(b"needle");
+    assert_eq!(SimdTwoWay::new(b"needle").find(&long), Some(100));
+    assert_eq!(SimdTwoWay::new(b"needle").find(&long[..105]), None);
+    assert_eq!(SimdTwoWay::new(b"").find_at(b"ab", 2), Some(2));
+    assert_eq!(SimdTwoWay::new(b"").find_at(b"ab", 3), None);
+}
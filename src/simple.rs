@@ -0,0 +1,131 @@
+
+use analysis::PatternInfo;
+use engine::SearchEngine;
+use {hrp_by, search_simple, Hrp};
+
+/// A k-simple pattern: a pattern with at most one k-HRP, which can be
+/// searched for without the decomposition that `Pattern` and `Finder` need.
+///
+/// Every pattern `v` in the decomposition u v is k-simple, so this is useful
+/// when the analysis of the pattern is already known, for example when it
+/// was cached with `PatternInfo` or when the pattern was generated.
+///
+/// ```
+/// use galil_seiferas::{analyze, SearchEngine, SimplePattern};
+///
+/// let pattern = b"abracadabra";
+/// let info = analyze(pattern);
+///
+/// // later, with the cached analysis
+/// let simple = SimplePattern::from_info(pattern, &info).unwrap();
+/// assert_eq!(simple.find(b"abracadabracadabra"), Some(0));
+/// ```
+#[derive(Debug)]
+pub struct SimplePattern<'a, T: 'a> {
+    pattern: &'a [T],
+    hrp1: Option<Hrp>,
+}
+
+impl<'a, T> Copy for SimplePattern<'a, T> { }
+impl<'a, T> Clone for SimplePattern<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: Eq> SimplePattern<'a, T> {
+    /// Check that `pattern` is k-simple, and return it if so.
+    ///
+    /// This computes in **O(m)** time; it is cheaper than `Finder::new`,
+    /// which also computes the period of the pattern.
+    pub fn new(pattern: &'a [T]) -> Option<Self> {
+        match hrp_by(1, 0, pattern, None, &T::eq) {
+            (hrp1, None) => Some(SimplePattern { pattern, hrp1 }),
+            (_, Some(_)) => None,
+        }
+    }
+
+    /// Use the k-simple `pattern` whose only k-HRP is `hrp1`, without any
+    /// preprocessing.
+    ///
+    /// This is checked with debug assertions only. If `pattern` is not
+    /// k-simple, or `hrp1` is not its k-HRP, searches may miss matches or
+    /// not terminate (but they stay memory safe).
+    pub fn new_unchecked(pattern: &'a [T], hrp1: Option<Hrp>) -> Self {
+        #[cfg(not(feature = "skip-debug-checks"))]
+        debug_assert_eq!(hrp_by(1, 0, pattern, None, &T::eq), (hrp1, None),
+                         "SimplePattern: the pattern must be k-simple with the given HRP");
+        SimplePattern { pattern, hrp1 }
+    }
+
+    /// Use the analysis `info` of `pattern`, if the pattern is k-simple,
+    /// without any preprocessing.
+    ///
+    /// The pattern is k-simple if its decomposition u v has an empty u.
+    pub fn from_info(pattern: &'a [T], info: &PatternInfo) -> Option<Self> {
+        debug_assert_eq!(pattern.len(), info.len());
+        if info.u_len() == 0 {
+            Some(SimplePattern::new_unchecked(pattern, info.hrp()))
+        } else {
+            None
+        }
+    }
+
+    /// The (only) k-HRP of the pattern, if it exists.
+    pub fn hrp(&self) -> Option<Hrp> {
+        self.hrp1
+    }
+}
+
+impl<'a, T: Eq> SearchEngine<T> for SimplePattern<'a, T> {
+    fn pattern(&self) -> &[T] {
+        self.pattern
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        if text.len() < self.pattern.len() || start > text.len() - self.pattern.len() {
+            return None;
+        }
+        let mut pos = start;
+        let mut j = 0;
+        search_simple(text, self.pattern, &mut pos, &mut j, &self.hrp1, 0, &T::eq)
+    }
+}
+
+#[test]
+fn test_simple_pattern() {
+    use analysis::analyze;
+
+    assert!(SimplePattern::new(b"banana").is_some());
+    assert!(SimplePattern::new(b"aaabaaabaaabaa").is_none());
+
+    let text = b"abaababaabaababaababaabaababaabaab.abaababaabaab";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 14) {
+            let pattern = &text[start..end];
+            let info = analyze(pattern);
+            let simple = match SimplePattern::new(pattern) {
+                Some(simple) => simple,
+                None => {
+                    assert!(SimplePattern::from_info(pattern, &info).is_none());
+                    continue;
+                }
+            };
+            assert_eq!(simple.hrp(), info.hrp());
+            let starts = (0..text.len() + 1)
+                .filter(|&i| text[i..].starts_with(pattern))
+                .collect::<Vec<_>>();
+            assert_eq!(simple.find_iter(text).next(), starts.first().cloned());
+            let found = (0..text.len() + 1)
+                .filter_map(|i| simple.find_at(text, i))
+                .collect::<::std::collections::BTreeSet<_>>();
+            assert_eq!(found.into_iter().collect::<Vec<_>>(), starts);
+        }
+    }
+    assert_eq!(SimplePattern::new(b"").unwrap().find_at(b"ab", 2), Some(2));
+}
+
+#[test]
+#[should_panic]
+#[cfg(all(debug_assertions, not(feature = "skip-debug-checks")))]
+fn test_simple_pattern_wrong_hrp() {
+    SimplePattern::new_unchecked(b"aaab", None);
+}
@@ -0,0 +1,121 @@
+use std::cmp::{max, min};
+
+use analysis::PatternInfo;
+use finder::{to_match, Finder, Match};
+use SearchState;
+
+/// The result of `SlicedSearch::run_for`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SearchPoll {
+    /// The search is not done; run it again to continue.
+    Pending,
+    /// A match was found; run the search again for the next match.
+    Found(Match),
+    /// The whole text was searched, and there are no more matches.
+    Done,
+}
+
+/// A search of a text that does a bounded amount of work per call, for
+/// driving it from an event loop or a game tick.
+///
+/// The caller owns the scheduling: each `run_for` advances the search over
+/// at most about `max_elems` text positions (and at most the pattern length
+/// more on the first call) and returns. The search is the same **O(n)**
+/// search as `Finder::find_iter`, however it is sliced, and the matches do
+/// not overlap.
+///
+/// ```
+/// use galil_seiferas::{SearchPoll, SlicedSearch};
+///
+/// let text = "lorem ipsum dolor sit amet, consectetur adipiscing elit".as_bytes();
+/// let mut search = SlicedSearch::new(b"it", text);
+/// let mut ticks = 0;
+/// let mut found = Vec::new();
+/// loop {
+///     match search.run_for(8) {
+///         SearchPoll::Pending => ticks += 1,
+///         SearchPoll::Found(m) => found.push(m.start()),
+///         SearchPoll::Done => break,
+///     }
+/// }
+/// assert_eq!(found, [19, 53]);
+/// assert!(ticks >= 5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SlicedSearch<'a, 't, T: 'a + 't> {
+    finder: Finder<'a, T>,
+    info: PatternInfo,
+    text: &'t [T],
+    state: SearchState,
+    /// The length of the prefix of the text that is searched so far
+    searched: usize,
+}
+
+impl<'a, 't, T: Eq> SlicedSearch<'a, 't, T> {
+    /// Preprocess `pattern`, for a search of `text` from the start.
+    pub fn new(pattern: &'a [T], text: &'t [T]) -> Self {
+        SlicedSearch::from_finder(Finder::new(pattern), text)
+    }
+
+    /// Use the preprocessing of `finder`, for a search of `text` from the
+    /// start.
+    pub fn from_finder(finder: Finder<'a, T>, text: &'t [T]) -> Self {
+        let info = finder.info();
+        SlicedSearch { finder, info, text, state: SearchState::default(), searched: 0 }
+    }
+
+    /// The length of the prefix of the text that is searched so far; all the
+    /// matches that end there are reported.
+    pub fn searched_len(&self) -> usize {
+        self.searched
+    }
+
+    /// Continue the search over at most about `max_elems` more positions of
+    /// the text (but at least one), and return the next match if it is found
+    /// there.
+    pub fn run_for(&mut self, max_elems: usize) -> SearchPoll {
+        let m = self.info.len();
+        let end = max(self.searched, m).saturating_add(max(max_elems, 1));
+        self.searched = min(end, self.text.len());
+        let pattern = self.finder.as_pattern(&self.info);
+        match pattern.search_from(&self.text[..self.searched], &mut self.state) {
+            Some(start) => {
+                if self.state.pos < start + m {
+                    self.state.restart(start + m);
+                }
+                SearchPoll::Found(to_match(&self.info, start))
+            }
+            None if self.searched == self.text.len() => SearchPoll::Done,
+            None => SearchPoll::Pending,
+        }
+    }
+}
+
+#[test]
+fn test_sliced_search() {
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbbaaabaaabaaabaabbbb";
+    // the last pattern has a nonempty u
+    for pattern in &[&b"abaab"[..], b"aab", b"aabaab", b"baabaabaab", b"", b"aaabaaabaaabaabbbb"] {
+        let finder = Finder::new(pattern);
+        let expected = finder.find_iter(text).collect::<Vec<_>>();
+        for &max_elems in &[0, 1, 2, 3, 7, text.len()] {
+            let mut search = SlicedSearch::from_finder(finder.clone(), text);
+            let mut found = Vec::new();
+            let mut calls = 0;
+            loop {
+                let searched = search.searched_len();
+                match search.run_for(max_elems) {
+                    SearchPoll::Pending => { }
+                    SearchPoll::Found(m) => found.push(m),
+                    SearchPoll::Done => break,
+                }
+                assert!(search.searched_len() <= searched.max(pattern.len()) + max_elems.max(1));
+                calls += 1;
+                assert!(calls <= 2 * text.len() + 2);
+            }
+            assert_eq!(found, expected, "{} {}", ::Bytestring(pattern), max_elems);
+            assert_eq!(search.run_for(max_elems), SearchPoll::Done);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use Needle;
+
+/// Split `text` at the first match of `pattern`, and return the parts
+/// before the match, the match, and after the match; `None` if there is no
+/// match.
+///
+/// ```
+/// use galil_seiferas::split_at_first_match;
+///
+/// let (key, _, value) = split_at_first_match(b"Host: example.com", b": ").unwrap();
+/// assert_eq!(key, b"Host");
+/// assert_eq!(value, b"example.com");
+/// assert_eq!(split_at_first_match(b"Host", b": "), None);
+/// ```
+pub fn split_at_first_match<T: Eq, N: Needle<T>>(text: &[T], pattern: N)
+    -> Option<(&[T], &[T], &[T])>
+{
+    let pattern = pattern.as_needle();
+    let start = ::gs_find(text, pattern)?;
+    let (before, rest) = text.split_at(start);
+    let (matched, after) = rest.split_at(pattern.len());
+    Some((before, matched, after))
+}
+
+#[test]
+fn test_split_at_first_match() {
+    let text = &b"a, b, c"[..];
+    assert_eq!(split_at_first_match(text, b", "), Some((&b"a"[..], &b", "[..], &b"b, c"[..])));
+    assert_eq!(split_at_first_match(text, b"c"), Some((&b"a, b, "[..], &b"c"[..], &b""[..])));
+    assert_eq!(split_at_first_match(text, b""), Some((&b""[..], &b""[..], text)));
+    assert_eq!(split_at_first_match(text, b";"), None);
+    assert_eq!(split_at_first_match(b"", b"a"), None);
+}
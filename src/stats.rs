@@ -0,0 +1,149 @@
+use std::cell::Cell;
+use std::cmp::max;
+
+use finder::Finder;
+use haystack::Haystack;
+use {decomposition_by, search_from_by, SearchState};
+
+/// Summary statistics of the matches of a pattern, created by
+/// `gs_match_stats`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MatchStats {
+    /// The number of (non-overlapping) matches
+    pub count: usize,
+    /// The start of the first match
+    pub first: Option<usize>,
+    /// The start of the last match
+    pub last: Option<usize>,
+}
+
+/// Count the non-overlapping matches of `pattern` in `text` and find the
+/// first and the last, in a single **O(n)** pass and **O(1)** space.
+///
+/// The matches are the same as those of `Finder::find_iter`.
+///
+/// ```
+/// use galil_seiferas::{gs_match_stats, MatchStats};
+///
+/// let stats = gs_match_stats(b"GET /a GET /b GET /c", b"GET");
+/// assert_eq!(stats, MatchStats { count: 3, first: Some(0), last: Some(14) });
+/// ```
+pub fn gs_match_stats<T: Eq>(text: &[T], pattern: &[T]) -> MatchStats {
+    let finder = Finder::new(pattern);
+    finder.find_iter(text).fold(MatchStats::default(), |stats, m| {
+        MatchStats {
+            count: stats.count + 1,
+            first: stats.first.or(Some(m.start())),
+            last: Some(m.start()),
+        }
+    })
+}
+
+/// A view of a text that records how far it is read.
+struct Examined<'a, T: 'a> {
+    text: &'a [T],
+    /// One past the furthest index that is read
+    end: &'a Cell<usize>,
+}
+
+impl<'a, T> Clone for Examined<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Examined<'a, T> { }
+
+impl<'a, T> Haystack for Examined<'a, T> {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    fn get(&self, index: usize) -> &'a T {
+        self.end.set(max(self.end.get(), index + 1));
+        &self.text[index]
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> &'a T {
+        self.end.set(max(self.end.get(), index + 1));
+        ::unchecked_index::get_unchecked(self.text, index)
+    }
+}
+
+/// Find the first match of `pattern` in `text` like `gs_find`, and also
+/// return the number of text elements that the search examined, for
+/// accounting for the work of a search.
+///
+/// The search scans the text from the start, so the number is the length of
+/// the prefix of the text that it read: one past the furthest element that
+/// was compared. It is at most the end of the match, or the length of the
+/// text if there is no match. Each examined element is compared at most a
+/// constant number of times; see `estimate_cost`.
+///
+/// ```
+/// use galil_seiferas::gs_find_examined;
+///
+/// assert_eq!(gs_find_examined(b"a needle in a haystack", b"needle"), (Some(2), 8));
+/// // The last element can not start a match, and it is not read
+/// assert_eq!(gs_find_examined(b"xxxxx", b"ab"), (None, 4));
+/// ```
+pub fn gs_find_examined<T: Eq>(text: &[T], pattern: &[T]) -> (Option<usize>, usize) {
+    let (u_len, hrp1) = decomposition_by(pattern, &T::eq);
+    let (u, v) = pattern.split_at(u_len);
+    let end = Cell::new(0);
+    let view = Examined { text, end: &end };
+    let found = search_from_by(view, u, v, hrp1, &mut SearchState::default(), &T::eq);
+    (found, end.get())
+}
+
+#[test]
+fn test_find_examined() {
+    // Count the text elements that are read
+    struct Probe<'c>(u8, Option<usize>, &'c Cell<usize>);
+
+    impl<'c> PartialEq for Probe<'c> {
+        fn eq(&self, other: &Self) -> bool {
+            if let Some(i) = self.1.or(other.1) {
+                self.2.set(self.2.get().max(i + 1));
+            }
+            self.0 == other.0
+        }
+    }
+
+    impl<'c> Eq for Probe<'c> { }
+
+    let furthest = Cell::new(0);
+    let text = b"abaababaabaababaababaabaababaabaab.aaabaaabaaabaabbbb";
+    let probes = text.iter().enumerate()
+        .map(|(i, &x)| Probe(x, Some(i), &furthest))
+        .collect::<Vec<_>>();
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 20) {
+            // Also a pattern that is not found
+            let mut pattern = text[start..end].to_vec();
+            for _ in 0..2 {
+                let pattern_probes = pattern.iter().map(|&x| Probe(x, None, &furthest))
+                                                   .collect::<Vec<_>>();
+                furthest.set(0);
+                let (found, examined) = gs_find_examined(&probes, &pattern_probes);
+                assert_eq!(found, ::gs_find(text, &pattern[..]));
+                assert_eq!(examined, furthest.get(), "{}", ::Bytestring(&pattern));
+                assert!(examined <= found.map_or(text.len(), |i| i + pattern.len()));
+                pattern.push(b'c');
+            }
+        }
+    }
+    assert_eq!(gs_find_examined(b"abc", b""), (Some(0), 0));
+}
+
+#[test]
+fn test_match_stats() {
+    assert_eq!(gs_match_stats(b"aaaaa", b"aa"),
+               MatchStats { count: 2, first: Some(0), last: Some(2) });
+    assert_eq!(gs_match_stats(b"abc", b"d"), MatchStats::default());
+    assert_eq!(gs_match_stats(b"abc", b""),
+               MatchStats { count: 4, first: Some(0), last: Some(3) });
+}
+
@@ -0,0 +1,181 @@
+//! Strategies for property testing string searches with `proptest`.
+//!
+//! The strategies generate byte strings with the structure that makes string
+//! search hard: texts that contain the pattern, periodic texts and patterns,
+//! and Fibonacci words, which are full of repetitions. They are like the
+//! generators of this crate's own quickcheck tests, for testing a search, or
+//! a wrapper of one, against a simpler implementation.
+//!
+//! ```
+//! #[macro_use] extern crate proptest;
+//! extern crate galil_seiferas;
+//!
+//! use galil_seiferas::gs_find;
+//! use galil_seiferas::strategy::text_with_match;
+//!
+//! proptest! {
+//!     fn finds_the_match((text, pattern, i) in text_with_match(b"ab", 64)) {
+//!         // The first match is at i or before
+//!         prop_assert!(gs_find(&text, &pattern).unwrap() <= i);
+//!     }
+//! }
+//! # fn main() { finds_the_match(); }
+//! ```
+//!
+//! This module needs the `proptest` and `alloc` features.
+
+use alloc::vec::Vec;
+
+use proptest::collection::vec;
+use proptest::sample::select;
+use proptest::strategy::{Just, Strategy};
+
+/// Generate words of `0..max_len` elements of `alphabet`.
+///
+/// **Panics** if `alphabet` is empty.
+pub fn word(alphabet: &[u8], max_len: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(select(alphabet.to_vec()), 0..max_len)
+}
+
+/// Generate a text and a pattern of `alphabet`, independently; the pattern
+/// is at most half as long as the text on average.
+///
+/// With a small alphabet, the pattern often occurs in the text.
+pub fn text_and_pattern(alphabet: &[u8], max_len: usize)
+    -> impl Strategy<Value = (Vec<u8>, Vec<u8>)>
+{
+    (word(alphabet, max_len), word(alphabet, max_len / 2 + 1))
+}
+
+/// Generate a text, a pattern, and an offset `i` where the pattern occurs in
+/// the text, which is not necessarily the first match.
+///
+/// The text is made of a prefix, the pattern and a suffix, each of
+/// `0..max_len` elements of `alphabet`.
+pub fn text_with_match(alphabet: &[u8], max_len: usize)
+    -> impl Strategy<Value = (Vec<u8>, Vec<u8>, usize)>
+{
+    let parts = (word(alphabet, max_len), word(alphabet, max_len), word(alphabet, max_len));
+    parts.prop_map(|(mut text, pattern, suffix)| {
+        let i = text.len();
+        text.extend_from_slice(&pattern);
+        text.extend_from_slice(&suffix);
+        (text, pattern, i)
+    })
+}
+
+/// Generate periodic words of `alphabet`: a word of `1..max_period + 1`
+/// elements, repeated to `0..max_len` elements.
+pub fn periodic_word(alphabet: &[u8], max_period: usize, max_len: usize)
+    -> impl Strategy<Value = Vec<u8>>
+{
+    (period(alphabet, max_period), 0..max_len)
+        .prop_map(|(period, len)| period.iter().cycle().take(len).cloned().collect())
+}
+
+/// Generate a text and a pattern that share a period.
+///
+/// The period is a word of `1..max_period + 1` elements of `alphabet`, the
+/// text is a repetition of it with `0..max_len` elements, and the pattern is
+/// a repetition that starts anywhere in the period and repeats it at least
+/// three times, so that it has a cube prefix (an HRP). One element of the
+/// text is sometimes replaced, so that the text is not quite periodic.
+pub fn periodic_text_and_pattern(alphabet: &[u8], max_period: usize, max_len: usize)
+    -> impl Strategy<Value = (Vec<u8>, Vec<u8>)>
+{
+    let alphabet = alphabet.to_vec();
+    period(&alphabet, max_period).prop_flat_map(move |period| {
+        let p = period.len();
+        let replacement = (0..max_len.max(1), select(alphabet.clone()));
+        (Just(period), 0..max_len, 0..p, 3 * p..4 * p + 1, proptest::option::of(replacement))
+    }).prop_map(|(period, text_len, start, pattern_len, replacement)| {
+        let repeat = || period.iter().cycle();
+        let mut text = repeat().take(text_len).cloned().collect::<Vec<_>>();
+        if let Some((i, elt)) = replacement {
+            if i < text.len() {
+                text[i] = elt;
+            }
+        }
+        let pattern = repeat().skip(start).take(pattern_len).cloned().collect();
+        (text, pattern)
+    })
+}
+
+/// Generate the Fibonacci word *S*ₙ with `n` in `0..max_n`, sometimes
+/// followed by a shorter Fibonacci word.
+///
+/// The Fibonacci words of a and b are *S*₀ = a, *S*₁ = ab and *S*ₙ =
+/// *S*ₙ₋₁ *S*ₙ₋₂; their length grows exponentially with *n*.
+pub fn fibonacci_word(max_n: usize) -> impl Strategy<Value = Vec<u8>> {
+    (0..max_n, proptest::option::of(0..max_n / 2 + 1)).prop_map(|(n, next)| {
+        let mut word = fibonacci(n);
+        if let Some(k) = next {
+            word.extend_from_slice(&fibonacci(k));
+        }
+        word
+    })
+}
+
+/// Generate a text and a pattern that are both from `fibonacci_word`.
+pub fn fibonacci_text_and_pattern(max_n: usize)
+    -> impl Strategy<Value = (Vec<u8>, Vec<u8>)>
+{
+    (fibonacci_word(max_n), fibonacci_word(max_n))
+}
+
+/// Generate the period of `periodic_word`
+fn period(alphabet: &[u8], max_period: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(select(alphabet.to_vec()), 1..max_period + 1)
+}
+
+/// The Fibonacci word *S*ₙ
+fn fibonacci(n: usize) -> Vec<u8> {
+    let (mut previous, mut word) = (b"a".to_vec(), b"ab".to_vec());
+    if n == 0 {
+        return previous;
+    }
+    for _ in 1..n {
+        let next = [&word[..], &previous[..]].concat();
+        previous = word;
+        word = next;
+    }
+    word
+}
+
+#[test]
+fn test_strategies() {
+    use proptest::test_runner::TestRunner;
+    use gs_find;
+    use test_util::brute_force_search;
+
+    assert_eq!(fibonacci(0), b"a");
+    assert_eq!(fibonacci(2), b"aba");
+    assert_eq!(fibonacci(4), b"abaababa");
+
+    let mut runner = TestRunner::default();
+    runner.run(&text_and_pattern(b"ab", 40), |(text, pattern)| {
+        assert!(text.len() < 40 && pattern.len() <= 20);
+        assert_eq!(gs_find(&text, &pattern), brute_force_search(&text, &pattern));
+        Ok(())
+    }).unwrap();
+    runner.run(&text_with_match(b"abc", 20), |(text, pattern, i)| {
+        assert_eq!(&text[i..i + pattern.len()], &pattern[..]);
+        assert!(gs_find(&text, &pattern).unwrap() <= i);
+        Ok(())
+    }).unwrap();
+    runner.run(&periodic_word(b"ab", 4, 30), |word| {
+        assert!(word.len() < 30);
+        assert!((1..5).any(|p| (p..word.len()).all(|i| word[i] == word[i - p])));
+        Ok(())
+    }).unwrap();
+    runner.run(&periodic_text_and_pattern(b"ab", 5, 60), |(text, pattern)| {
+        assert!(pattern.len() >= 3);
+        assert_eq!(gs_find(&text, &pattern), brute_force_search(&text, &pattern));
+        Ok(())
+    }).unwrap();
+    runner.run(&fibonacci_text_and_pattern(12), |(text, pattern)| {
+        assert!(text.iter().chain(&pattern).all(|&x| x == b'a' || x == b'b'));
+        assert_eq!(gs_find(&text, &pattern), brute_force_search(&text, &pattern));
+        Ok(())
+    }).unwrap();
+}
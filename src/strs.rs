@@ -0,0 +1,110 @@
+use std::iter::FusedIterator;
+
+use analysis::analyze;
+use iter::{Matches, Options};
+#[cfg(test)]
+use GsSearchable;
+
+/// An iterator of the matches in a string, as slices of the string, created
+/// by `GsSearchable::gs_matches`.
+#[derive(Clone)]
+pub struct StrMatches<'p, 't> {
+    matches: Matches<'p, 't, u8>,
+    text: &'t str,
+}
+
+impl<'p, 't> StrMatches<'p, 't> {
+    pub(crate) fn new(text: &'t str, needle: &'p str) -> Self {
+        let needle = needle.as_bytes();
+        StrMatches {
+            matches: Matches::with_info(needle, analyze(needle), text.as_bytes(),
+                                        Options::default()),
+            text,
+        }
+    }
+}
+
+impl<'p, 't> StrMatches<'p, 't> {
+    /// Return the byte index and the slice of the next match
+    fn next_match(&mut self) -> Option<(usize, &'t str)> {
+        // A match of a nonempty needle starts and ends at char boundaries,
+        // since UTF-8 is self-synchronizing; an empty needle also matches
+        // inside chars.
+        loop {
+            let m = self.matches.next()?;
+            if let Some(matched) = self.text.get(m.range()) {
+                return Some((m.start(), matched));
+            }
+        }
+    }
+}
+
+impl<'p, 't> Iterator for StrMatches<'p, 't> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<&'t str> {
+        self.next_match().map(|(_, matched)| matched)
+    }
+}
+
+impl<'p, 't> FusedIterator for StrMatches<'p, 't> { }
+
+/// An iterator of the matches in a string with their byte and char indices,
+/// created by `GsSearchable::gs_char_match_indices`.
+///
+/// The iterator element is `(byte_index, char_index, matched)`.
+#[derive(Clone)]
+pub struct CharMatchIndices<'p, 't> {
+    matches: StrMatches<'p, 't>,
+    /// The byte index that `chars` is counted up to
+    pos: usize,
+    /// The number of chars in `text[..pos]`
+    chars: usize,
+}
+
+impl<'p, 't> CharMatchIndices<'p, 't> {
+    pub(crate) fn new(text: &'t str, needle: &'p str) -> Self {
+        CharMatchIndices { matches: StrMatches::new(text, needle), pos: 0, chars: 0 }
+    }
+}
+
+impl<'p, 't> Iterator for CharMatchIndices<'p, 't> {
+    type Item = (usize, usize, &'t str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, matched) = self.matches.next_match()?;
+        let text = self.matches.text.as_bytes();
+        // Count the first byte of each UTF-8 sequence
+        self.chars += text[self.pos..start].iter().filter(|&&byte| byte & 0xC0 != 0x80).count();
+        self.pos = start;
+        Some((start, self.chars, matched))
+    }
+}
+
+impl<'p, 't> FusedIterator for CharMatchIndices<'p, 't> { }
+
+#[test]
+fn test_gs_matches() {
+    for &(text, needle) in &[("abcabc", "bc"), ("aaaaa", "aa"), ("", "a"), ("", ""),
+                             ("å∂ƒå∂", "∂"), ("å∂ƒå∂", ""), ("x", "xy"), ("ßßß", "ßß")]
+    {
+        assert_eq!(text.gs_matches(needle).collect::<Vec<_>>(),
+                   text.matches(needle).collect::<Vec<_>>(), "{:?} {:?}", text, needle);
+    }
+    let text = String::from("one two one");
+    let matches = text.gs_matches("one").map(|m| m.as_ptr() as usize - text.as_ptr() as usize);
+    assert_eq!(matches.collect::<Vec<_>>(), [0, 8]);
+}
+
+#[test]
+fn test_gs_char_match_indices() {
+    for &(text, needle) in &[("abcabc", "bc"), ("aaaaa", "aa"), ("", ""), ("å∂ƒå∂", "∂"),
+                             ("å∂ƒå∂", ""), ("x", "xy"), ("ßaßßaß", "ßa")]
+    {
+        let expected = text.match_indices(needle)
+            .map(|(i, m)| (i, text[..i].chars().count(), m))
+            .collect::<Vec<_>>();
+        assert_eq!(text.gs_char_match_indices(needle).collect::<Vec<_>>(), expected,
+                   "{:?} {:?}", text, needle);
+    }
+}
@@ -0,0 +1,200 @@
+
+use std::cmp::min;
+use std::convert::TryInto;
+
+use {decompose_by, GS_K};
+
+/// The number of bytes compared at a time
+const WORD: usize = 8;
+
+/// Load the word at `bytes[i..i + WORD]`, with the first byte in the low
+/// bits (on any target).
+fn load(bytes: &[u8], i: usize) -> u64 {
+    debug_assert!(i + WORD <= bytes.len());
+    u64::from_le_bytes(get!(bytes, i..i + WORD).try_into().unwrap())
+}
+
+/// The first word of a pattern, and the mask of its bytes (if the pattern
+/// is shorter than a word).
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Head {
+    word: u64,
+    mask: u64,
+}
+
+impl Head {
+    fn new(pattern: &[u8]) -> Self {
+        let len = min(WORD, pattern.len());
+        let mut word = [0; WORD];
+        word[..len].copy_from_slice(&pattern[..len]);
+        Head {
+            word: u64::from_le_bytes(word),
+            mask: if len == 0 { 0 } else { !0 >> (8 * (WORD - len)) },
+        }
+    }
+}
+
+/// An element type with a specialized comparison of a text and a pattern,
+/// for `find_fast`: `u8`, here, and `u16` and `u32` (see `Wide`).
+pub(crate) trait FastCompare: Copy + Eq {
+    /// What the comparisons precompute from the pattern
+    type Head: Copy;
+
+    fn head(pattern: &[Self]) -> Self::Head;
+
+    /// Find the greatest shared prefix, starting at from, of text and
+    /// pattern; like `longest_common_prefix_from`. `head` is the head of
+    /// `pattern`.
+    fn common_prefix_from(from: usize, text: &[Self], pattern: &[Self], head: Self::Head)
+        -> usize;
+}
+
+impl FastCompare for u8 {
+    type Head = Head;
+
+    fn head(pattern: &[u8]) -> Head {
+        Head::new(pattern)
+    }
+
+    #[inline(always)]
+    fn common_prefix_from(from: usize, text: &[u8], pattern: &[u8], head: Head) -> usize {
+        common_prefix_from(from, text, pattern, head)
+    }
+}
+
+/// Find the greatest shared prefix, starting at from, of text and pattern;
+/// like `longest_common_prefix_from`, but for bytes. `head` is the head of
+/// `pattern`.
+///
+/// From the start of the pattern, the bytes are compared a word at a time
+/// with xor, and the first differing byte is found with `trailing_zeros`,
+/// without a branch per byte.
+#[inline(always)]
+fn common_prefix_from(from: usize, text: &[u8], pattern: &[u8], head: Head) -> usize {
+    debug_assert!(pattern.len() <= text.len());
+    debug_assert!(from <= pattern.len());
+    let m = pattern.len();
+    if from > 0 {
+        // Resumed in the scope of the HRP; only the end is left to compare
+        return common_prefix_bytewise(from, text, pattern);
+    }
+    // Most comparisons end at the first byte, in a predictable branch
+    if m == 0 || get!(text, 0) != get!(pattern, 0) {
+        return 0;
+    }
+    if m < WORD {
+        if text.len() < WORD {
+            return common_prefix_bytewise(1, text, pattern);
+        }
+        let diff = (load(text, 0) ^ head.word) & head.mask;
+        return if diff == 0 { m } else { first_difference(diff) };
+    }
+    let mut i = 0;
+    while i + WORD <= m {
+        let diff = load(text, i) ^ load(pattern, i);
+        if diff != 0 {
+            return i + first_difference(diff);
+        }
+        i += WORD;
+    }
+    if i < m {
+        // Compare the last word of the pattern, without the bytes before i
+        let last = m - WORD;
+        let diff = (load(text, last) ^ load(pattern, last)) >> (8 * (i - last));
+        i = if diff == 0 { m } else { i + first_difference(diff) };
+    }
+    i
+}
+
+fn common_prefix_bytewise(from: usize, text: &[u8], pattern: &[u8]) -> usize {
+    let mut i = from;
+    while i < pattern.len() && get!(text, i) == get!(pattern, i) {
+        i += 1;
+    }
+    i
+}
+
+/// The index of the first differing byte in the xor `diff` of two words.
+fn first_difference(diff: u64) -> usize {
+    debug_assert!(diff != 0);
+    diff.trailing_zeros() as usize / 8
+}
+
+/// The Galil-Seiferas string matching algorithm, specialized for bytes and
+/// wide elements: it is the same search as `find_from_by` and
+/// `search_simple`, with the comparisons in `FastCompare`.
+pub(crate) fn find_fast<T: FastCompare>(text: &[T], pattern: &[T]) -> Option<usize> {
+    let (u, v, hrp1) = decompose_by(pattern, &T::eq);
+    let m = pattern.len();
+    if text.len() < m {
+        return None;
+    }
+    let (scope_l, scope_r) = match hrp1 {
+        Some(hrp1) => (hrp1.period * 2, hrp1.len),
+        None => (0, 0),
+    };
+    let has_scope = scope_l < scope_r;
+    let head = T::head(v);
+
+    // Find each occurence of v in text[u.len()..], then check if u precedes it.
+    let text_v = &text[u.len()..];
+    let mut pos = 0;
+    let mut j = 0;
+    while pos <= text.len() - m {
+        j = T::common_prefix_from(j, get!(text_v, pos..), v, head);
+        let has_match = j == v.len();
+        let match_pos = pos;
+        if j == 0 {
+            pos += 1;
+        } else if has_scope && j >= scope_l && j <= scope_r {
+            pos += scope_l / 2;
+            j -= scope_l / 2;
+        } else {
+            pos += j / GS_K + 1;
+            j = 0;
+        }
+        if has_match && *get!(text, match_pos..match_pos + u.len()) == *u {
+            return Some(match_pos);
+        }
+    }
+    None
+}
+
+#[test]
+fn test_common_prefix_from() {
+    let text = b"abcdefghijklmnopqrstuvwxyz";
+    for len in 0..text.len() + 1 {
+        let mut pattern = text[..len].to_vec();
+        assert_eq!(common_prefix_from(0, text, &pattern, Head::new(&pattern)), len);
+        for i in 0..len {
+            pattern[i] = b'.';
+            for from in 0..i + 1 {
+                assert_eq!(common_prefix_from(from, text, &pattern, Head::new(&pattern)), i);
+            }
+            assert_eq!(common_prefix_from(i + 1, text, &pattern, Head::new(&pattern)), len);
+            pattern[i] = text[i];
+        }
+    }
+    let head = Head::new(b"abd");
+    assert_eq!(common_prefix_from(0, b"abc", b"abd", head), 2);
+    assert_eq!(common_prefix_from(0, b"abdabc", b"abd", head), 3);
+    assert_eq!(common_prefix_from(0, b"abcdefghij", b"abd", head), 2);
+}
+
+#[test]
+fn test_find_fast() {
+    let text = b"abaababaabaababaababaabaababaabaab.abaababaabaababaababaabaababaabaab";
+    let brute_force = |text: &[u8], pattern: &[u8]| {
+        (0..text.len() + 1).find(|&i| text[i..].starts_with(pattern))
+    };
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 30) {
+            let pattern = &text[start..end];
+            for skip in 0..3 {
+                let text = &text[skip..];
+                assert_eq!(find_fast(text, pattern), brute_force(text, pattern),
+                           "{}", ::Bytestring(pattern));
+            }
+        }
+    }
+}
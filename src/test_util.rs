@@ -2,6 +2,9 @@
 #[cfg(test)]
 use std::fmt;
 
+#[cfg(feature = "test-functions")]
+use alloc::vec::Vec;
+
 #[cfg(test)]
 pub(crate) struct Bytestring<'a, T: 'a>(pub &'a [T]);
 
@@ -46,6 +49,104 @@ pub fn brute_force_search<T: Eq>(text: &[T], pattern: &[T]) -> Option<usize> {
 }
 
 
+/// Construct a text of length `len` that makes the Galil-Seiferas search for
+/// `pattern` compare as much as possible.
+///
+/// The text is chosen by an adversary that follows the search, and decides
+/// each text element when it is first compared: it extends each partial
+/// match of the pattern until its last element, and then makes it fail. The
+/// search repeatedly meets long partial matches, including ones in the
+/// scope of the HRP of the pattern. The elements are chosen from the
+/// pattern.
+#[cfg(feature = "test-functions")]
+pub fn worst_case_text_for<T: Eq + Clone>(pattern: &[T], len: usize) -> Vec<T> {
+    use decompose_by;
+    use GS_K;
+
+    let m = pattern.len();
+    let (u, v, hrp1) = decompose_by(pattern, &T::eq);
+    if m == 0 || len < m {
+        return pattern.iter().cycle().take(len).cloned().collect();
+    }
+    let mut text: Vec<Option<T>> = ::alloc::vec![None; len];
+    let (scope_l, scope_r) = match hrp1 {
+        Some(hrp1) => (hrp1.period * 2, hrp1.len),
+        None => (0, 0),
+    };
+    let has_scope = scope_l < scope_r;
+    // The search in search_simple, for v in text[u.len()..]
+    let mut pos = 0;
+    let mut j = 0;
+    while pos <= len - m {
+        while j < v.len() {
+            let elt = text[u.len() + pos + j].get_or_insert_with(|| {
+                if j + 1 < v.len() {
+                    v[j].clone()
+                } else {
+                    pattern.iter().find(|&x| *x != v[j]).unwrap_or(&v[j]).clone()
+                }
+            });
+            if *elt != v[j] {
+                break;
+            }
+            j += 1;
+        }
+        if has_scope && j >= scope_l && j <= scope_r {
+            pos += scope_l / 2;
+            j -= scope_l / 2;
+        } else {
+            pos += j / GS_K + 1;
+            j = 0;
+        }
+    }
+    text.into_iter().enumerate().map(|(i, elt)| {
+        elt.unwrap_or_else(|| pattern[i % m].clone())
+    }).collect()
+}
+
+/// Construct a de Bruijn sequence of the given `order` over `alphabet`, as
+/// a text where each word of length `order` over the alphabet occurs exactly
+/// once.
+///
+/// The alphabet should have distinct elements. For an alphabet of *k*
+/// elements, the text has length *k*<sup>order</sup> + order - 1; it is the
+/// cyclic sequence that concatenates the Lyndon words whose length divides
+/// `order`, in lexicographic order (Fredricksen, Kessler and Maiorana),
+/// followed by its first order - 1 elements. It is empty if the alphabet is
+/// empty or if the order is zero.
+#[cfg(feature = "test-functions")]
+// is_multiple_of needs Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+pub fn de_bruijn_sequence<T: Clone>(alphabet: &[T], order: usize) -> Vec<T> {
+    let k = alphabet.len();
+    let mut sequence = Vec::new();
+    if k == 0 || order == 0 {
+        return sequence;
+    }
+    // Generate the Lyndon words of length at most `order`, as indices into
+    // the alphabet, in lexicographic order
+    let mut word = ::alloc::vec![0];
+    loop {
+        if order % word.len() == 0 {
+            sequence.extend(word.iter().map(|&i| alphabet[i].clone()));
+        }
+        let len = word.len();
+        while word.len() < order {
+            let elt = word[word.len() - len];
+            word.push(elt);
+        }
+        while word.last() == Some(&(k - 1)) {
+            word.pop();
+        }
+        match word.last_mut() {
+            Some(last) => *last += 1,
+            None => break,
+        }
+    }
+    let wrap = sequence.iter().cycle().take(order - 1).cloned().collect::<Vec<_>>();
+    sequence.extend(wrap);
+    sequence
+}
 
 #[test]
 fn test_brute_force_search() {
@@ -54,3 +155,52 @@ fn test_brute_force_search() {
     assert_eq!(brute_force_search(b"ab", b"abc"), None);
 }
 
+
+#[test]
+#[cfg(feature = "test-functions")]
+fn test_worst_case_text_for() {
+    use std::cell::Cell;
+    use gs_find_by;
+
+    fn comparisons(text: &[u8], pattern: &[u8]) -> usize {
+        let count = Cell::new(0);
+        gs_find_by(text, pattern, |a, b| { count.set(count.get() + 1); a == b });
+        count.get()
+    }
+
+    let n = 10_000;
+    for pattern in &[&b"ab"[..], b"aab", b"aaaab", b"abaab", b"abaababaab", b"aaabaaabaaab"] {
+        let text = worst_case_text_for(pattern, n);
+        assert_eq!(text.len(), n);
+        assert_eq!(brute_force_search(&text, pattern), None);
+        // At least two comparisons per shift, and within the linear bound
+        let worst = comparisons(&text, pattern);
+        assert!(worst >= 2 * (n - pattern.len()), "{}: {}", Bytestring(pattern), worst);
+        assert!(worst <= 5 * n, "{}: {}", Bytestring(pattern), worst);
+    }
+    assert_eq!(worst_case_text_for(b"ab", 1), b"a");
+    assert_eq!(worst_case_text_for(b"aa", 3), b"aaa");
+}
+
+#[test]
+#[cfg(feature = "test-functions")]
+fn test_de_bruijn_sequence() {
+    assert_eq!(de_bruijn_sequence(b"ab", 3), b"aaababbbaa");
+    assert_eq!(de_bruijn_sequence(b"abc", 1), b"abc");
+    assert_eq!(de_bruijn_sequence(b"a", 4), b"aaaa");
+    assert!(de_bruijn_sequence(b"ab", 0).is_empty());
+    assert!(de_bruijn_sequence::<u8>(b"", 3).is_empty());
+
+    for k in 1..5usize {
+        let alphabet = (0..k as u8).collect::<Vec<_>>();
+        for order in 1..6 {
+            let text = de_bruijn_sequence(&alphabet, order);
+            let words = k.pow(order as u32);
+            assert_eq!(text.len(), words + order - 1);
+            let mut factors = text.windows(order).collect::<Vec<_>>();
+            factors.sort();
+            factors.dedup();
+            assert_eq!(factors.len(), words);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+
+use std::ops::Sub;
+
+/// Find the first position where every element of `pattern` is within
+/// `tolerance` of the corresponding element of `text`, that is, where
+/// `|text[i + j] - pattern[j]| <= tolerance` for all `j`.
+///
+/// Approximate equality is not an equivalence relation (it is not
+/// transitive), so it must not be used with `gs_find_by`: the
+/// Galil-Seiferas algorithm would then skip over matches. This function
+/// instead verifies the pattern at each position of the text, using
+/// **O(n m)** time in the worst case and **O(1)** space. The comparison at
+/// each position stops at the first element that is out of tolerance.
+///
+/// Elements that are not comparable, like a floating point NaN, are never
+/// within tolerance.
+///
+/// ```
+/// use galil_seiferas::find_with_tolerance;
+///
+/// let telemetry = [0.1, 5.02, 6.97, 5.1, 4.99, 7.03];
+/// assert_eq!(find_with_tolerance(&telemetry, &[5.0, 7.0], 0.05), Some(1));
+/// assert_eq!(find_with_tolerance(&telemetry, &[5.0, 5.0], 0.1), Some(3));
+/// ```
+pub fn find_with_tolerance<T>(text: &[T], pattern: &[T], tolerance: T) -> Option<usize>
+    where T: Copy + PartialOrd + Sub<Output = T>,
+{
+    let within = |a: &T, b: &T| {
+        let distance = if *a > *b { *a - *b } else { *b - *a };
+        distance <= tolerance
+    };
+    let m = pattern.len();
+    if text.len() < m {
+        return None;
+    }
+    (0..text.len() - m + 1).find(|&i| {
+        text[i..i + m].iter().zip(pattern).all(|(a, b)| within(a, b))
+    })
+}
+
+#[test]
+fn test_find_with_tolerance() {
+    // With tolerance 1, 2 ≈ 3 and 3 ≈ 4 but 2 ≉ 4
+    let text = [3u32, 2, 4, 4, 5, 9];
+    assert_eq!(find_with_tolerance(&text, &[2, 4, 5], 1), Some(1));
+    assert_eq!(find_with_tolerance(&text, &[3, 3, 3], 1), Some(0));
+    assert_eq!(find_with_tolerance(&text, &[4, 3, 5, 10], 1), Some(2));
+    assert_eq!(find_with_tolerance(&text, &[4, 4, 4, 4], 0), None);
+    assert_eq!(find_with_tolerance(&text, &[], 0), Some(0));
+    assert_eq!(find_with_tolerance(&text[..2], &[3, 2, 4], 10), None);
+
+    let text = [1.0, f64::NAN, 2.0, 2.5];
+    assert_eq!(find_with_tolerance(&text, &[1.0, 2.0], 1.0), Some(2));
+    assert_eq!(find_with_tolerance(&text, &[f64::NAN], 1.0), None);
+}
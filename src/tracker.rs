@@ -0,0 +1,180 @@
+
+use std::iter::FromIterator;
+
+use alloc::vec::Vec;
+
+use {Hrp, GS_K};
+
+/// The smallest period and the cube prefix of a buffer that grows at the
+/// end, maintained as elements are appended.
+///
+/// Each `push` takes amortized **O(1)** time, like a step of the
+/// Knuth-Morris-Pratt preprocessing; the tracker stores the elements and
+/// their borders.
+///
+/// ```
+/// use galil_seiferas::PeriodTracker;
+///
+/// let mut tracker = PeriodTracker::new();
+/// for &state in &[3, 1, 4, 3, 1, 4, 3, 1] {
+///     tracker.push(state);
+/// }
+/// // The states are in a loop of length 3
+/// assert_eq!(tracker.period(), 3);
+/// assert!(tracker.is_periodic());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PeriodTracker<T> {
+    elements: Vec<T>,
+    /// The length of the longest border of each prefix `elements[..i + 1]`
+    borders: Vec<usize>,
+    hrp: Option<Hrp>,
+}
+
+impl<T> PeriodTracker<T> {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        PeriodTracker { elements: Vec::new(), borders: Vec::new(), hrp: None }
+    }
+
+    /// The elements that were appended
+    pub fn as_slice(&self) -> &[T] {
+        &self.elements
+    }
+
+    /// The number of elements
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Return `true` if the tracker is empty
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// The smallest period of the elements, or zero if it is empty.
+    ///
+    /// This is the smallest *p* > 0 such that `x[i] == x[i + p]` for all
+    /// valid *i*, where x is the elements; it is equal to the length if there
+    /// is no shorter period.
+    pub fn period(&self) -> usize {
+        match self.borders.last() {
+            Some(&border) => self.len() - border,
+            None => 0,
+        }
+    }
+
+    /// Return `true` if the elements are periodic, which means that their
+    /// smallest period fits at least twice.
+    pub fn is_periodic(&self) -> bool {
+        !self.is_empty() && self.period() * 2 <= self.len()
+    }
+
+    /// The first k-HRP of the elements, with *k* = 3, if it exists.
+    ///
+    /// Its period is the period of the shortest cube prefix (see
+    /// `cube_prefix`), and its length is the length of the longest prefix
+    /// with that period. Once it exists, it stays, and it grows while the
+    /// appended elements continue its period.
+    pub fn hrp(&self) -> Option<Hrp> {
+        self.hrp
+    }
+
+    /// Remove all the elements.
+    pub fn clear(&mut self) {
+        self.elements.clear();
+        self.borders.clear();
+        self.hrp = None;
+    }
+}
+
+impl<T: Eq> PeriodTracker<T> {
+    /// Append `element` and update the period and the HRP.
+    pub fn push(&mut self, element: T) {
+        let i = self.elements.len();
+        // The longest border of elements[..i + 1] extends a border of
+        // elements[..i]
+        let mut border = if i == 0 { 0 } else { self.borders[i - 1] };
+        let border = loop {
+            if i > 0 && self.elements[border] == element {
+                break border + 1;
+            }
+            if border == 0 {
+                break 0;
+            }
+            border = self.borders[border - 1];
+        };
+        self.hrp = match self.hrp {
+            Some(hrp) if hrp.len == i && self.elements[i - hrp.period] == element => {
+                Some(Hrp { period: hrp.period, len: i + 1 })
+            }
+            Some(hrp) => Some(hrp),
+            None => {
+                // The first prefix that is at least a cube of its period is
+                // the shortest cube prefix
+                let period = i + 1 - border;
+                if i + 1 >= GS_K * period {
+                    Some(Hrp { period, len: i + 1 })
+                } else {
+                    None
+                }
+            }
+        };
+        self.elements.push(element);
+        self.borders.push(border);
+    }
+}
+
+impl<T> Default for PeriodTracker<T> {
+    fn default() -> Self {
+        PeriodTracker::new()
+    }
+}
+
+impl<T: Eq> Extend<T> for PeriodTracker<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<T: Eq> FromIterator<T> for PeriodTracker<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tracker = PeriodTracker::new();
+        tracker.extend(iter);
+        tracker
+    }
+}
+
+#[test]
+fn test_period_tracker() {
+    use analysis::smallest_period_by;
+    use verify::find_k_hrp;
+
+    let tracker = PeriodTracker::<u8>::new();
+    assert_eq!(tracker.period(), 0);
+    assert!(!tracker.is_periodic());
+    assert_eq!(tracker.hrp(), None);
+
+    // Every prefix of every ternary word of length 9
+    for n in 0..3u32.pow(9) {
+        let x = (0..9).map(|i| n / 3u32.pow(i) % 3).collect::<Vec<_>>();
+        let mut tracker = PeriodTracker::new();
+        for (i, &elt) in x.iter().enumerate() {
+            tracker.push(elt);
+            let prefix = &x[..i + 1];
+            assert_eq!(tracker.as_slice(), prefix);
+            assert_eq!(tracker.period(), smallest_period_by(prefix, &u32::eq), "{:?}", prefix);
+            assert_eq!(tracker.hrp(), find_k_hrp(GS_K, 1, prefix, &u32::eq), "{:?}", prefix);
+        }
+    }
+
+    let mut tracker = "abaabaabaab".bytes().collect::<PeriodTracker<_>>();
+    assert_eq!(tracker.hrp(), Some(Hrp { period: 3, len: 11 }));
+    tracker.extend(b"b".iter().cloned());
+    assert_eq!(tracker.period(), 12);
+    assert_eq!(tracker.hrp(), Some(Hrp { period: 3, len: 11 }));
+    tracker.clear();
+    assert!(tracker.is_empty());
+}
@@ -0,0 +1,213 @@
+use std::cmp::min;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use alloc::vec::Vec;
+
+use analysis::PatternInfo;
+use finder::Finder;
+use SearchState;
+
+/// The number of transformed elements that are produced at a time
+const CHUNK: usize = 4096;
+
+/// A view of a slice with a transform applied to it, that is searched
+/// without making a transformed copy of the slice.
+///
+/// The transform is a function that is called with the rest of the slice;
+/// it pushes the transformed elements of one step to the vector, and
+/// returns the number of elements of the slice that it consumed, which must
+/// be at least one. A step can push any number of elements, so the
+/// transform can change the length, like removing hyphenation or unescaping.
+///
+/// The transformed elements are produced a chunk at a time while searching,
+/// so the memory use is independent of the length of the slice. Each match
+/// is reported as a range in the original slice: from the start of the step
+/// that produced its first element, to the end of the step that produced its
+/// last element.
+///
+/// ```
+/// use galil_seiferas::TransformedHaystack;
+///
+/// // Ignore the line breaks after hyphens, and case
+/// let text = b"The Galil-Sei-\nferas algo-\nrithm";
+/// let haystack = TransformedHaystack::new(text, |rest: &[u8], out: &mut Vec<u8>| {
+///     if rest.starts_with(b"-\n") {
+///         return 2;
+///     }
+///     out.push(rest[0].to_ascii_lowercase());
+///     1
+/// });
+/// let found = haystack.find_iter(b"seiferas").collect::<Vec<_>>();
+/// assert_eq!(found, [10..20]);
+/// ```
+pub struct TransformedHaystack<'a, T: 'a, F> {
+    text: &'a [T],
+    transform: F,
+}
+
+impl<'a, T, F> TransformedHaystack<'a, T, F> {
+    /// Create a view of `text` with `transform` applied to it.
+    pub fn new(text: &'a [T], transform: F) -> Self {
+        TransformedHaystack { text, transform }
+    }
+
+    /// Return an iterator of the ranges in the slice of the non-overlapping
+    /// matches of `pattern` in the transformed slice.
+    ///
+    /// This computes in **O(n)** time, not counting the transform, where n
+    /// is the length of the transformed slice.
+    pub fn find_iter<'p, U>(self, pattern: &'p [U]) -> TransformedMatches<'a, 'p, T, U, F>
+        where U: Eq,
+              F: FnMut(&[T], &mut Vec<U>) -> usize,
+    {
+        let finder = Finder::new(pattern);
+        let info = finder.info();
+        TransformedMatches {
+            text: self.text,
+            transform: self.transform,
+            pos: 0,
+            finder,
+            info,
+            state: SearchState::default(),
+            window: Vec::new(),
+            spans: Vec::new(),
+        }
+    }
+}
+
+/// An iterator of the matches in a `TransformedHaystack`, created by
+/// `TransformedHaystack::find_iter`.
+pub struct TransformedMatches<'a, 'p, T: 'a, U: 'p, F> {
+    text: &'a [T],
+    transform: F,
+    /// The number of elements of `text` that are transformed
+    pos: usize,
+    finder: Finder<'p, U>,
+    info: PatternInfo,
+    state: SearchState,
+    /// The transformed elements that can still be a part of a match
+    window: Vec<U>,
+    /// The range in `text` of the step that produced each element of
+    /// `window`
+    spans: Vec<Range<usize>>,
+}
+
+impl<'a, 'p, T, U, F> TransformedMatches<'a, 'p, T, U, F>
+    where U: Eq,
+          F: FnMut(&[T], &mut Vec<U>) -> usize,
+{
+    /// Drop the elements before the next candidate match from the window,
+    /// and transform the next chunk of the text.
+    fn refill(&mut self) {
+        let drop = min(self.state.pos, self.window.len());
+        self.window.drain(..drop);
+        self.spans.drain(..drop);
+        self.state.pos -= drop;
+
+        let target = self.window.len() + CHUNK;
+        while self.window.len() < target && self.pos < self.text.len() {
+            let rest = &self.text[self.pos..];
+            let produced = self.window.len();
+            let consumed = (self.transform)(rest, &mut self.window);
+            assert!(consumed >= 1 && consumed <= rest.len(),
+                    "TransformedHaystack: the transform consumed {} of {} elements",
+                    consumed, rest.len());
+            let span = self.pos..self.pos + consumed;
+            self.spans.extend((produced..self.window.len()).map(|_| span.clone()));
+            self.pos += consumed;
+        }
+    }
+
+    /// The range in the text of the match at `start` in the window
+    fn original_range(&self, start: usize) -> Range<usize> {
+        let m = self.info.len();
+        if m == 0 {
+            let pos = self.spans.get(start).map_or(self.pos, |span| span.start);
+            return pos..pos;
+        }
+        self.spans[start].start..self.spans[start + m - 1].end
+    }
+}
+
+impl<'a, 'p, T, U, F> Iterator for TransformedMatches<'a, 'p, T, U, F>
+    where U: Eq,
+          F: FnMut(&[T], &mut Vec<U>) -> usize,
+{
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        let m = self.info.len();
+        loop {
+            // Search when the window extends past the next candidate match,
+            // so that a match is not reported before its last element is
+            // known (for an empty pattern, not at the end of the window).
+            let done = self.pos == self.text.len();
+            if done || self.window.len() > self.state.pos + m {
+                let pattern = self.finder.as_pattern(&self.info);
+                if let Some(start) = pattern.search_from(&self.window, &mut self.state) {
+                    if self.state.pos < start + m {
+                        self.state.restart(start + m);
+                    }
+                    return Some(self.original_range(start));
+                }
+                if done {
+                    return None;
+                }
+            }
+            self.refill();
+        }
+    }
+}
+
+impl<'a, 'p, T, U, F> FusedIterator for TransformedMatches<'a, 'p, T, U, F>
+    where U: Eq,
+          F: FnMut(&[T], &mut Vec<U>) -> usize,
+{ }
+
+#[test]
+fn test_transformed_haystack() {
+    // Unescape \n and \\, and compare with searching an unescaped copy
+    let unescape = |rest: &[u8], out: &mut Vec<u8>| {
+        match rest {
+            [b'\\', b'n', ..] => { out.push(b'\n'); 2 }
+            [b'\\', b'\\', ..] => { out.push(b'\\'); 2 }
+            _ => { out.push(rest[0]); 1 }
+        }
+    };
+    let line = b"ab\\nab\\\\nab\\n\\\\".repeat(CHUNK / 5);
+    let mut unescaped = Vec::new();
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while pos < line.len() {
+        let produced = unescaped.len();
+        let consumed = unescape(&line[pos..], &mut unescaped);
+        spans.extend((produced..unescaped.len()).map(|_| pos..pos + consumed));
+        pos += consumed;
+    }
+    for pattern in &[&b"ab\n"[..], b"\\nab", b"b\nab\\", b"", b"\n\\ab\nab\\", b"x"] {
+        let found = TransformedHaystack::new(&line, unescape).find_iter(pattern)
+                                                              .collect::<Vec<_>>();
+        if pattern.is_empty() {
+            assert_eq!(found.len(), unescaped.len() + 1);
+            assert!(found.iter().all(|range| range.start == range.end));
+            continue;
+        }
+        let expected = Finder::new(pattern).find_iter(&unescaped)
+            .map(|m| spans[m.start()].start..spans[m.end() - 1].end)
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty() || pattern == b"x");
+        assert_eq!(found, expected, "{}", ::Bytestring(pattern));
+    }
+
+    // A transform that removes everything
+    let mut none = TransformedHaystack::new(b"abc", |_: &[u8], _: &mut Vec<u8>| 1).find_iter(b"");
+    assert_eq!(none.next(), Some(3..3));
+    assert_eq!(none.next(), None);
+}
+
+#[test]
+#[should_panic(expected = "consumed 0 of 3")]
+fn test_transformed_haystack_no_progress() {
+    TransformedHaystack::new(b"abc", |_: &[u8], _: &mut Vec<u8>| 0).find_iter(b"a").next();
+}
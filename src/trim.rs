@@ -0,0 +1,91 @@
+use verify::ends_at;
+use {text_has_prefix, Needle};
+
+/// Remove all the leading occurrences of `pattern` from `text`, like
+/// `str::trim_start_matches`.
+///
+/// An empty pattern removes nothing. This computes in **O(n)** time.
+///
+/// ```
+/// use galil_seiferas::trim_start_matches;
+///
+/// assert_eq!(trim_start_matches(b"\r\n\r\nbody\r\n", b"\r\n"), b"body\r\n");
+/// ```
+pub fn trim_start_matches<T: Eq, N: Needle<T>>(text: &[T], pattern: N) -> &[T] {
+    let pattern = pattern.as_needle();
+    let m = pattern.len();
+    let mut text = text;
+    while m > 0 && text.len() >= m && text_has_prefix(text, pattern, &T::eq) {
+        text = &text[m..];
+    }
+    text
+}
+
+/// Remove all the trailing occurrences of `pattern` from `text`, like
+/// `str::trim_end_matches`.
+///
+/// An empty pattern removes nothing. This computes in **O(n)** time.
+///
+/// ```
+/// use galil_seiferas::trim_end_matches;
+///
+/// assert_eq!(trim_end_matches(b"\r\nbody\r\n\r\n", b"\r\n"), b"\r\nbody");
+/// ```
+pub fn trim_end_matches<T: Eq, N: Needle<T>>(text: &[T], pattern: N) -> &[T] {
+    let pattern = pattern.as_needle();
+    let m = pattern.len();
+    let mut text = text;
+    while m > 0 && ends_at(text, pattern, text.len()) {
+        text = &text[..text.len() - m];
+    }
+    text
+}
+
+/// Remove all the leading and trailing occurrences of `pattern` from
+/// `text`, like `str::trim_matches`.
+///
+/// The leading occurrences are removed first, so that an occurrence is not
+/// removed twice where they meet.
+///
+/// ```
+/// use galil_seiferas::trim_matches;
+///
+/// assert_eq!(trim_matches(&[0, 0, 7, 0, 0, 0], &[0, 0]), &[7, 0]);
+/// assert_eq!(trim_matches(b"ababa", b"ab"), b"a");
+/// ```
+pub fn trim_matches<T: Eq, N: Needle<T>>(text: &[T], pattern: N) -> &[T] {
+    let pattern = pattern.as_needle();
+    trim_end_matches(trim_start_matches(text, pattern), pattern)
+}
+
+#[test]
+fn test_trim_matches() {
+    let text = &b"ababxabab"[..];
+    assert_eq!(trim_start_matches(text, b"ab"), b"xabab");
+    assert_eq!(trim_end_matches(text, b"ab"), b"ababx");
+    assert_eq!(trim_matches(text, b"ab"), b"x");
+    assert_eq!(trim_matches(text, b"ba"), text);
+    assert_eq!(trim_matches(text, b""), text);
+    assert_eq!(trim_matches(b"abab", b"ab"), b"");
+    assert_eq!(trim_matches(b"a", b"ab"), b"a");
+    assert_eq!(trim_matches(b"", b"ab"), b"");
+
+    // The same as str, for all binary texts
+    for len in 0..10 {
+        for bits in 0..1u32 << len {
+            let text = (0..len).map(|i| if bits >> i & 1 == 1 { 'b' } else { 'a' })
+                               .collect::<String>();
+            for pattern in &["a", "ab", "aba", "bb"] {
+                let text = text.as_str();
+                let (bytes, pattern_bytes) = (text.as_bytes(), pattern.as_bytes());
+                assert_eq!(trim_start_matches(bytes, pattern_bytes),
+                           text.trim_start_matches(pattern).as_bytes());
+                assert_eq!(trim_end_matches(bytes, pattern_bytes),
+                           text.trim_end_matches(pattern).as_bytes());
+                assert_eq!(trim_matches(bytes, pattern_bytes),
+                           text.trim_start_matches(pattern).trim_end_matches(pattern)
+                               .as_bytes());
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+
+use std::cmp::{max, Ordering};
+
+use engine::SearchEngine;
+use SearchState;
+
+/// The Two-Way string matching algorithm, by Crochemore and Perrin.
+///
+/// Like Galil-Seiferas, it is a linear time, constant space algorithm, but
+/// its preprocessing computes a *critical factorization* of the pattern,
+/// which needs an ordering of the elements: `T: Ord`.
+///
+/// It compares the right part of the pattern from left to right, and then
+/// the left part from right to left; the shifts after a mismatch in the right
+/// part are often longer than Galil-Seiferas's.
+///
+/// ```
+/// use galil_seiferas::{SearchEngine, TwoWay};
+///
+/// let engine = TwoWay::new(b"needle");
+/// assert_eq!(engine.find(b"a needle in a haystack"), Some(2));
+/// ```
+#[derive(Debug)]
+pub struct TwoWay<'a, T: 'a> {
+    pattern: &'a [T],
+    info: TwoWayInfo,
+}
+
+/// The preprocessing of the Two-Way algorithm
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TwoWayInfo {
+    /// The critical position: the start of the right part of the pattern
+    critical: usize,
+    /// The shift after a match of the right part
+    shift: usize,
+    /// True if the left part of the pattern repeats at `shift`, so that it
+    /// can be remembered as matched after a shift
+    memory: bool,
+}
+
+impl<'a, T> Copy for TwoWay<'a, T> { }
+impl<'a, T> Clone for TwoWay<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: Ord> TwoWay<'a, T> {
+    /// Preprocess `pattern` for searching.
+    pub fn new(pattern: &'a [T]) -> Self {
+        TwoWay { pattern, info: two_way_info(pattern) }
+    }
+
+    /// The critical position of the pattern, where it is split into a left
+    /// and a right part.
+    pub fn critical_position(&self) -> usize {
+        self.info.critical
+    }
+}
+
+impl<'a, T: Ord> SearchEngine<T> for TwoWay<'a, T> {
+    fn pattern(&self) -> &[T] {
+        self.pattern
+    }
+
+    fn find_at(&self, text: &[T], start: usize) -> Option<usize> {
+        two_way_find_at(&self.info, self.pattern, text, start)
+    }
+}
+
+/// Compute the lexicographically maximal suffix of `x`; return its start and
+/// its period.
+///
+/// If `reversed_order` is true, the order of the elements is reversed (but
+/// a proper prefix of a suffix is still less than the suffix). The later of
+/// the two maximal suffixes is a critical factorization of `x`, like in
+/// `TwoWay`. It can also be used for finding the maximal rotation of a word.
+///
+/// The computation takes **O(m)** time. For the empty `x`, the maximal
+/// suffix is empty, and its period is 1.
+///
+/// ```
+/// use galil_seiferas::maximal_suffix;
+///
+/// assert_eq!(maximal_suffix(b"banana", false), (2, 2)); // "nana"
+/// assert_eq!(maximal_suffix(b"banana", true), (1, 2)); // "anana"
+/// ```
+pub fn maximal_suffix<T: Ord>(x: &[T], reversed_order: bool) -> (usize, usize) {
+    if reversed_order {
+        maximal_suffix_by(x, |a, b| b.cmp(a))
+    } else {
+        maximal_suffix_by(x, T::cmp)
+    }
+}
+
+/// Compute the maximal suffix of `x` with respect to the ordering `cmp`;
+/// return its start and its period.
+fn maximal_suffix_by<T, F>(x: &[T], cmp: F) -> (usize, usize)
+    where F: Fn(&T, &T) -> Ordering,
+{
+    let mut suffix = MaxSuffix::new();
+    suffix.extend(x, x.len(), cmp);
+    (suffix.start, suffix.period)
+}
+
+/// The state of an incremental computation of the maximal suffix of a
+/// prefix of a string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MaxSuffix {
+    /// The start of the maximal suffix
+    pub(crate) start: usize,
+    /// The start of the suffix that is compared with it, at offset `k`;
+    /// the prefix that is computed for is `candidate + k` long.
+    pub(crate) candidate: usize,
+    pub(crate) k: usize,
+    /// The period of the maximal suffix
+    pub(crate) period: usize,
+}
+
+impl MaxSuffix {
+    /// The state for the empty prefix
+    pub(crate) fn new() -> Self {
+        MaxSuffix { start: 0, candidate: 1, k: 0, period: 1 }
+    }
+
+    /// Extend the computation to the prefix `x[..len]`, which must not be
+    /// shorter than the current prefix.
+    pub(crate) fn extend<T, F>(&mut self, x: &[T], len: usize, cmp: F)
+        where F: Fn(&T, &T) -> Ordering,
+    {
+        while self.candidate + self.k < len {
+            match cmp(&x[self.candidate + self.k], &x[self.start + self.k]) {
+                Ordering::Less => {
+                    self.candidate += self.k + 1;
+                    self.k = 0;
+                    self.period = self.candidate - self.start;
+                }
+                Ordering::Equal => {
+                    if self.k + 1 == self.period {
+                        self.candidate += self.period;
+                        self.k = 0;
+                    } else {
+                        self.k += 1;
+                    }
+                }
+                Ordering::Greater => {
+                    self.start = self.candidate;
+                    self.candidate += 1;
+                    self.k = 0;
+                    self.period = 1;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn two_way_info<T: Ord>(pattern: &[T]) -> TwoWayInfo {
+    let m = pattern.len();
+    if m == 0 {
+        return TwoWayInfo { critical: 0, shift: 1, memory: false };
+    }
+    // The later of the two maximal suffixes is a critical factorization
+    let (start1, period1) = maximal_suffix(pattern, false);
+    let (start2, period2) = maximal_suffix(pattern, true);
+    let (critical, period) = if start1 >= start2 {
+        (start1, period1)
+    } else {
+        (start2, period2)
+    };
+    if pattern[..critical] == pattern[period..period + critical] {
+        TwoWayInfo { critical, shift: period, memory: true }
+    } else {
+        TwoWayInfo { critical, shift: max(critical, m - critical) + 1, memory: false }
+    }
+}
+
+/// Find the first match of `pattern` in `text` that starts at or after
+/// `start`, using the preprocessing `info` of the pattern.
+pub(crate) fn two_way_find_at<T: Eq>(info: &TwoWayInfo, pattern: &[T], text: &[T],
+                                     start: usize) -> Option<usize>
+{
+    two_way_find_with(info, pattern, text, start, Some)
+}
+
+/// Like `two_way_find_at`, but start from `state`, where `state.j` is the
+/// length of the prefix of the pattern that is known to match at
+/// `state.pos`. The state is updated so that the search resumes after the
+/// match that is returned, with what the match tells about the text
+/// remembered; finding all the matches, including the overlapping ones,
+/// is a single linear time pass over the text.
+pub(crate) fn two_way_find_from<T: Eq>(info: &TwoWayInfo, pattern: &[T], text: &[T],
+                                       state: &mut SearchState) -> Option<usize>
+{
+    two_way_search(info, pattern, text, &mut state.pos, &mut state.j, Some)
+}
+
+/// Like `two_way_find_at`, but use `prefilter` to skip ahead when no part of
+/// the pattern is remembered as matched: `prefilter(pos)` must return a
+/// position `>= pos` such that no match starts in between, or `None` if no
+/// match starts at or after `pos`.
+pub(crate) fn two_way_find_with<T, F>(info: &TwoWayInfo, pattern: &[T], text: &[T],
+                                      start: usize, prefilter: F) -> Option<usize>
+    where T: Eq,
+          F: FnMut(usize) -> Option<usize>,
+{
+    let mut pos = start;
+    // The length of the prefix of the pattern that is known to match
+    let mut memory = 0;
+    two_way_search(info, pattern, text, &mut pos, &mut memory, prefilter)
+}
+
+/// The Two-Way search, from `pos` with the prefix `memory` of the pattern
+/// known to match there; after a match, they are left at the next position
+/// to search.
+fn two_way_search<T, F>(info: &TwoWayInfo, pattern: &[T], text: &[T],
+                        pos: &mut usize, memory: &mut usize, mut prefilter: F) -> Option<usize>
+    where T: Eq,
+          F: FnMut(usize) -> Option<usize>,
+{
+    let (n, m) = (text.len(), pattern.len());
+    if n < m || *pos > n - m {
+        return None;
+    }
+    let critical = info.critical;
+    while *pos <= n - m {
+        if *memory == 0 {
+            *pos = prefilter(*pos)?;
+            if *pos > n - m {
+                return None;
+            }
+        }
+        // Match the right part, from left to right
+        let mut i = max(critical, *memory);
+        while i < m && pattern[i] == text[*pos + i] {
+            i += 1;
+        }
+        if i < m {
+            *pos += i - critical + 1;
+            *memory = 0;
+            continue;
+        }
+        // Match the left part, from right to left
+        let mut i = critical;
+        while i > *memory && pattern[i - 1] == text[*pos + i - 1] {
+            i -= 1;
+        }
+        let found = if i <= *memory { Some(*pos) } else { None };
+        // After a match too, the shifted pattern matches as far as it is
+        // remembered
+        *pos += info.shift;
+        *memory = if info.memory { m - info.shift } else { 0 };
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+#[test]
+fn test_maximal_suffix() {
+    assert_eq!(maximal_suffix(b"banana", false), (2, 2));
+    assert_eq!(maximal_suffix(b"banana", true), (1, 2));
+    assert_eq!(maximal_suffix(b"aaa", false), (0, 1));
+    assert_eq!(maximal_suffix(b"abab", false), (1, 2));
+    assert_eq!(maximal_suffix::<u8>(b"", false), (0, 1));
+
+    // The greatest suffix and its smallest period, for all ternary strings
+    // of length < 9
+    for len in 1..9 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| n / 3u32.pow(i) % 3).collect::<Vec<_>>();
+            for &reversed_order in &[false, true] {
+                let key = |i: usize| {
+                    x[i..].iter().map(|&c| if reversed_order { 2 - c } else { c })
+                                 .collect::<Vec<_>>()
+                };
+                let start = (0..x.len()).max_by_key(|&i| key(i)).unwrap();
+                let suffix = &x[start..];
+                let period = (1..suffix.len() + 1)
+                    .find(|&p| (p..suffix.len()).all(|i| suffix[i] == suffix[i - p]))
+                    .unwrap();
+                assert_eq!(maximal_suffix(&x, reversed_order), (start, period), "{:?}", x);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_two_way() {
+    let text = b"abaababaabaababaababaabaababaabaab";
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 12) {
+            let pattern = &text[start..end];
+            let engine = TwoWay::new(pattern);
+            assert_eq!(engine.find(text), ::gs_find(text, pattern), "{:?}", pattern);
+            let starts = (0..text.len() + 1)
+                .filter(|&i| text[i..].starts_with(pattern))
+                .collect::<Vec<_>>();
+            let found = (0..text.len() + 1)
+                .filter_map(|i| engine.find_at(text, i))
+                .collect::<::std::collections::BTreeSet<_>>();
+            assert_eq!(found.into_iter().collect::<Vec<_>>(), starts);
+        }
+    }
+    // The remembered prefix extends past the critical position
+    assert_eq!(TwoWay::new(b"abbbaabbba").find(b"abbbbaabbabbabbaabbbaabbbaabb"), Some(16));
+    assert_eq!(TwoWay::new(b"").find(b""), Some(0));
+    assert_eq!(TwoWay::new(b"").find_at(b"ab", 2), Some(2));
+    assert_eq!(TwoWay::new(b"a").find_at(b"ab", 2), None);
+}
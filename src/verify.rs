@@ -0,0 +1,341 @@
+
+use std::borrow::Borrow;
+use std::fmt;
+
+use haystack::Haystack;
+use {hrp_by, text_has_prefix, Hrp, GS_K};
+
+/// The reason a decomposition is not valid, returned by
+/// `verify_decomposition`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DecompositionError {
+    /// *k* is less than 3, where the decomposition does not always exist
+    InvalidK {
+        /// The *k* that was used
+        k: usize,
+    },
+    /// v is not k-simple: it has a second k-HRP
+    NotSimple {
+        /// The length of u
+        u_len: usize,
+        /// The length of v
+        v_len: usize,
+        /// The first k-HRP of v
+        hrp1: Hrp,
+        /// The second k-HRP of v
+        hrp2: Hrp,
+    },
+}
+
+impl fmt::Display for DecompositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecompositionError::InvalidK { k } => {
+                write!(f, "the decomposition needs k >= 3, but k is {}", k)
+            }
+            DecompositionError::NotSimple { u_len, v_len, hrp1, hrp2 } => {
+                write!(f, "Factorization u, v = {} , {} is not k-simple because \
+                           v's HRP1 is {:?} and HRP2 is {:?}",
+                       u_len, v_len, hrp1, hrp2)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for DecompositionError { }
+
+/// Check that u v is a valid decomposition for the Galil-Seiferas search with
+/// parameter `k`: that v is k-simple, which means that it has at most one
+/// k-HRP.
+///
+/// This checks the definition directly, in **O(m²)** time in the worst case,
+/// so it is meant for testing; the search uses *k* = 3.
+///
+/// ```
+/// use galil_seiferas::{verify_decomposition, DecompositionError};
+///
+/// assert_eq!(verify_decomposition(3, b"a", b"aabaabaabb"), Ok(()));
+/// // aaa has period 1 and aaabaaabaaabaaab has period 4
+/// assert!(matches!(verify_decomposition(3, b"", b"aaabaaabaaabaaab"),
+///                  Err(DecompositionError::NotSimple { .. })));
+/// ```
+pub fn verify_decomposition<T: Eq>(k: usize, u: &[T], v: &[T])
+    -> Result<(), DecompositionError>
+{
+    verify_decomposition_by(k, u.len(), v, &T::eq)
+}
+
+pub(crate) fn verify_decomposition_by<T, V, F>(k: usize, u_len: usize, v: V, equal: &F)
+    -> Result<(), DecompositionError>
+    where V: Haystack + Copy,
+          V::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    if k < 3 {
+        return Err(DecompositionError::InvalidK { k });
+    }
+    match find_k_hrps(k, v, equal) {
+        (Some(hrp1), Some(hrp2)) => {
+            Err(DecompositionError::NotSimple { u_len, v_len: v.len(), hrp1, hrp2 })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Return true if `pattern` occurs in `text` at `pos`, by comparing them
+/// directly.
+///
+/// Use it to re-check a match that was reported by a search, for example one
+/// with a custom comparison function or a new `SearchEngine`. It is false if
+/// the pattern does not fit in the text at `pos`.
+///
+/// ```
+/// use galil_seiferas::{gs_find, verify_match};
+///
+/// let pos = gs_find(b"abracadabra", b"cad").unwrap();
+/// assert!(verify_match(b"abracadabra", b"cad", pos));
+/// assert!(!verify_match(b"abracadabra", b"cad", pos + 1));
+/// ```
+pub fn verify_match<T: Eq>(text: &[T], pattern: &[T], pos: usize) -> bool {
+    verify_match_by(text, pattern, pos, T::eq)
+}
+
+/// Like `verify_match`, using `equal` as the equality comparison function.
+pub fn verify_match_by<T, F>(text: &[T], pattern: &[T], pos: usize, equal: F) -> bool
+    where F: Fn(&T, &T) -> bool,
+{
+    match text.get(pos..) {
+        Some(rest) if rest.len() >= pattern.len() => {
+            rest.iter().zip(pattern).all(|(a, b)| equal(a, b))
+        }
+        _ => false,
+    }
+}
+
+/// Check the match of `pattern` in `text` at `pos` like `verify_match`, in
+/// debug builds only.
+///
+/// **Panics** if the pattern does not occur in the text at `pos`, when debug
+/// assertions are enabled.
+pub fn debug_verify_match<T: Eq>(text: &[T], pattern: &[T], pos: usize) {
+    if cfg!(debug_assertions) && !verify_match(text, pattern, pos) {
+        panic!("debug_verify_match: the pattern (length {}) does not match at {} in the \
+                text (length {})", pattern.len(), pos, text.len());
+    }
+}
+
+/// Return true if `pattern` occurs in `text` with its match ending exactly
+/// at `end`, which is commonly `text.len()` for a suffix check.
+///
+/// The comparison uses the same loop as the search, which compares long
+/// patterns a chunk at a time. It is false if `end` is past the end of the
+/// text or the pattern does not fit before it.
+///
+/// ```
+/// use galil_seiferas::ends_at;
+///
+/// let text = b"archive.tar.gz";
+/// assert!(ends_at(text, b".tar.gz", text.len()));
+/// assert!(ends_at(text, b".tar", 11));
+/// assert!(!ends_at(text, b".tar", text.len()));
+/// ```
+pub fn ends_at<T: Eq>(text: &[T], pattern: &[T], end: usize) -> bool {
+    ends_at_by(text, pattern, end, T::eq)
+}
+
+/// Like `ends_at`, using `equal` as the equality comparison function.
+pub fn ends_at_by<T, F>(text: &[T], pattern: &[T], end: usize, equal: F) -> bool
+    where F: Fn(&T, &T) -> bool,
+{
+    match end.checked_sub(pattern.len()).and_then(|start| text.get(start..end)) {
+        Some(part) => text_has_prefix(part, pattern, &equal),
+        None => false,
+    }
+}
+
+/// Return true if `x` is k-simple: if it has at most one k-HRP.
+///
+/// Each pattern v in the decomposition u v of the search is k-simple with
+/// *k* = 3; such a pattern can be searched with `SimplePattern`. This checks
+/// the definition directly, in **O(m²)** time in the worst case.
+///
+/// **Panics** if `k` is less than 2.
+///
+/// ```
+/// use galil_seiferas::is_k_simple;
+///
+/// assert!(is_k_simple(b"abracadabra", 3));
+/// // aaa has period 1 and aaabaaabaaab has period 4
+/// assert!(!is_k_simple(b"aaabaaabaaab", 3));
+/// assert!(is_k_simple(b"aaabaaabaaab", 4));
+/// ```
+pub fn is_k_simple<T: Eq>(x: &[T], k: usize) -> bool {
+    assert!(k >= 2, "is_k_simple: k must be at least 2, but it is {}", k);
+    find_k_hrps(k, x, &T::eq).1.is_none()
+}
+
+/// Return the period of the shortest cube prefix of `x`: the least *p* > 0
+/// such that `x` starts with z z z, where z is `x[..p]`.
+///
+/// The cube prefix is the first k-HRP of `x` with *k* = 3 ([CR] section 3,
+/// Cube Prefixes); it is found in **O(m)** time, with the same scan as the
+/// decomposition of the search.
+///
+/// ```
+/// use galil_seiferas::cube_prefix;
+///
+/// assert_eq!(cube_prefix(b"abaabaabaab"), Some(3));
+/// assert_eq!(cube_prefix(b"aaab"), Some(1));
+/// assert_eq!(cube_prefix(b"abaabaab"), None);
+/// ```
+pub fn cube_prefix<T: Eq>(x: &[T]) -> Option<usize> {
+    cube_prefix_by(x, T::eq)
+}
+
+/// Like `cube_prefix`, using `equal` as the equality comparison function.
+pub fn cube_prefix_by<T, F>(x: &[T], equal: F) -> Option<usize>
+    where F: Fn(&T, &T) -> bool,
+{
+    // The first HRP of the search is a cube prefix, since its k is 3
+    debug_assert_eq!(GS_K, 3);
+    hrp_by(1, 0, x, None, &equal).0.map(|hrp| hrp.period)
+}
+
+/// Return the first two k-HRPs of `x`, if they exist; `k` must be at least 2.
+fn find_k_hrps<T, P, F>(k: usize, x: P, equal: &F) -> (Option<Hrp>, Option<Hrp>)
+    where P: Haystack + Copy,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    debug_assert!(k >= 2);
+    match find_k_hrp(k, 1, x, equal) {
+        // A period between hrp1.period and hrp1.len - hrp1.period is a
+        // multiple of hrp1.period by the periodicity lemma, so it is not the
+        // period of another HRP.
+        Some(hrp1) => (Some(hrp1), find_k_hrp(k, hrp1.len - hrp1.period, x, equal)),
+        None => (None, None),
+    }
+}
+
+/// Return the k-HRP of `x` with the least period that is at least `period`,
+/// if any, by trying each period.
+pub(crate) fn find_k_hrp<T, P, F>(k: usize, period: usize, x: P, equal: &F) -> Option<Hrp>
+    where P: Haystack,
+          P::Item: Borrow<T>,
+          F: Fn(&T, &T) -> bool,
+{
+    let mut pos = 0;
+    let mut period = period;
+    while pos < x.len() && period < x.len() {
+        while pos + period < x.len() && equal(x.get(pos).borrow(), x.get(pos + period).borrow()) {
+            pos += 1;
+        }
+        if pos + period >= k * period {
+            return Some(Hrp { period, len: pos + period });
+        }
+        pos = 0;
+        period += 1;
+    }
+    None
+}
+
+#[test]
+fn test_is_k_simple() {
+    assert!(is_k_simple::<u8>(b"", 3));
+    assert!(is_k_simple(b"aaaa", 3));
+    assert!(!is_k_simple(b"aabaabaabaab", 2));
+    assert!(is_k_simple(b"aabaabaabaab", 3));
+
+    // Agrees with hrp_by, for all binary strings of length < 12
+    for len in 0..12 {
+        for bits in 0..1u32 << len {
+            let x = (0..len).map(|i| (bits >> i) & 1).collect::<Vec<_>>();
+            let simple = hrp_by(1, 0, &x[..], None, &u32::eq).1.is_none();
+            assert_eq!(is_k_simple(&x, GS_K), simple, "{:?}", x);
+        }
+    }
+}
+
+#[test]
+fn test_cube_prefix() {
+    assert_eq!(cube_prefix::<u8>(b""), None);
+    assert_eq!(cube_prefix(b"aaa"), Some(1));
+    assert_eq!(cube_prefix(b"aabaabaab"), Some(3));
+    assert_eq!(cube_prefix(b"abababa"), Some(2));
+    assert_eq!(cube_prefix(b"ababaab"), None);
+    assert_eq!(cube_prefix_by(b"aAa", |a: &u8, b: &u8| a.eq_ignore_ascii_case(b)), Some(1));
+
+    // The least period with a cube, for all ternary strings of length < 9
+    for len in 0..9 {
+        for n in 0..3u32.pow(len) {
+            let x = (0..len).map(|i| n / 3u32.pow(i) % 3).collect::<Vec<_>>();
+            let least = (1..len as usize / 3 + 1)
+                .find(|&p| (p..3 * p).all(|i| x[i] == x[i - p]));
+            assert_eq!(cube_prefix(&x), least, "{:?}", x);
+        }
+    }
+}
+
+#[test]
+fn test_verify_match() {
+    let text = &b"abaababaab"[..];
+    for pos in 0..text.len() + 2 {
+        for len in 0..text.len() + 1 {
+            let pattern = &text[text.len() - len..];
+            let expected = text.get(pos..).is_some_and(|rest| rest.starts_with(pattern));
+            assert_eq!(verify_match(text, pattern, pos), expected);
+        }
+    }
+    assert!(verify_match_by(b"ABA", b"ba", 1, |a: &u8, b: &u8| a.eq_ignore_ascii_case(b)));
+    debug_verify_match(text, b"abab", 3);
+}
+
+#[test]
+fn test_ends_at() {
+    let text = &b"abaababaab"[..];
+    for end in 0..text.len() + 2 {
+        for len in 0..text.len() + 1 {
+            let pattern = &text[text.len() - len..];
+            let expected = text.get(..end).is_some_and(|rest| rest.ends_with(pattern));
+            assert_eq!(ends_at(text, pattern, end), expected);
+        }
+    }
+    // Long patterns are compared in chunks
+    let text = "abcdefghijklmnopqrstuvwxyz".repeat(3);
+    let text = text.as_bytes();
+    assert!(ends_at(text, &text[26..], text.len()));
+    assert!(ends_at(text, &text[..52], 52));
+    assert!(!ends_at(text, &text[..52], 53));
+    assert!(ends_at_by(b"ABA", b"ab", 2, |a: &u8, b: &u8| a.eq_ignore_ascii_case(b)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "does not match at 2")]
+fn test_debug_verify_match() {
+    debug_verify_match(b"abaab", b"ab", 2);
+}
+
+#[test]
+fn test_verify_decomposition() {
+    use decompose;
+
+    assert_eq!(verify_decomposition(2, b"", b"ab"), Err(DecompositionError::InvalidK { k: 2 }));
+    let error = verify_decomposition(3, b"", b"aaabaaabaaabaa").unwrap_err();
+    assert_eq!(error, DecompositionError::NotSimple {
+        u_len: 0,
+        v_len: 14,
+        hrp1: Hrp { period: 1, len: 3 },
+        hrp2: Hrp { period: 4, len: 14 },
+    });
+    // aaaa is not a second HRP
+    assert_eq!(verify_decomposition(3, b"", b"aaaaaaaaaaaaaaaa"), Ok(()));
+
+    for pattern in &["aaab".repeat(4) + "bbbb", "abaababaab".repeat(5), "banana".to_string()] {
+        let (u, v, _) = decompose(pattern.as_bytes());
+        assert_eq!(verify_decomposition(3, u, v), Ok(()));
+    }
+    assert!(verify_decomposition(3, b"", ("aaab".repeat(4) + "bbbb").as_bytes()).is_err());
+}
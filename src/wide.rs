@@ -0,0 +1,152 @@
+use std::cmp::max;
+use std::convert::TryInto;
+use std::mem::{size_of, size_of_val};
+use std::slice;
+
+use swar::FastCompare;
+
+/// An element type that is searched by comparing its bytes: `u16` or `u32`.
+pub(crate) trait Wide: Copy + Eq { }
+
+impl Wide for u16 { }
+impl Wide for u32 { }
+
+/// View a slice of wide elements as a slice of bytes.
+fn as_bytes<T: Wide>(elements: &[T]) -> &[u8] {
+    // Safety: u16 and u32 have no padding, and each byte is initialized.
+    unsafe { slice::from_raw_parts(elements.as_ptr() as *const u8, size_of_val(elements)) }
+}
+
+/// The number of bytes compared at a time with SSE2
+#[cfg(target_arch = "x86_64")]
+const VECTOR: usize = 16;
+
+/// The number of bytes compared at a time without SIMD
+const WORD: usize = 8;
+
+/// Return the length of the common prefix of `a` and `b`, in bytes.
+///
+/// The slices are compared 16 bytes at a time with SSE2 on x86-64, and the
+/// first differing byte is found from the mask of the equal bytes.
+#[cfg(target_arch = "x86_64")]
+fn common_prefix_bytes(a: &[u8], b: &[u8]) -> usize {
+    use std::arch::x86_64::{_mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, __m128i};
+
+    debug_assert!(a.len() == b.len());
+    let mut i = 0;
+    // SSE2 is always available on x86-64.
+    unsafe {
+        while i + VECTOR <= a.len() {
+            let x = _mm_loadu_si128(a.as_ptr().add(i) as *const __m128i);
+            let y = _mm_loadu_si128(b.as_ptr().add(i) as *const __m128i);
+            let equal = _mm_movemask_epi8(_mm_cmpeq_epi8(x, y)) as u32;
+            if equal != 0xffff {
+                return i + (!equal).trailing_zeros() as usize;
+            }
+            i += VECTOR;
+        }
+    }
+    common_prefix_words(i, a, b)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn common_prefix_bytes(a: &[u8], b: &[u8]) -> usize {
+    common_prefix_words(0, a, b)
+}
+
+/// Return the length of the common prefix of `a` and `b`, in bytes, given
+/// that it is at least `from`; the bytes are compared a word at a time.
+fn common_prefix_words(from: usize, a: &[u8], b: &[u8]) -> usize {
+    debug_assert!(a.len() == b.len());
+    let load = |bytes: &[u8], i: usize| {
+        u64::from_le_bytes(get!(bytes, i..i + WORD).try_into().unwrap())
+    };
+    let mut i = from;
+    while i + WORD <= a.len() {
+        let diff = load(a, i) ^ load(b, i);
+        if diff != 0 {
+            return i + diff.trailing_zeros() as usize / 8;
+        }
+        i += WORD;
+    }
+    while i < a.len() && get!(a, i) == get!(b, i) {
+        i += 1;
+    }
+    i
+}
+
+/// Find the greatest shared prefix, starting at from, of text and pattern;
+/// like `longest_common_prefix_from`, but for wide elements.
+///
+/// The elements are equal if and only if their bytes are, so the common
+/// prefix of the elements is found from the common prefix of their bytes;
+/// the first differing byte is in the first differing element, in either
+/// byte order.
+#[inline(always)]
+fn common_prefix_from<T: Wide>(from: usize, text: &[T], pattern: &[T]) -> usize {
+    debug_assert!(pattern.len() <= text.len());
+    debug_assert!(from <= pattern.len());
+    let m = pattern.len();
+    // Most comparisons end at the first element, in a predictable branch
+    if from == 0 && (m == 0 || get!(text, 0) != get!(pattern, 0)) {
+        return 0;
+    }
+    let from = max(from, 1);
+    let bytes = common_prefix_bytes(as_bytes(get!(text, from..m)),
+                                    as_bytes(get!(pattern, from..m)));
+    from + bytes / size_of::<T>()
+}
+
+impl<T: Wide> FastCompare for T {
+    type Head = ();
+
+    fn head(_pattern: &[T]) { }
+
+    #[inline(always)]
+    fn common_prefix_from(from: usize, text: &[T], pattern: &[T], _head: ()) -> usize {
+        common_prefix_from(from, text, pattern)
+    }
+}
+
+#[test]
+fn test_common_prefix_from() {
+    let text = (0x1ff00..0x1ff40u32).collect::<Vec<_>>();
+    let text16 = text.iter().map(|&x| x as u16).collect::<Vec<_>>();
+    for len in 0..text.len() + 1 {
+        let mut pattern = text[..len].to_vec();
+        assert_eq!(common_prefix_from(0, &text, &pattern), len);
+        for i in 0..len {
+            // Differ in the high byte only
+            pattern[i] ^= 0x100;
+            let pattern16 = pattern.iter().map(|&x| x as u16).collect::<Vec<_>>();
+            for from in 0..i + 1 {
+                assert_eq!(common_prefix_from(from, &text, &pattern), i);
+                assert_eq!(common_prefix_from(from, &text16, &pattern16), i);
+            }
+            assert_eq!(common_prefix_from(i + 1, &text, &pattern), len);
+            assert_eq!(common_prefix_from(i + 1, &text16, &pattern16), len);
+            pattern[i] = text[i];
+        }
+    }
+}
+
+#[test]
+fn test_find_wide() {
+    use swar::find_fast;
+
+    let text = "abaababaabaababaababaabaababaabaab.abaababaabaababaababaabaababaabaab";
+    let text = text.bytes().map(|b| 0x100 * b as u32 + 1).collect::<Vec<_>>();
+    let text16 = text.iter().map(|&x| x as u16).collect::<Vec<_>>();
+    let brute_force = |text: &[u32], pattern: &[u32]| {
+        (0..text.len() + 1).find(|&i| text[i..].starts_with(pattern))
+    };
+    for start in 0..text.len() {
+        for end in start..text.len().min(start + 30) {
+            for skip in 0..3 {
+                let expected = brute_force(&text[skip..], &text[start..end]);
+                assert_eq!(find_fast(&text[skip..], &text[start..end]), expected);
+                assert_eq!(find_fast(&text16[skip..], &text16[start..end]), expected);
+            }
+        }
+    }
+}
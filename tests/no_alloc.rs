@@ -0,0 +1,62 @@
+//! Check that the searches perform no heap allocation.
+//!
+//! The allocator of this test binary counts the allocations inside
+//! `assert_no_alloc`; each test first checks that an allocation inside it is
+//! detected, then that the searches make none. The tests share the counter,
+//! so they run in one test function.
+
+extern crate assert_no_alloc;
+extern crate galil_seiferas;
+
+use std::hint::black_box;
+
+use assert_no_alloc::{assert_no_alloc, reset_violation_count, violation_count, AllocDisabler};
+
+use galil_seiferas::{gs_find, gs_find_by, gs_find_bytes, gs_find_plain, gs_rfind, Finder,
+                     GrowingBufferFinder};
+
+#[global_allocator]
+static ALLOCATOR: AllocDisabler = AllocDisabler;
+
+/// Return the number of allocations that `f` performs.
+fn allocations<F: FnOnce()>(f: F) -> u32 {
+    reset_violation_count();
+    assert_no_alloc(f);
+    violation_count()
+}
+
+#[test]
+fn no_alloc() {
+    // The guard detects an allocation
+    assert!(allocations(|| drop(black_box(vec![0u8; 16]))) > 0);
+
+    let mut text = b"abaababaabaababaababaabaababaabaab".repeat(1000);
+    text.extend_from_slice(b"aaabaaabaaabaabbbb");
+    let wide = text.iter().map(|&b| b as u16).collect::<Vec<_>>();
+    // the last patterns have a nonempty u and an HRP
+    let patterns = [&b"abaababaab"[..], b"", b"aabb", b"aaabaaabaaabaabbbb", b"aaaaaaaaaaab"];
+    let wide_patterns = patterns.iter().map(|p| p.iter().map(|&b| b as u16).collect::<Vec<_>>())
+                                       .collect::<Vec<_>>();
+
+    let count = allocations(|| {
+        for (pattern, wide_pattern) in patterns.iter().zip(&wide_patterns) {
+            let pattern = *pattern;
+            let first = gs_find(&text, pattern);
+            assert_eq!(gs_find_by(&text, pattern, |a, b| a == b), first);
+            assert_eq!(gs_find_bytes(&text, pattern), first);
+            assert_eq!(gs_find_plain(&wide, wide_pattern), first);
+            assert_eq!(gs_find(&wide, &wide_pattern[..]), first);
+            gs_rfind(&text, pattern);
+
+            let finder = Finder::new(pattern);
+            assert_eq!(finder.find(&text).map(|m| m.start()), first);
+            finder.find_iter(&text).count();
+
+            let mut growing = GrowingBufferFinder::new(pattern);
+            for end in (0..text.len()).step_by(1000) {
+                while growing.poll(&text[..end]).is_some() { }
+            }
+        }
+    });
+    assert_eq!(count, 0, "the searches allocated");
+}
@@ -126,40 +126,6 @@ fn test_fib_word() {
     assert_eq!(&*FibWord::new(4), "abaababa");
 }
 
-// Write a fuzz dictionary -- need to use --ignored to run these
-quickcheck! {
-    #[ignore]
-    fn generate_dict_fibwords(n: usize) -> () {
-        let word = FibWord::new(n % 10);
-        writeln_to_file(format!("{:?}", word.as_str()), "dict-fibwords");
-    }
-
-    #[ignore]
-    fn generate_dict_lsys(words: Vec<usize>, repeats: Vec<usize>) -> () {
-        let mut s = String::new();
-        for (w, repeat) in words.into_iter().zip(repeats) {
-            for _ in 0..((repeat % 5) + 1) {
-                s.push_str(&LSys1::new(w % 6));
-            }
-        }
-
-        if !s.is_empty() {
-            writeln_to_file(format!("\"{}\"", s), "dict-lsys");
-        }
-    }
-}
-
-fn writeln_to_file(s: String, name: &str) {
-    use std::io::Write;
-    use std::fs::OpenOptions;
-    let mut f = OpenOptions::new()
-        .write(true)
-        .append(true)
-        .create(true)
-        .open(name).unwrap();
-    writeln!(f, "{}", s).unwrap();
-}
-
 impl Arbitrary for FibWord {
     fn arbitrary<G: qc::Gen>(g: &mut G) -> Self {
         let n = u8::arbitrary(g) % 20;
@@ -175,47 +141,6 @@ impl Arbitrary for FibWord {
     }
 }
 
-macro_attr! {
-    #[derive(Clone, Debug, NewtypeDeref!, NewtypeDerefMut!)]
-    struct LSys1(String);
-}
-
-// L-System where we rewrite a string in generations
-/// Starting state
-const LSTART: &str = "0";
-
-/// Rewrite rules
-const LRULES: &[(u8, &str)] = &[
-    (b'0', "100"),
-    (b'1', "11"),
-];
-
-impl LSys1 {
-    fn new(n: usize) -> Self {
-        let mut f = LSys1(String::from(LSTART));
-        let mut g = LSys1(String::new());
-        for _ in 0..n {
-            f.next_into(&mut g);
-            std::mem::swap(&mut f, &mut g);
-        }
-        f
-    }
-
-    fn next_into(&self, s: &mut String) {
-        s.clear();
-        'bytes: for byte in self.bytes() {
-            for &(rule, replace) in LRULES {
-                if byte == rule {
-                    s.push_str(replace);
-                    continue 'bytes;
-                }
-            }
-            // else it is a constant
-            s.push(byte as char);
-        }
-    }
-}
-
 pub fn contains(hay: &str, n: &str) -> bool {
     gs_find(hay.as_bytes(), n.as_bytes()).is_some()
 }